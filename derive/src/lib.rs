@@ -143,17 +143,32 @@
 //!
 //! 1. Terminals
 //!
-//!     | Terminal   | Usage                                                          |
-//!     |------------|----------------------------------------------------------------|
-//!     | `"a"`      | matches the exact string `"a"`                                 |
-//!     | `^"a"`     | matches the exact string `"a"` case insensitively (ASCII only) |
-//!     | `'a'..'z'` | matches one character between `'a'` and `'z'`                  |
-//!     | `a`        | matches rule `a`                                               |
+//!     | Terminal             | Usage                                                          |
+//!     |----------------------|----------------------------------------------------------------|
+//!     | `"a"`                | matches the exact string `"a"`                                 |
+//!     | `^"a"`               | matches the exact string `"a"` case insensitively (ASCII only) |
+//!     | `^u"a"`              | matches the exact string `"a"` under Unicode case folding      |
+//!     | `'a'..'z'`           | matches one character between `'a'` and `'z'`                  |
+//!     | `b"\xFF"`            | matches the exact bytes `0xFF`                                 |
+//!     | `b'\x00'..b'\x7F'`   | matches one byte between `0x00` and `0x7F`                     |
+//!     | `a`                  | matches rule `a`                                               |
 //!
 //! Strings and characters follow
 //! [Rust's escape mechanisms](https://doc.rust-lang.org/reference/tokens.html#byte-escapes), while
 //! identifiers can contain alpha-numeric characters and underscores (`_`), as long as they do not
-//! start with a digit.
+//! start with a digit. Byte strings and bytes may only contain ASCII characters and `\x` escapes
+//! of any value.
+//!
+//! Insensitive strings only fold ASCII letters by default. Parsing with
+//! `ParserConfig::fold_unicode_case` matches them under full Unicode case folding instead, so
+//! that `^"STRASSE"` also matches `straße`. A single string can be matched that way whatever the
+//! configuration by writing it `^u"STRASSE"`. Folding is not locale-aware: `İ` does not match
+//! `i`, nor `I` match `ı`, as they would in Turkish.
+//!
+//! Byte literals are meant for inputs that need not be valid UTF-8, parsed with
+//! `Parser::parse_bytes`, where `ANY` matches a single byte and strings match their UTF-8
+//! encoding. On `&str` inputs they match the same bytes, provided they end on a character
+//! boundary.
 //!
 //! 2. Non-terminals
 //!
@@ -222,6 +237,20 @@
 //! a = { b ~ WHITESPACE* ~ (COMMENT ~ WHITESPACE*)* ~ c }
 //! ```
 //!
+//! ## Left recursion
+//!
+//! Rules may call themselves before consuming any input, as long as they have another way to
+//! match. They are grown from a seed: the recursive call first fails, and the rule is then parsed
+//! again with it matching what the previous attempt did, for as long as more input is matched.
+//! Left-recursive rules thus produce left-associative pairs, so that this grammar:
+//!
+//! ```ignore
+//! sum = { sum ~ "+" ~ number | number }
+//! ```
+//!
+//! parses `1+2+3` as `sum(sum(sum(1) + 2) + 3)`. Disabling the default `left-recursion` feature
+//! rejects left-recursive grammars instead.
+//!
 //! ## `PUSH`, `POP`, `DROP`, and `PEEK`
 //!
 //! `PUSH(e)` simply pushes the captured string of the expression `e` down a stack. This stack can
@@ -273,6 +302,74 @@
 //! All rules defined or used in the grammar populate a generated `enum` called `Rule`. This
 //! implements `pest`'s `RuleType` and can be used throughout the API.
 //!
+//! ## `Visitor` and `Fold`
+//!
+//! Adding the `#[visitor]` attribute also generates two traits with one method per rule that
+//! produces pairs, i.e. every rule but the silent ones:
+//!
+//! * `Visitor` has a `visit_<rule>` method per rule, which by default walks the inner pairs.
+//!   `visit` dispatches a pair to the method of its rule.
+//! * `Fold` has a `fold_<rule>` method per rule returning `Self::Output`, which has no default,
+//!   so a rule added to the grammar does not compile until it is handled. `fold` dispatches a
+//!   pair to the method of its rule.
+//!
+//! ```ignore
+//! #[derive(Parser)]
+//! #[grammar = "calc.pest"]
+//! #[visitor]
+//! struct CalcParser;
+//!
+//! struct Eval;
+//!
+//! impl Fold for Eval {
+//!     type Output = i64;
+//!
+//!     fn fold_number(&mut self, pair: Pair<Rule>) -> i64 {
+//!         pair.as_str().parse().unwrap()
+//!     }
+//!
+//!     fn fold_sum(&mut self, pair: Pair<Rule>) -> i64 {
+//!         pair.into_inner().map(|pair| self.fold(pair)).sum()
+//!     }
+//! }
+//! ```
+//!
+//! ## `FromPairs`
+//!
+//! `#[derive(FromPairs)]` implements `pest::from_pairs::FromPairs`, which converts pairs into
+//! typed syntax trees:
+//!
+//! * A struct, or an enum variant, with `#[pest_ast(rule(Rule::name))]` converts a pair of that
+//!   rule. Its fields are converted in order from the inner pairs of the pair, which must all be
+//!   used up, unless fields are marked with `#[pest_ast(span)]` to take the pair's `Span` or
+//!   `#[pest_ast(text)]` to parse the pair's text with `FromStr`.
+//! * `Option<T>` fields take a `T` if the next pair starts one, `Vec<T>` fields take as many as
+//!   there are, and `Box<T>` fields take a `T`.
+//! * An enum converts into the first variant that the next pair starts. Variants without a rule
+//!   must wrap a single type. With a rule, the enum converts a pair of that rule and picks the
+//!   variant from its inner pairs.
+//!
+//! Pairs that are missing, left over, or of the wrong rule are reported as an `Error` at their
+//! position.
+//!
+//! ```ignore
+//! #[derive(FromPairs)]
+//! #[pest_ast(rule(Rule::fn_decl))]
+//! struct FnDecl {
+//!     name: Ident,
+//!     params: Vec<Param>,
+//!     ret: Option<Ty>,
+//!     #[pest_ast(span)]
+//!     span: Span,
+//! }
+//!
+//! #[derive(FromPairs)]
+//! #[pest_ast(rule(Rule::ident))]
+//! struct Ident(#[pest_ast(text)] String);
+//!
+//! let fn_decl = FnDecl::from_pair(pair)?;
+//! ```
+//!
 //! ## `Built-in rules`
 //!
 //! Pest also comes with a number of built-in rules for convenience. They are:
@@ -295,7 +392,12 @@ extern crate proc_macro;
 
 use proc_macro::TokenStream;
 
-#[proc_macro_derive(Parser, attributes(grammar, grammar_inline))]
+#[proc_macro_derive(Parser, attributes(grammar, grammar_inline, visitor))]
 pub fn derive_parser(input: TokenStream) -> TokenStream {
     pest_generator::derive_parser(input.into(), true).into()
 }
+
+#[proc_macro_derive(FromPairs, attributes(pest_ast))]
+pub fn derive_from_pairs(input: TokenStream) -> TokenStream {
+    pest_generator::derive_from_pairs(input.into()).into()
+}
@@ -0,0 +1,145 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+extern crate pest;
+#[macro_use]
+extern crate fuel_pest_derive as pest_derive;
+
+use std::sync::Arc;
+
+use pest::error::{ErrorVariant, InputLocation, LineColLocation};
+use pest::Parser;
+
+#[derive(Parser)]
+#[grammar = "../tests/bytes.pest"]
+struct BytesParser;
+
+#[test]
+fn packet() {
+    let input: Arc<[u8]> = Arc::from(&b"\xCA\xFE\x01\xFF\x80\x00\x02hi\x00"[..]);
+    let packet = BytesParser::parse_bytes(Rule::packet, input)
+        .unwrap()
+        .next()
+        .unwrap();
+
+    let records: Vec<_> = packet
+        .into_inner()
+        .filter(|pair| pair.as_rule() == Rule::record)
+        .map(|record| {
+            let span = record.as_span();
+            let fields: Vec<_> = record
+                .into_inner()
+                .map(|pair| pair.as_bytes().to_vec())
+                .collect();
+
+            (span.start(), span.end(), fields)
+        })
+        .collect();
+
+    assert_eq!(
+        records,
+        vec![
+            (2, 6, vec![b"\x01".to_vec(), b"\xFF\x80\x00".to_vec()]),
+            (6, 10, vec![b"\x02".to_vec(), b"hi\x00".to_vec()]),
+        ]
+    );
+}
+
+#[test]
+fn packet_error() {
+    let input: Arc<[u8]> = Arc::from(&b"\xCA\xFE\x01\xFF\x80"[..]);
+    let error = BytesParser::parse_bytes(Rule::packet, input).unwrap_err();
+
+    assert_eq!(
+        error.variant,
+        ErrorVariant::ParsingError {
+            positives: vec![Rule::text],
+            negatives: vec![],
+        }
+    );
+    assert_eq!(error.location, InputLocation::Pos(3));
+}
+
+#[test]
+fn literal_as_utf8() {
+    let input: Arc<[u8]> = Arc::from(&b"\xC3\xA9\xFF"[..]);
+    let pair = BytesParser::parse_bytes(Rule::accent, input)
+        .unwrap()
+        .next()
+        .unwrap();
+
+    assert_eq!(pair.as_bytes(), b"\xC3\xA9\xFF");
+}
+
+#[test]
+fn range_as_utf8() {
+    let input: Arc<[u8]> = Arc::from(&b"\xC3\xA9\xFF"[..]);
+    let pair = BytesParser::parse_bytes(Rule::accented, input)
+        .unwrap()
+        .next()
+        .unwrap();
+
+    assert_eq!(pair.as_bytes(), b"\xC3\xA9\xFF");
+
+    // 0xE9 is `é` in Latin-1, but not in UTF-8.
+    let input: Arc<[u8]> = Arc::from(&b"\xE9\xFF"[..]);
+    assert!(BytesParser::parse_bytes(Rule::accented, input).is_err());
+}
+
+#[test]
+fn error_line_as_utf8() {
+    let input: Arc<[u8]> = Arc::from(&b"\xC3\xA9\xFF\xC3\xA8\xFF"[..]);
+    let error = BytesParser::parse_bytes(Rule::accents, input).unwrap_err();
+
+    assert_eq!(error.location, InputLocation::Pos(3));
+    assert_eq!(error.line_col, LineColLocation::Pos((1, 3)));
+    assert_eq!(
+        format!("{}", error),
+        [
+            " --> 1:3",
+            "  |",
+            "1 | \u{e9}\u{fffd}\u{e8}\u{fffd}",
+            "  |   ^---",
+            "  |",
+            "  = expected accent",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn error_line_keeps_whitespace() {
+    let input: Arc<[u8]> = Arc::from(&b"\xC3\xA9\xFF\r\n"[..]);
+    let error = BytesParser::parse_bytes(Rule::accents, input).unwrap_err();
+
+    assert_eq!(error.line_col, LineColLocation::Pos((1, 3)));
+    assert_eq!(
+        format!("{}", error),
+        [
+            " --> 1:3",
+            "  |",
+            "1 | \u{e9}\u{fffd}␍␊",
+            "  |   ^---",
+            "  |",
+            "  = expected accent",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn bytes_in_text() {
+    let pair = BytesParser::parse(Rule::ascii, Arc::from("a7"))
+        .unwrap()
+        .next()
+        .unwrap();
+
+    assert_eq!(pair.as_str(), "a7");
+    assert!(BytesParser::parse(Rule::ascii, Arc::from("ab")).is_err());
+}
@@ -0,0 +1,107 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+extern crate pest;
+#[macro_use]
+extern crate fuel_pest_derive as pest_derive;
+
+use std::sync::Arc;
+
+use pest::{Parser, ParserConfig};
+
+#[derive(Parser)]
+#[grammar = "../tests/case_folding.pest"]
+struct CaseFoldingParser;
+
+fn words(input: &str, config: ParserConfig<Rule>) -> Option<Vec<String>> {
+    let pairs = CaseFoldingParser::parse_with_config(Rule::greeting, Arc::from(input), config);
+
+    pairs.ok().map(|pairs| {
+        pairs
+            .flatten()
+            .filter(|pair| pair.as_rule() == Rule::word)
+            .map(|pair| pair.as_str().to_owned())
+            .collect()
+    })
+}
+
+#[test]
+fn ascii_by_default() {
+    assert_eq!(
+        words("GRüßE,Straße", ParserConfig::new()),
+        Some(vec!["GRüßE".to_owned(), "Straße".to_owned()])
+    );
+    assert_eq!(words("GRÜSSE", ParserConfig::new()), None);
+    assert_eq!(words("ŞEKER", ParserConfig::new()), None);
+}
+
+#[test]
+fn unicode_folding() {
+    let config = || ParserConfig::new().fold_unicode_case();
+
+    assert_eq!(
+        words("GRÜSSE,STRASSE,ŞEKER", config()),
+        Some(vec![
+            "GRÜSSE".to_owned(),
+            "STRASSE".to_owned(),
+            "ŞEKER".to_owned()
+        ])
+    );
+    assert_eq!(
+        words("grüẞe,strasse", config()),
+        Some(vec!["grüẞe".to_owned(), "strasse".to_owned()])
+    );
+    assert_eq!(words("ısı", config()), Some(vec!["ısı".to_owned()]));
+    assert_eq!(words("ISI", config()), None);
+    assert_eq!(words("STRAS", config()), None);
+}
+
+#[test]
+fn unicode_folding_error() {
+    let error = CaseFoldingParser::parse_with_config(
+        Rule::greeting,
+        Arc::from("straße,GRÜSS"),
+        ParserConfig::new().fold_unicode_case(),
+    )
+    .unwrap_err();
+
+    assert_eq!(error.line_col, pest::error::LineColLocation::Pos((1, 8)));
+}
+
+fn places(input: &str) -> Option<Vec<String>> {
+    let pairs = CaseFoldingParser::parse(Rule::places, Arc::from(input));
+
+    pairs.ok().map(|pairs| {
+        pairs
+            .flatten()
+            .filter(|pair| pair.as_rule() == Rule::place)
+            .map(|pair| pair.as_str().to_owned())
+            .collect()
+    })
+}
+
+#[test]
+fn unicode_literals() {
+    assert_eq!(
+        places("STRASSE,Istanbul"),
+        Some(vec!["STRASSE".to_owned(), "Istanbul".to_owned()])
+    );
+    assert_eq!(places("STRAS"), None);
+}
+
+#[test]
+fn unicode_literals_not_turkic() {
+    assert_eq!(places("İSTANBUL"), None);
+    assert_eq!(places("izmir"), None);
+    assert_eq!(places("IZMIR"), None);
+    assert_eq!(
+        places("i\u{307}zmir"),
+        Some(vec!["i\u{307}zmir".to_owned()])
+    );
+}
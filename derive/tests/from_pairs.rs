@@ -0,0 +1,200 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+extern crate pest;
+#[macro_use]
+extern crate fuel_pest_derive as pest_derive;
+
+use std::sync::Arc;
+
+use pest::error::{Error, InputLocation};
+use pest::from_pairs::FromPairs;
+use pest::{Parser, Span};
+
+#[derive(Parser)]
+#[grammar = "../tests/from_pairs.pest"]
+struct FnParser;
+
+#[derive(Debug, FromPairs)]
+#[pest_ast(rule(Rule::file))]
+struct File {
+    fns: Vec<FnDecl>,
+    _eoi: Eoi,
+}
+
+#[derive(Debug, FromPairs)]
+#[pest_ast(rule(Rule::EOI))]
+struct Eoi;
+
+#[derive(Debug, FromPairs)]
+#[pest_ast(rule(Rule::fn_decl))]
+struct FnDecl {
+    name: Ident,
+    params: Vec<Param>,
+    ret: Option<Ty>,
+    body: Block,
+    #[pest_ast(span)]
+    span: Span,
+}
+
+#[derive(Debug, FromPairs)]
+#[pest_ast(rule(Rule::param))]
+struct Param {
+    name: Ident,
+    ty: Ty,
+}
+
+#[derive(Debug, FromPairs)]
+#[pest_ast(rule(Rule::ty))]
+struct Ty(Ident);
+
+#[derive(Debug, FromPairs, PartialEq)]
+#[pest_ast(rule(Rule::ident))]
+struct Ident {
+    #[pest_ast(text)]
+    name: String,
+}
+
+#[derive(Debug, FromPairs)]
+#[pest_ast(rule(Rule::block))]
+struct Block {
+    stmts: Vec<Stmt>,
+}
+
+#[derive(Debug, FromPairs)]
+#[pest_ast(rule(Rule::stmt))]
+struct Stmt {
+    first: Expr,
+    rest: Vec<Operand>,
+}
+
+#[derive(Debug, FromPairs)]
+#[pest_ast(rule(Rule::operand))]
+struct Operand {
+    op: Op,
+    expr: Expr,
+}
+
+#[derive(Debug, FromPairs, PartialEq)]
+#[pest_ast(rule(Rule::op))]
+enum Op {
+    #[pest_ast(rule(Rule::add))]
+    Add,
+    #[pest_ast(rule(Rule::sub))]
+    Sub,
+}
+
+#[derive(Debug, FromPairs, PartialEq)]
+enum Expr {
+    Int(Int),
+    Call(Call),
+    Var(Ident),
+}
+
+#[derive(Debug, FromPairs, PartialEq)]
+#[pest_ast(rule(Rule::int))]
+struct Int(#[pest_ast(text)] u32);
+
+#[derive(Debug, FromPairs, PartialEq)]
+#[pest_ast(rule(Rule::call))]
+struct Call {
+    callee: Ident,
+}
+
+fn convert<T: FromPairs<Rule>>(rule: Rule, input: &str) -> Result<T, Error<Rule>> {
+    let pair = FnParser::parse(rule, Arc::from(input))
+        .unwrap()
+        .next()
+        .unwrap();
+
+    T::from_pair(pair)
+}
+
+fn ident(name: &str) -> Ident {
+    Ident {
+        name: name.to_owned(),
+    }
+}
+
+#[test]
+fn file() {
+    let input = "fn main() { f() + 1 - x; }\nfn g(a: int, b: bool) -> int { 2; }";
+    let file: File = convert(Rule::file, input).unwrap();
+
+    assert_eq!(file.fns.len(), 2);
+
+    let main = &file.fns[0];
+    assert_eq!(main.name, ident("main"));
+    assert!(main.params.is_empty());
+    assert!(main.ret.is_none());
+    assert_eq!(main.span.as_str(), "fn main() { f() + 1 - x; }");
+
+    let stmt = &main.body.stmts[0];
+    assert_eq!(stmt.first, Expr::Call(Call { callee: ident("f") }));
+    assert_eq!(stmt.rest[0].op, Op::Add);
+    assert_eq!(stmt.rest[0].expr, Expr::Int(Int(1)));
+    assert_eq!(stmt.rest[1].op, Op::Sub);
+    assert_eq!(stmt.rest[1].expr, Expr::Var(ident("x")));
+
+    let g = &file.fns[1];
+    assert_eq!(g.params.len(), 2);
+    assert_eq!(g.params[1].name, ident("b"));
+    assert_eq!(g.params[1].ty.0, ident("bool"));
+    assert!(g.ret.is_some());
+}
+
+#[allow(dead_code)]
+#[derive(Debug, FromPairs)]
+#[pest_ast(rule(Rule::fn_decl))]
+struct NoParams {
+    name: Ident,
+    body: Block,
+}
+
+#[test]
+fn unexpected_pair() {
+    let error = convert::<NoParams>(Rule::fn_decl, "fn f(a: b) {}").unwrap_err();
+
+    assert_eq!(error.variant.message(), "unexpected param; expected block");
+    assert_eq!(error.location, InputLocation::Span((5, 9)));
+}
+
+#[allow(dead_code)]
+#[derive(Debug, FromPairs)]
+#[pest_ast(rule(Rule::call))]
+struct TwoIdents(Ident, Ident);
+
+#[test]
+fn missing_pair() {
+    let error = convert::<TwoIdents>(Rule::call, "f()").unwrap_err();
+
+    assert_eq!(error.variant.message(), "expected ident");
+    assert_eq!(error.location, InputLocation::Pos(3));
+}
+
+#[test]
+fn text_parse_failure() {
+    let error = convert::<Int>(Rule::int, "99999999999").unwrap_err();
+
+    assert_eq!(
+        error.variant.message(),
+        "number too large to fit in target type"
+    );
+    assert_eq!(error.location, InputLocation::Span((0, 11)));
+}
+
+#[test]
+fn no_variant() {
+    let error = convert::<Expr>(Rule::param, "a: b").unwrap_err();
+
+    assert_eq!(
+        error.variant.message(),
+        "unexpected param; expected int, call, or ident"
+    );
+}
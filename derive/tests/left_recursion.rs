@@ -0,0 +1,101 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+#![cfg(feature = "left-recursion")]
+
+extern crate pest;
+#[macro_use]
+extern crate fuel_pest_derive as pest_derive;
+
+use std::sync::Arc;
+
+use pest::iterators::Pair;
+use pest::{Parser, ParserConfig};
+
+#[derive(Parser)]
+#[grammar = "../tests/left_recursion.pest"]
+struct LeftRecursionParser;
+
+// Renders the pairs of `rule` as parenthesized groups.
+fn tree(pair: Pair<Rule>) -> String {
+    let inner: Vec<_> = pair.clone().into_inner().map(tree).collect();
+
+    if inner.is_empty() {
+        pair.as_str().to_owned()
+    } else {
+        format!("({})", inner.join(" "))
+    }
+}
+
+fn parse(rule: Rule, input: &str, config: ParserConfig<Rule>) -> Option<String> {
+    LeftRecursionParser::parse_with_config(rule, Arc::from(input), config)
+        .ok()
+        .map(|mut pairs| tree(pairs.next().unwrap()))
+}
+
+#[test]
+fn left_associative() {
+    assert_eq!(
+        parse(Rule::calc, "1 - 2 + 3", ParserConfig::new()).unwrap(),
+        "(((((1)) (2)) (3)) )"
+    );
+    assert_eq!(
+        parse(Rule::calc, "1 - 2 * 3 * 4", ParserConfig::new()).unwrap(),
+        "((((1)) (((2) 3) 4)) )"
+    );
+    assert_eq!(
+        parse(Rule::calc, "(1 - 2) * 3", ParserConfig::new()).unwrap(),
+        "(((((((1)) (2))) 3)) )"
+    );
+}
+
+#[test]
+fn failure() {
+    let error = LeftRecursionParser::parse(Rule::calc, Arc::from("1 - 2 -")).unwrap_err();
+
+    assert_eq!(error.line_col, pest::error::LineColLocation::Pos((1, 8)));
+    assert_eq!(parse(Rule::calc, "", ParserConfig::new()), None);
+}
+
+#[test]
+fn mutual() {
+    assert_eq!(
+        parse(Rule::chain, "a.b().c", ParserConfig::new()).unwrap(),
+        "((((((((a) b)))) c)) )"
+    );
+    assert_eq!(
+        parse(Rule::chain, "a()()", ParserConfig::new()).unwrap(),
+        "((((((a))))) )"
+    );
+    assert_eq!(parse(Rule::chain, "a.()", ParserConfig::new()), None);
+}
+
+#[test]
+fn silent() {
+    assert_eq!(
+        parse(Rule::list, "1, 2, 3", ParserConfig::new()).unwrap(),
+        "(1 2 3 )"
+    );
+}
+
+#[test]
+fn memoized() {
+    let inputs = [
+        (Rule::calc, "1 - 2 * (3 + 4) - 5"),
+        (Rule::chain, "a.b().c().d"),
+        (Rule::list, "1, 2, 3"),
+    ];
+
+    for &(rule, input) in &inputs {
+        assert_eq!(
+            parse(rule, input, ParserConfig::new().memoize_all()),
+            parse(rule, input, ParserConfig::new())
+        );
+    }
+}
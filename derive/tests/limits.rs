@@ -0,0 +1,53 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+extern crate pest;
+#[macro_use]
+extern crate fuel_pest_derive as pest_derive;
+
+use std::sync::Arc;
+
+use pest::error::{ErrorVariant, InputLocation, Limit};
+use pest::{Parser, ParserConfig};
+
+#[derive(Parser)]
+#[grammar = "../tests/limits.pest"]
+struct LimitsParser;
+
+#[test]
+fn silent_depth_limit() {
+    let depth = 2_000_000;
+    let input = format!("{}x{}", "(".repeat(depth), ")".repeat(depth));
+
+    let error = LimitsParser::parse_with_config(
+        Rule::parens,
+        Arc::from(input),
+        ParserConfig::new().max_depth(100),
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        error.variant,
+        ErrorVariant::LimitError {
+            limit: Limit::Depth(100)
+        }
+    );
+    // parens > silent_parens > ... exceeds 100 rules right after the 99th parenthesis
+    assert_eq!(error.location, InputLocation::Pos(99));
+}
+
+#[test]
+fn silent_depth_limit_not_reached() {
+    LimitsParser::parse_with_config(
+        Rule::parens,
+        Arc::from("((((x))))"),
+        ParserConfig::new().max_depth(16),
+    )
+    .unwrap();
+}
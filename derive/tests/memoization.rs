@@ -0,0 +1,82 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+extern crate pest;
+#[macro_use]
+extern crate fuel_pest_derive as pest_derive;
+
+use std::sync::Arc;
+
+use pest::{Parser, ParserConfig};
+
+#[derive(Parser)]
+#[grammar = "../tests/memoization.pest"]
+struct MemoizationParser;
+
+fn assert_same(rule: Rule, input: &str, config: ParserConfig<Rule>) {
+    let input: Arc<str> = Arc::from(input);
+    let plain = MemoizationParser::parse(rule, input.clone());
+    let memoized = MemoizationParser::parse_with_config(rule, input, config);
+
+    match (plain, memoized) {
+        (Ok(plain), Ok(memoized)) => {
+            assert_eq!(
+                plain.tokens().collect::<Vec<_>>(),
+                memoized.tokens().collect::<Vec<_>>()
+            );
+        }
+        (Err(plain), Err(memoized)) => assert_eq!(plain, memoized),
+        (plain, memoized) => panic!("{:?} != {:?}", plain, memoized),
+    }
+}
+
+#[test]
+fn same_pairs() {
+    for input in &["x", "x+x", "(x-x)+x", "((x))-(x+(x))", "x,(x+x),x-x"] {
+        assert_same(Rule::exprs, input, ParserConfig::new().memoize_all());
+        assert_same(Rule::exprs, input, ParserConfig::new().memoize(Rule::term));
+    }
+}
+
+#[test]
+fn same_errors() {
+    for input in &["", "y", "x+", "(x-x", "((x))-(x+(x)))", "x,(x+x),x-"] {
+        assert_same(Rule::exprs, input, ParserConfig::new().memoize_all());
+        assert_same(Rule::exprs, input, ParserConfig::new().memoize(Rule::term));
+    }
+}
+
+#[test]
+fn stack() {
+    for input in &["<a></a>", "<ab></a>", "<a></ab>"] {
+        assert_same(Rule::tag, input, ParserConfig::new().memoize_all());
+    }
+}
+
+#[test]
+fn exponential_backtracking() {
+    let depth = 100;
+    let input = format!("{}x{}", "(".repeat(depth), ")".repeat(depth));
+
+    let pairs = MemoizationParser::parse_with_config(
+        Rule::expr,
+        Arc::from(input.as_str()),
+        ParserConfig::new().memoize_all(),
+    )
+    .unwrap();
+
+    assert_eq!(pairs.as_str(), input);
+    assert_eq!(
+        pairs
+            .flatten()
+            .filter(|pair| pair.as_rule() == Rule::term)
+            .count(),
+        depth + 1
+    );
+}
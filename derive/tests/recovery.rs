@@ -0,0 +1,145 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+extern crate pest;
+#[macro_use]
+extern crate fuel_pest_derive as pest_derive;
+
+use std::sync::Arc;
+
+use pest::error::{ErrorVariant, InputLocation, Limit};
+use pest::{Parser, ParserConfig, Recovered};
+#[derive(Parser)]
+#[grammar = "../tests/recovery.pest"]
+struct RecoveryParser;
+
+fn recover(input: &str) -> Recovered<Rule> {
+    RecoveryParser::parse_with_recovery(Rule::stmts, Arc::from(input), ParserConfig::new())
+}
+
+fn errors(recovered: &Recovered<Rule>) -> Vec<(usize, Vec<Rule>)> {
+    recovered
+        .errors()
+        .iter()
+        .map(|error| match (&error.location, &error.variant) {
+            (InputLocation::Pos(pos), ErrorVariant::ParsingError { positives, .. }) => {
+                (*pos, positives.clone())
+            }
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+#[test]
+fn no_errors() {
+    let recovered = recover("a = 1; b = 2;");
+
+    assert!(recovered.is_ok());
+    assert!(recovered.errors().is_empty());
+    assert_eq!(recovered.pairs().unwrap().as_str(), "a = 1; b = 2;");
+}
+
+#[test]
+fn multiple_errors() {
+    let recovered = recover("a = 1; b = ; = 3; d = 4;");
+
+    assert!(!recovered.is_ok());
+    assert_eq!(
+        errors(&recovered),
+        vec![(11, vec![Rule::number]), (13, vec![Rule::ident])]
+    );
+
+    let stmts: Vec<_> = recovered
+        .pairs()
+        .unwrap()
+        .clone()
+        .next()
+        .unwrap()
+        .into_inner()
+        .map(|pair| (pair.as_rule(), pair.as_str().to_owned()))
+        .collect();
+
+    assert_eq!(
+        stmts,
+        vec![
+            (Rule::stmt, "a = 1".to_owned()),
+            (Rule::ERROR, "b = ".to_owned()),
+            (Rule::ERROR, "= 3".to_owned()),
+            (Rule::stmt, "d = 4".to_owned()),
+            (Rule::EOI, "".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn parse_returns_first_error() {
+    let input = "a = ; b = ; c = 3;";
+
+    let recovered = recover(input);
+    let error = RecoveryParser::parse(Rule::stmts, Arc::from(input)).unwrap_err();
+
+    assert_eq!(recovered.errors().len(), 2);
+    assert_eq!(recovered.errors()[0], error);
+}
+
+#[test]
+fn unrecoverable() {
+    let recovered = recover("a = ; b = 2");
+
+    assert!(recovered.pairs().is_none());
+    assert_eq!(
+        errors(&recovered),
+        vec![(4, vec![Rule::number]), (6, vec![Rule::EOI])]
+    );
+}
+
+#[test]
+fn atomic_has_no_error_node() {
+    let recovered =
+        RecoveryParser::parse_with_recovery(Rule::tag, Arc::from("<ab>"), ParserConfig::new());
+
+    assert_eq!(errors(&recovered), vec![(1, vec![])]);
+
+    let pairs: Vec<_> = recovered
+        .pairs()
+        .unwrap()
+        .clone()
+        .flatten()
+        .map(|pair| pair.as_rule())
+        .collect();
+
+    assert_eq!(pairs, vec![Rule::tag]);
+}
+
+#[test]
+fn atomic_backtracked() {
+    let recovered =
+        RecoveryParser::parse_with_recovery(Rule::tags, Arc::from("<ab>"), ParserConfig::new());
+
+    assert!(recovered.is_ok());
+    assert!(recovered.errors().is_empty());
+}
+
+#[test]
+fn step_limit_while_skipping() {
+    let recovered = RecoveryParser::parse_with_recovery(
+        Rule::stmts,
+        Arc::from(format!("a = 1; b = {}", "x".repeat(1000))),
+        ParserConfig::new().max_steps(100),
+    );
+
+    assert!(recovered.pairs().is_none());
+    assert_eq!(recovered.errors().len(), 1);
+    assert_eq!(
+        recovered.errors()[0].variant,
+        ErrorVariant::LimitError {
+            limit: Limit::Steps(100),
+        }
+    );
+}
@@ -0,0 +1,123 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+extern crate pest;
+#[macro_use]
+extern crate fuel_pest_derive as pest_derive;
+
+use std::sync::Arc;
+
+use pest::iterators::{Leaf, Pair};
+use pest::{Parser, ParserConfig};
+
+#[derive(Parser)]
+#[grammar = "../tests/trivia.pest"]
+struct TriviaParser;
+
+const INPUT: &str = "// the answer\nx = 42 ; // trailing\ny=1;\n// end\n";
+
+fn parse(config: ParserConfig<Rule>) -> Pair<Rule> {
+    TriviaParser::parse_with_config(Rule::file, Arc::from(INPUT), config)
+        .unwrap()
+        .next()
+        .unwrap()
+}
+
+fn strs(pairs: Vec<Pair<Rule>>) -> Vec<String> {
+    pairs
+        .into_iter()
+        .map(|pair| pair.as_str().to_owned())
+        .collect()
+}
+
+#[test]
+fn pairs_unchanged() {
+    let lossless = parse(ParserConfig::new().track_trivia());
+
+    assert_eq!(
+        format!("{:?}", lossless),
+        format!("{:?}", parse(ParserConfig::new()))
+    );
+    assert_eq!(lossless.clone().into_inner().count(), 3);
+    assert_eq!(lossless.into_inner().flatten().count(), 7);
+}
+
+#[test]
+fn leaves_rebuild_input() {
+    let leaves: Vec<_> = parse(ParserConfig::new().track_trivia()).leaves().collect();
+
+    assert_eq!(
+        leaves.iter().map(|leaf| leaf.as_str()).collect::<String>(),
+        INPUT
+    );
+    assert_eq!(
+        leaves
+            .iter()
+            .filter_map(|leaf| match *leaf {
+                Leaf::Trivia(ref pair) if pair.as_rule() == Rule::COMMENT => Some(pair.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>(),
+        vec!["// the answer", "// trailing", "// end"]
+    );
+    assert!(leaves
+        .iter()
+        .any(|leaf| *leaf == Leaf::Text(leaf.as_span()) && leaf.as_str() == "="));
+}
+
+#[test]
+fn leaves_without_trivia() {
+    let leaves = parse(ParserConfig::new()).leaves();
+
+    assert_eq!(
+        leaves
+            .map(|leaf| leaf.as_str().to_owned())
+            .collect::<String>(),
+        INPUT
+    );
+}
+
+#[test]
+fn leading_trivia() {
+    let items: Vec<_> = parse(ParserConfig::new().track_trivia())
+        .into_inner()
+        .collect();
+
+    assert_eq!(strs(items[0].leading_trivia()), vec!["// the answer", "\n"]);
+    assert_eq!(
+        strs(items[1].leading_trivia()),
+        vec![" ", "// trailing", "\n"]
+    );
+    assert_eq!(strs(items[2].leading_trivia()), vec!["\n", "// end", "\n"]);
+    assert_eq!(items[2].as_rule(), Rule::EOI);
+    assert!(items[0].trailing_trivia().is_empty());
+}
+
+#[test]
+fn trailing_trivia() {
+    let item = parse(ParserConfig::new().track_trivia())
+        .into_inner()
+        .next()
+        .unwrap();
+    let mut inner = item.into_inner();
+    let ident = inner.next().unwrap();
+    let number = inner.next().unwrap();
+
+    assert_eq!(strs(ident.trailing_trivia()), vec![" "]);
+    assert_eq!(strs(number.leading_trivia()), vec![" "]);
+    assert_eq!(strs(number.trailing_trivia()), vec![" "]);
+    assert!(number.into_inner().next().is_none());
+}
+
+#[test]
+fn trivia_untracked() {
+    let item = parse(ParserConfig::new()).into_inner().nth(1).unwrap();
+
+    assert!(item.leading_trivia().is_empty());
+}
@@ -0,0 +1,100 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+extern crate pest;
+#[macro_use]
+extern crate fuel_pest_derive as pest_derive;
+
+use std::sync::Arc;
+
+use pest::iterators::Pair;
+use pest::Parser;
+
+#[derive(Parser)]
+#[grammar = "../tests/visitor.pest"]
+#[visitor]
+struct VisitorParser;
+
+fn parse(input: &str) -> Pair<Rule> {
+    VisitorParser::parse(Rule::calc, Arc::from(input))
+        .unwrap()
+        .next()
+        .unwrap()
+}
+
+#[derive(Default)]
+struct Numbers {
+    numbers: Vec<String>,
+    sums: usize,
+}
+
+impl Visitor for Numbers {
+    fn visit_number(&mut self, pair: Pair<Rule>) {
+        self.numbers.push(pair.as_str().to_owned());
+    }
+
+    fn visit_sum(&mut self, pair: Pair<Rule>) {
+        self.sums += 1;
+        self.walk(pair);
+    }
+}
+
+struct Eval;
+
+impl Fold for Eval {
+    type Output = u64;
+
+    fn fold_sum(&mut self, pair: Pair<Rule>) -> u64 {
+        pair.into_inner().map(|pair| self.fold(pair)).sum()
+    }
+
+    fn fold_product(&mut self, pair: Pair<Rule>) -> u64 {
+        pair.into_inner().map(|pair| self.fold(pair)).product()
+    }
+
+    fn fold_number(&mut self, pair: Pair<Rule>) -> u64 {
+        pair.as_str().parse().unwrap()
+    }
+
+    fn fold_EOI(&mut self, _: Pair<Rule>) -> u64 {
+        unreachable!()
+    }
+}
+
+#[test]
+fn visit() {
+    let mut numbers = Numbers::default();
+    numbers.visit(parse("1 + 2 * (3 + 4)"));
+
+    assert_eq!(numbers.numbers, vec!["1", "2", "3", "4"]);
+    assert_eq!(numbers.sums, 2);
+}
+
+#[test]
+fn visit_default_walks() {
+    struct Products(usize);
+
+    impl Visitor for Products {
+        fn visit_product(&mut self, pair: Pair<Rule>) {
+            self.0 += 1;
+            self.walk(pair);
+        }
+    }
+
+    let mut products = Products(0);
+    products.visit(parse("1 * (2 + 3 * 4)"));
+
+    assert_eq!(products.0, 3);
+}
+
+#[test]
+fn fold() {
+    assert_eq!(Eval.fold(parse("1 + 2 * (3 + 4)")), 15);
+    assert_eq!(Eval.fold(parse("(2)")), 2);
+}
@@ -0,0 +1,395 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+use proc_macro2::TokenStream;
+use syn::{self, Attribute, Data, DeriveInput, Fields, Generics, Ident, Meta, NestedMeta, Path};
+
+// Where the value of a field comes from.
+#[derive(Debug, PartialEq)]
+enum Source {
+    // The next inner pairs, converted with `FromPairs`
+    Inner,
+    // The span of the pair
+    Span,
+    // The text of the pair, converted with `FromStr`
+    Text,
+}
+
+pub fn derive_from_pairs(input: TokenStream) -> TokenStream {
+    let ast: DeriveInput = syn::parse2(input).unwrap();
+    let name = ast.ident;
+    let rule = parse_rule(&ast.attrs);
+
+    match ast.data {
+        Data::Struct(data) => {
+            let rule = match rule {
+                Some(rule) => rule,
+                None => panic!("structs need a rule, provided with #[pest_ast(rule(Rule::name))]"),
+            };
+            let body = generate_rule_body(&rule, quote!(#name), &data.fields);
+
+            generate_impl(
+                &name,
+                &ast.generics,
+                &ast.generics,
+                &rule_type(&rule),
+                quote!(vec![#rule]),
+                body,
+            )
+        }
+        Data::Enum(data) => {
+            let variants: Vec<_> = data
+                .variants
+                .iter()
+                .map(|variant| (variant, parse_rule(&variant.attrs)))
+                .collect();
+
+            // Without any rule to take the type from, the impl is generic over it.
+            let concrete = rule
+                .iter()
+                .chain(variants.iter().filter_map(|(_, rule)| rule.as_ref()))
+                .next()
+                .map(rule_type);
+            let generic = concrete.is_none();
+            let rule_ty = concrete.unwrap_or_else(|| quote!(__R));
+
+            let mut generics = ast.generics.clone();
+            let mut variant_rules = vec![];
+            let mut conversions = vec![];
+
+            for (variant, variant_rule) in variants {
+                let ident = &variant.ident;
+
+                match variant_rule {
+                    Some(variant_rule) => {
+                        variant_rules.push(quote! {
+                            rules.push(#variant_rule);
+                        });
+
+                        let body = generate_rule_body(
+                            &variant_rule,
+                            quote!(#name::#ident),
+                            &variant.fields,
+                        );
+                        conversions.push(quote! {
+                            if rule == ::std::option::Option::Some(#variant_rule) {
+                                #body
+                            }
+                        });
+                    }
+                    None => {
+                        let ty = match variant.fields {
+                            Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => {
+                                &fields.unnamed[0].ty
+                            }
+                            _ => panic!(
+                                "enum variants need a rule, provided with \
+                                 #[pest_ast(rule(Rule::name))], unless they wrap a single type"
+                            ),
+                        };
+
+                        if generic {
+                            generics
+                                .make_where_clause()
+                                .predicates
+                                .push(syn::parse_quote!(#ty: ::pest::from_pairs::FromPairs<__R>));
+                        }
+
+                        variant_rules.push(quote! {
+                            rules.extend(
+                                <#ty as ::pest::from_pairs::FromPairs<#rule_ty>>::rules()
+                            );
+                        });
+                        conversions.push(quote! {
+                            if rule.map_or(false, |rule| {
+                                <#ty as ::pest::from_pairs::FromPairs<#rule_ty>>::rules()
+                                    .contains(&rule)
+                            }) {
+                                ::pest::from_pairs::FromPairs::from_pairs(pairs, span)
+                                    .map(#name::#ident)
+                            }
+                        });
+                    }
+                }
+            }
+
+            let variant_rules = quote! {{
+                let mut rules = vec![];
+                #( #variant_rules )*
+                rules
+            }};
+            let dispatch = quote! {
+                let rule = pairs.peek().map(|pair| pair.as_rule());
+
+                #( #conversions else )* {
+                    ::std::result::Result::Err(
+                        ::pest::from_pairs::expected(#variant_rules, pairs, span)
+                    )
+                }
+            };
+
+            match rule {
+                Some(rule) => {
+                    let body = quote! {
+                        let pair = ::pest::from_pairs::next_pair(pairs, span, #rule)?;
+                        let span = pair.as_span();
+                        let mut inner = pair.into_inner();
+                        let result = {
+                            let pairs = &mut inner;
+                            let span = &span;
+                            #dispatch
+                        }?;
+
+                        ::pest::from_pairs::finish(inner)?;
+
+                        ::std::result::Result::Ok(result)
+                    };
+
+                    generate_impl(
+                        &name,
+                        &ast.generics,
+                        &generics,
+                        &rule_ty,
+                        quote!(vec![#rule]),
+                        body,
+                    )
+                }
+                None => {
+                    if generic {
+                        generics
+                            .params
+                            .push(syn::parse_quote!(__R: ::pest::RuleType));
+                    }
+
+                    generate_impl(
+                        &name,
+                        &ast.generics,
+                        &generics,
+                        &rule_ty,
+                        variant_rules,
+                        dispatch,
+                    )
+                }
+            }
+        }
+        Data::Union(_) => panic!("FromPairs cannot be derived for unions"),
+    }
+}
+
+// `impl_generics` are the `generics` of the type extended with the ones of the impl.
+fn generate_impl(
+    name: &Ident,
+    generics: &Generics,
+    impl_generics: &Generics,
+    rule_ty: &TokenStream,
+    rules: TokenStream,
+    body: TokenStream,
+) -> TokenStream {
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+
+    quote! {
+        #[allow(clippy::all)]
+        impl #impl_generics ::pest::from_pairs::FromPairs<#rule_ty> for #name #ty_generics
+            #where_clause
+        {
+            fn rules() -> ::std::vec::Vec<#rule_ty> {
+                #rules
+            }
+
+            #[allow(unused_variables)]
+            fn from_pairs(
+                pairs: &mut ::pest::iterators::Pairs<#rule_ty>,
+                span: &::pest::Span,
+            ) -> ::std::result::Result<Self, ::pest::error::Error<#rule_ty>> {
+                #body
+            }
+        }
+    }
+}
+
+// Converts the next pair, which must be of `rule`, into `path` with `fields`.
+fn generate_rule_body(rule: &Path, path: TokenStream, fields: &Fields) -> TokenStream {
+    let values: Vec<_> = fields
+        .iter()
+        .map(|field| match parse_source(&field.attrs) {
+            Source::Inner => quote! {
+                ::pest::from_pairs::FromPairs::from_pairs(&mut inner, &span)?
+            },
+            Source::Span => quote!(pair.as_span()),
+            Source::Text => quote!(::pest::from_pairs::parse_text(&pair)?),
+        })
+        .collect();
+
+    let value = match *fields {
+        Fields::Named(ref fields) => {
+            let idents = fields.named.iter().map(|field| &field.ident);
+            quote!(#path { #( #idents: #values ),* })
+        }
+        Fields::Unnamed(_) => quote!(#path( #( #values ),* )),
+        Fields::Unit => quote!(#path),
+    };
+
+    // Pairs whose fields all come from the pair itself are leaves, which ignore inner pairs.
+    let leaf = fields
+        .iter()
+        .all(|field| parse_source(&field.attrs) != Source::Inner);
+
+    if leaf {
+        quote! {{
+            let pair = ::pest::from_pairs::next_pair(pairs, span, #rule)?;
+
+            ::std::result::Result::Ok(#value)
+        }}
+    } else {
+        quote! {{
+            let pair = ::pest::from_pairs::next_pair(pairs, span, #rule)?;
+            let span = pair.as_span();
+            let mut inner = pair.clone().into_inner();
+            let result = #value;
+
+            ::pest::from_pairs::finish(inner)?;
+
+            ::std::result::Result::Ok(result)
+        }}
+    }
+}
+
+// `Rule::name` is of type `Rule`.
+fn rule_type(rule: &Path) -> TokenStream {
+    let segments: Vec<_> = rule.segments.iter().collect();
+
+    if segments.len() < 2 {
+        panic!("rule must be a path to a variant, such as `Rule::name`");
+    }
+
+    let leading_colon = rule.leading_colon;
+    let segments = &segments[..segments.len() - 1];
+
+    quote!(#leading_colon #( #segments )::*)
+}
+
+fn parse_rule(attrs: &[Attribute]) -> Option<Path> {
+    let mut rule = None;
+
+    for meta in pest_ast_metas(attrs) {
+        let path = match meta {
+            Meta::List(ref list) if list.path.is_ident("rule") && list.nested.len() == 1 => {
+                match list.nested[0] {
+                    NestedMeta::Meta(Meta::Path(ref path)) => path.clone(),
+                    _ => panic!("rule must be a path, such as `rule(Rule::name)`"),
+                }
+            }
+            _ => panic!("expected `rule(...)` in pest_ast attribute"),
+        };
+
+        if rule.replace(path).is_some() {
+            panic!("only 1 rule can be provided");
+        }
+    }
+
+    rule
+}
+
+fn parse_source(attrs: &[Attribute]) -> Source {
+    let mut source = Source::Inner;
+
+    for meta in pest_ast_metas(attrs) {
+        source = match meta {
+            Meta::Path(ref path) if path.is_ident("span") => Source::Span,
+            Meta::Path(ref path) if path.is_ident("text") => Source::Text,
+            _ => panic!("expected `span` or `text` in pest_ast attribute"),
+        };
+    }
+
+    source
+}
+
+fn pest_ast_metas(attrs: &[Attribute]) -> Vec<Meta> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("pest_ast"))
+        .flat_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => list
+                .nested
+                .into_iter()
+                .map(|nested| match nested {
+                    NestedMeta::Meta(meta) => meta,
+                    NestedMeta::Lit(_) => panic!("pest_ast attribute cannot contain literals"),
+                })
+                .collect::<Vec<_>>(),
+            _ => panic!("pest_ast attribute must be of the form `#[pest_ast(...)]`"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rule_type_of_path() {
+        let rule: Path = syn::parse_str("::parser::Rule::name").unwrap();
+
+        assert_eq!(
+            rule_type(&rule).to_string(),
+            quote!(::parser::Rule).to_string()
+        );
+    }
+
+    #[test]
+    fn sources() {
+        let ast: DeriveInput = syn::parse_str(
+            "
+            #[pest_ast(rule(Rule::a))]
+            struct A {
+                inner: B,
+                #[pest_ast(span)]
+                span: Span,
+                #[pest_ast(text)]
+                text: String,
+            }
+            ",
+        )
+        .unwrap();
+        let sources: Vec<_> = match ast.data {
+            Data::Struct(data) => data
+                .fields
+                .iter()
+                .map(|field| parse_source(&field.attrs))
+                .collect(),
+            _ => unreachable!(),
+        };
+
+        assert_eq!(
+            parse_rule(&ast.attrs).map(|rule| quote!(#rule).to_string()),
+            Some(quote!(Rule::a).to_string())
+        );
+        assert_eq!(sources, vec![Source::Inner, Source::Span, Source::Text]);
+    }
+
+    #[test]
+    #[should_panic(expected = "structs need a rule, provided with #[pest_ast(rule(Rule::name))]")]
+    fn struct_without_rule() {
+        derive_from_pairs(quote! {
+            struct A {
+                b: B,
+            }
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "rule must be a path to a variant, such as `Rule::name`")]
+    fn rule_without_type() {
+        derive_from_pairs(quote! {
+            #[pest_ast(rule(name))]
+            struct A;
+        });
+    }
+}
@@ -7,7 +7,9 @@
 // option. All files in the project carrying such notice may not be copied,
 // modified, or distributed except according to those terms.
 
-use proc_macro2::{Span, TokenStream};
+use std::collections::{HashMap, HashSet};
+
+use proc_macro2::{Literal, Span, TokenStream};
 use quote::{ToTokens, TokenStreamExt};
 use syn::{self, Generics, Ident};
 
@@ -23,8 +25,14 @@ pub fn generate(
     rules: Vec<OptimizedRule>,
     defaults: Vec<&str>,
     include_grammar: bool,
+    visitor: bool,
 ) -> TokenStream {
     let uses_eoi = defaults.iter().any(|name| *name == "EOI");
+    let uses_recover = rules.iter().any(|rule| {
+        rule.expr
+            .iter_top_down()
+            .any(|expr| matches!(expr, OptimizedExpr::Recover(..)))
+    });
 
     let builtins = generate_builtin_rules();
     let include_fix = if include_grammar {
@@ -35,11 +43,24 @@ pub fn generate(
     } else {
         quote!()
     };
-    let rule_enum = generate_enum(&rules, uses_eoi);
-    let patterns = generate_patterns(&rules, uses_eoi);
+    let rule_enum = generate_enum(&rules, uses_eoi, uses_recover);
+    let visitor = if visitor {
+        generate_visitor(&rules, uses_eoi, uses_recover)
+    } else {
+        quote!()
+    };
+    let patterns = generate_patterns(&rules, uses_eoi, uses_recover);
     let skip = generate_skip(&rules);
 
-    let mut rules: Vec<_> = rules.into_iter().map(generate_rule).collect();
+    let left_recursive = left_recursive_rules(&rules);
+
+    let mut rules: Vec<_> = rules
+        .into_iter()
+        .map(|rule| {
+            let left_recursive = left_recursive.contains(&rule.name);
+            generate_rule(rule, left_recursive)
+        })
+        .collect();
     rules.extend(builtins.into_iter().filter_map(|(builtin, tokens)| {
         if defaults.contains(&builtin) {
             Some(tokens)
@@ -62,6 +83,25 @@ pub fn generate(
                 ::pest::iterators::Pairs<Rule>,
                 ::pest::error::Error<Rule>
             > {
+                Self::parse_with_config(rule, input, ::pest::ParserConfig::default())
+            }
+
+            fn parse_with_config(
+                rule: Rule,
+                input: ::std::sync::Arc<str>,
+                config: ::pest::ParserConfig<Rule>,
+            ) -> #result<
+                ::pest::iterators::Pairs<Rule>,
+                ::pest::error::Error<Rule>
+            > {
+                Self::parse_with_recovery(rule, input, config).into_result()
+            }
+
+            fn parse_with_recovery(
+                rule: Rule,
+                input: ::std::sync::Arc<str>,
+                config: ::pest::ParserConfig<Rule>,
+            ) -> ::pest::Recovered<Rule> {
                 mod rules {
                     #![allow(clippy::upper_case_acronyms)]
                     pub mod hidden {
@@ -77,7 +117,7 @@ pub fn generate(
                     pub use self::visible::*;
                 }
 
-                ::pest::state(::std::sync::Arc::from(input), |state| {
+                ::pest::state_with_recovery(::std::sync::Arc::from(input), config, |state| {
                     match rule {
                         #patterns
                     }
@@ -89,6 +129,7 @@ pub fn generate(
     quote! {
         #include_fix
         #rule_enum
+        #visitor
         #parser_impl
     }
 }
@@ -175,31 +216,132 @@ fn generate_include(name: &Ident, path: &str) -> TokenStream {
     }
 }
 
-fn generate_enum(rules: &[OptimizedRule], uses_eoi: bool) -> TokenStream {
-    let rules = rules
+fn generate_enum(rules: &[OptimizedRule], uses_eoi: bool, uses_recover: bool) -> TokenStream {
+    let mut rules: Vec<_> = rules
         .iter()
-        .map(|rule| Ident::new(rule.name.as_str(), Span::call_site()));
+        .map(|rule| Ident::new(rule.name.as_str(), Span::call_site()))
+        .collect();
+
     if uses_eoi {
-        quote! {
-            #[allow(dead_code, non_camel_case_types, clippy::upper_case_acronyms)]
-            #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-            pub enum Rule {
-                EOI,
-                #( #rules ),*
-            }
+        rules.insert(0, Ident::new("EOI", Span::call_site()));
+    }
+
+    if uses_recover {
+        rules.push(Ident::new("ERROR", Span::call_site()));
+    }
+
+    quote! {
+        #[allow(dead_code, non_camel_case_types, clippy::upper_case_acronyms)]
+        #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+        pub enum Rule {
+            #( #rules ),*
         }
+    }
+}
+
+// Silent rules never produce pairs, so they get no methods.
+fn generate_visitor(rules: &[OptimizedRule], uses_eoi: bool, uses_recover: bool) -> TokenStream {
+    let mut names: Vec<_> = rules
+        .iter()
+        .filter(|rule| rule.ty != RuleType::Silent)
+        .map(|rule| rule.name.as_str())
+        .collect();
+
+    if uses_eoi {
+        names.insert(0, "EOI");
+    }
+
+    if uses_recover {
+        names.push("ERROR");
+    }
+
+    let ident = |name: String| Ident::new(&name, Span::call_site());
+    let variants: Vec<_> = names.iter().map(|name| ident(name.to_string())).collect();
+    let visits: Vec<_> = names
+        .iter()
+        .map(|name| ident(format!("visit_{}", name)))
+        .collect();
+    let folds: Vec<_> = names
+        .iter()
+        .map(|name| ident(format!("fold_{}", name)))
+        .collect();
+    let visit_docs: Vec<_> = names
+        .iter()
+        .map(|name| {
+            format!(
+                "Visits a `{}` pair. Walks its inner pairs by default.",
+                name
+            )
+        })
+        .collect();
+    let fold_docs: Vec<_> = names
+        .iter()
+        .map(|name| format!("Folds a `{}` pair into a value.", name))
+        .collect();
+
+    let silent: Vec<_> = rules
+        .iter()
+        .filter(|rule| rule.ty == RuleType::Silent)
+        .map(|rule| ident(rule.name.clone()))
+        .collect();
+    let silent = if silent.is_empty() {
+        quote!()
     } else {
         quote! {
-            #[allow(dead_code, non_camel_case_types, clippy::upper_case_acronyms)]
-            #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-            pub enum Rule {
-                #( #rules ),*
+            #( Rule::#silent )|* => unreachable!("silent rules do not produce pairs"),
+        }
+    };
+
+    quote! {
+        /// Walks a parse tree, calling the `visit_<rule>` method of every pair's rule.
+        #[allow(dead_code, non_snake_case)]
+        pub trait Visitor {
+            /// Calls the `visit_<rule>` method of `pair`'s rule.
+            fn visit(&mut self, pair: ::pest::iterators::Pair<Rule>) {
+                match pair.as_rule() {
+                    #( Rule::#variants => self.#visits(pair), )*
+                    #silent
+                }
+            }
+
+            /// Visits the inner pairs of `pair` in order.
+            fn walk(&mut self, pair: ::pest::iterators::Pair<Rule>) {
+                for inner in pair.into_inner() {
+                    self.visit(inner);
+                }
             }
+
+            #(
+                #[doc = #visit_docs]
+                fn #visits(&mut self, pair: ::pest::iterators::Pair<Rule>) {
+                    self.walk(pair)
+                }
+            )*
+        }
+
+        /// Folds a parse tree into values, with one required `fold_<rule>` method per rule.
+        #[allow(dead_code, non_snake_case)]
+        pub trait Fold {
+            /// The value every pair is folded into.
+            type Output;
+
+            /// Calls the `fold_<rule>` method of `pair`'s rule.
+            fn fold(&mut self, pair: ::pest::iterators::Pair<Rule>) -> Self::Output {
+                match pair.as_rule() {
+                    #( Rule::#variants => self.#folds(pair), )*
+                    #silent
+                }
+            }
+
+            #(
+                #[doc = #fold_docs]
+                fn #folds(&mut self, pair: ::pest::iterators::Pair<Rule>) -> Self::Output;
+            )*
         }
     }
 }
 
-fn generate_patterns(rules: &[OptimizedRule], uses_eoi: bool) -> TokenStream {
+fn generate_patterns(rules: &[OptimizedRule], uses_eoi: bool, uses_recover: bool) -> TokenStream {
     let mut rules: Vec<TokenStream> = rules
         .iter()
         .map(|rule| {
@@ -216,12 +358,19 @@ fn generate_patterns(rules: &[OptimizedRule], uses_eoi: bool) -> TokenStream {
         });
     }
 
+    // Error nodes are only ever produced by `RECOVER`; they cannot be parsed on their own.
+    if uses_recover {
+        rules.push(quote! {
+            Rule::ERROR => Err(state)
+        });
+    }
+
     quote! {
         #( #rules ),*
     }
 }
 
-fn generate_rule(rule: OptimizedRule) -> TokenStream {
+fn generate_rule(rule: OptimizedRule, left_recursive: bool) -> TokenStream {
     let name = Ident::new(&rule.name, Span::call_site());
     let expr = if rule.ty == RuleType::Atomic || rule.ty == RuleType::CompoundAtomic {
         generate_expr_atomic(rule.expr)
@@ -237,59 +386,163 @@ fn generate_rule(rule: OptimizedRule) -> TokenStream {
         generate_expr(rule.expr)
     };
 
-    let box_ty = box_type();
-
-    match rule.ty {
+    let body = match rule.ty {
         RuleType::Normal => quote! {
-            #[inline]
-            #[allow(non_snake_case, unused_variables)]
-            pub fn #name(state: #box_ty<::pest::ParserState<Rule>>) -> ::pest::ParseResult<#box_ty<::pest::ParserState<Rule>>> {
-                state.rule(Rule::#name, |state| {
-                    #expr
-                })
-            }
+            state.rule(Rule::#name, |state| {
+                #expr
+            })
         },
         RuleType::Silent => quote! {
-            #[inline]
-            #[allow(non_snake_case, unused_variables)]
-            pub fn #name(state: #box_ty<::pest::ParserState<Rule>>) -> ::pest::ParseResult<#box_ty<::pest::ParserState<Rule>>> {
+            state.silent(|state| {
                 #expr
-            }
+            })
         },
         RuleType::Atomic => quote! {
-            #[inline]
-            #[allow(non_snake_case, unused_variables)]
-            pub fn #name(state: #box_ty<::pest::ParserState<Rule>>) -> ::pest::ParseResult<#box_ty<::pest::ParserState<Rule>>> {
-                state.rule(Rule::#name, |state| {
-                    state.atomic(::pest::Atomicity::Atomic, |state| {
-                        #expr
-                    })
+            state.rule(Rule::#name, |state| {
+                state.atomic(::pest::Atomicity::Atomic, |state| {
+                    #expr
                 })
-            }
+            })
         },
         RuleType::CompoundAtomic => quote! {
-            #[inline]
-            #[allow(non_snake_case, unused_variables)]
-            pub fn #name(state: #box_ty<::pest::ParserState<Rule>>) -> ::pest::ParseResult<#box_ty<::pest::ParserState<Rule>>> {
-                state.atomic(::pest::Atomicity::CompoundAtomic, |state| {
-                    state.rule(Rule::#name, |state| {
-                        #expr
-                    })
+            state.atomic(::pest::Atomicity::CompoundAtomic, |state| {
+                state.rule(Rule::#name, |state| {
+                    #expr
                 })
-            }
+            })
         },
         RuleType::NonAtomic => quote! {
-            #[inline]
-            #[allow(non_snake_case, unused_variables)]
-            pub fn #name(state: #box_ty<::pest::ParserState<Rule>>) -> ::pest::ParseResult<#box_ty<::pest::ParserState<Rule>>> {
-                state.atomic(::pest::Atomicity::NonAtomic, |state| {
-                    state.rule(Rule::#name, |state| {
-                        #expr
-                    })
+            state.atomic(::pest::Atomicity::NonAtomic, |state| {
+                state.rule(Rule::#name, |state| {
+                    #expr
                 })
-            }
+            })
         },
+    };
+    let body = if left_recursive {
+        quote! {
+            state.left_recursive(Rule::#name, |state| {
+                #body
+            })
+        }
+    } else {
+        body
+    };
+
+    let box_ty = box_type();
+
+    quote! {
+        #[inline]
+        #[allow(non_snake_case, unused_variables)]
+        pub fn #name(state: #box_ty<::pest::ParserState<Rule>>) -> ::pest::ParseResult<#box_ty<::pest::ParserState<Rule>>> {
+            #body
+        }
+    }
+}
+
+// Returns the names of the rules which can call themselves without consuming any input, which
+// are grown from a seed with `ParserState::left_recursive`.
+fn left_recursive_rules(rules: &[OptimizedRule]) -> HashSet<String> {
+    // Whether `expr` can succeed without consuming any input, given the rules which can.
+    fn is_nullable(expr: &OptimizedExpr, nullable: &HashSet<&str>) -> bool {
+        match *expr {
+            OptimizedExpr::Str(ref string)
+            | OptimizedExpr::Insens(ref string)
+            | OptimizedExpr::InsensUnicode(ref string) => string.is_empty(),
+            OptimizedExpr::Bytes(ref bytes) => bytes.is_empty(),
+            OptimizedExpr::Range(..) | OptimizedExpr::ByteRange(..) => false,
+            OptimizedExpr::Ident(ref name) => match name.as_str() {
+                "SOI" | "EOI" | "PEEK" | "PEEK_ALL" | "POP" | "POP_ALL" | "DROP" => true,
+                name => nullable.contains(name),
+            },
+            OptimizedExpr::PeekSlice(..)
+            | OptimizedExpr::PosPred(_)
+            | OptimizedExpr::NegPred(_)
+            | OptimizedExpr::Opt(_)
+            | OptimizedExpr::Rep(_)
+            | OptimizedExpr::Skip(_) => true,
+            OptimizedExpr::Seq(ref lhs, ref rhs) => {
+                is_nullable(lhs, nullable) && is_nullable(rhs, nullable)
+            }
+            OptimizedExpr::Choice(ref lhs, ref rhs) => {
+                is_nullable(lhs, nullable) || is_nullable(rhs, nullable)
+            }
+            OptimizedExpr::RepOnce(ref expr)
+            | OptimizedExpr::Push(ref expr)
+            | OptimizedExpr::RestoreOnErr(ref expr)
+            | OptimizedExpr::Recover(ref expr, _) => is_nullable(expr, nullable),
+        }
     }
+
+    // Collects the rules `expr` can call before consuming any input.
+    fn left_calls<'a>(expr: &'a OptimizedExpr, nullable: &HashSet<&str>, calls: &mut Vec<&'a str>) {
+        match *expr {
+            OptimizedExpr::Ident(ref name) => calls.push(name),
+            OptimizedExpr::Seq(ref lhs, ref rhs) => {
+                left_calls(lhs, nullable, calls);
+                if is_nullable(lhs, nullable) {
+                    left_calls(rhs, nullable, calls);
+                }
+            }
+            OptimizedExpr::Choice(ref lhs, ref rhs) => {
+                left_calls(lhs, nullable, calls);
+                left_calls(rhs, nullable, calls);
+            }
+            OptimizedExpr::PosPred(ref expr)
+            | OptimizedExpr::NegPred(ref expr)
+            | OptimizedExpr::Opt(ref expr)
+            | OptimizedExpr::Rep(ref expr)
+            | OptimizedExpr::RepOnce(ref expr)
+            | OptimizedExpr::Push(ref expr)
+            | OptimizedExpr::RestoreOnErr(ref expr)
+            | OptimizedExpr::Recover(ref expr, _) => left_calls(expr, nullable, calls),
+            _ => {}
+        }
+    }
+
+    let mut nullable = HashSet::new();
+    loop {
+        let len = nullable.len();
+        for rule in rules {
+            if is_nullable(&rule.expr, &nullable) {
+                nullable.insert(rule.name.as_str());
+            }
+        }
+        if nullable.len() == len {
+            break;
+        }
+    }
+
+    let calls: HashMap<&str, Vec<&str>> = rules
+        .iter()
+        .map(|rule| {
+            let mut calls = vec![];
+            left_calls(&rule.expr, &nullable, &mut calls);
+            (rule.name.as_str(), calls)
+        })
+        .collect();
+
+    rules
+        .iter()
+        .filter(|rule| {
+            let mut visited = HashSet::new();
+            let mut stack = calls[rule.name.as_str()].clone();
+
+            while let Some(name) = stack.pop() {
+                if name == rule.name {
+                    return true;
+                }
+                if visited.insert(name) {
+                    if let Some(next) = calls.get(name) {
+                        stack.extend(next);
+                    }
+                }
+            }
+
+            false
+        })
+        .map(|rule| rule.name.clone())
+        .collect()
 }
 
 fn generate_skip(rules: &[OptimizedRule]) -> TokenStream {
@@ -301,7 +554,9 @@ fn generate_skip(rules: &[OptimizedRule]) -> TokenStream {
         (true, false) => generate_rule!(
             skip,
             if state.atomicity() == ::pest::Atomicity::NonAtomic {
-                state.repeat(|state| super::visible::WHITESPACE(state))
+                state.repeat(|state| {
+                    state.trivia(Rule::WHITESPACE, |state| super::visible::WHITESPACE(state))
+                })
             } else {
                 Ok(state)
             }
@@ -309,7 +564,9 @@ fn generate_skip(rules: &[OptimizedRule]) -> TokenStream {
         (false, true) => generate_rule!(
             skip,
             if state.atomicity() == ::pest::Atomicity::NonAtomic {
-                state.repeat(|state| super::visible::COMMENT(state))
+                state.repeat(|state| {
+                    state.trivia(Rule::COMMENT, |state| super::visible::COMMENT(state))
+                })
             } else {
                 Ok(state)
             }
@@ -319,13 +576,24 @@ fn generate_skip(rules: &[OptimizedRule]) -> TokenStream {
             if state.atomicity() == ::pest::Atomicity::NonAtomic {
                 state.sequence(|state| {
                     state
-                        .repeat(|state| super::visible::WHITESPACE(state))
+                        .repeat(|state| {
+                            state
+                                .trivia(Rule::WHITESPACE, |state| super::visible::WHITESPACE(state))
+                        })
                         .and_then(|state| {
                             state.repeat(|state| {
                                 state.sequence(|state| {
-                                    super::visible::COMMENT(state).and_then(|state| {
-                                        state.repeat(|state| super::visible::WHITESPACE(state))
-                                    })
+                                    state
+                                        .trivia(Rule::COMMENT, |state| {
+                                            super::visible::COMMENT(state)
+                                        })
+                                        .and_then(|state| {
+                                            state.repeat(|state| {
+                                                state.trivia(Rule::WHITESPACE, |state| {
+                                                    super::visible::WHITESPACE(state)
+                                                })
+                                            })
+                                        })
                                 })
                             })
                         })
@@ -349,6 +617,11 @@ fn generate_expr(expr: OptimizedExpr) -> TokenStream {
                 state.match_insensitive(#string)
             }
         }
+        OptimizedExpr::InsensUnicode(string) => {
+            quote! {
+                state.match_insensitive_unicode(#string)
+            }
+        }
         OptimizedExpr::Range(start, end) => {
             let start = start.chars().next().unwrap();
             let end = end.chars().next().unwrap();
@@ -357,6 +630,18 @@ fn generate_expr(expr: OptimizedExpr) -> TokenStream {
                 state.match_range(#start..#end)
             }
         }
+        OptimizedExpr::Bytes(bytes) => {
+            let bytes = Literal::byte_string(&bytes);
+
+            quote! {
+                state.match_bytes(#bytes)
+            }
+        }
+        OptimizedExpr::ByteRange(start, end) => {
+            quote! {
+                state.match_byte_range(#start..#end)
+            }
+        }
         OptimizedExpr::Ident(ident) => {
             let ident = Ident::new(&ident, Span::call_site());
             quote! { self::#ident(state) }
@@ -501,6 +786,14 @@ fn generate_expr(expr: OptimizedExpr) -> TokenStream {
                 state.restore_on_err(|state| #expr)
             }
         }
+        OptimizedExpr::Recover(expr, sync) => {
+            let expr = generate_expr(*expr);
+            let sync = generate_expr(*sync);
+
+            quote! {
+                state.recover(Rule::ERROR, |state| #expr, |state| #sync)
+            }
+        }
     }
 }
 
@@ -516,6 +809,11 @@ fn generate_expr_atomic(expr: OptimizedExpr) -> TokenStream {
                 state.match_insensitive(#string)
             }
         }
+        OptimizedExpr::InsensUnicode(string) => {
+            quote! {
+                state.match_insensitive_unicode(#string)
+            }
+        }
         OptimizedExpr::Range(start, end) => {
             let start = start.chars().next().unwrap();
             let end = end.chars().next().unwrap();
@@ -524,6 +822,18 @@ fn generate_expr_atomic(expr: OptimizedExpr) -> TokenStream {
                 state.match_range(#start..#end)
             }
         }
+        OptimizedExpr::Bytes(bytes) => {
+            let bytes = Literal::byte_string(&bytes);
+
+            quote! {
+                state.match_bytes(#bytes)
+            }
+        }
+        OptimizedExpr::ByteRange(start, end) => {
+            quote! {
+                state.match_byte_range(#start..#end)
+            }
+        }
         OptimizedExpr::Ident(ident) => {
             let ident = Ident::new(&ident, Span::call_site());
             quote! { self::#ident(state) }
@@ -650,6 +960,14 @@ fn generate_expr_atomic(expr: OptimizedExpr) -> TokenStream {
                 state.restore_on_err(|state| #expr)
             }
         }
+        OptimizedExpr::Recover(expr, sync) => {
+            let expr = generate_expr_atomic(*expr);
+            let sync = generate_expr_atomic(*sync);
+
+            quote! {
+                state.recover(Rule::ERROR, |state| #expr, |state| #sync)
+            }
+        }
     }
 }
 
@@ -702,7 +1020,7 @@ mod tests {
         }];
 
         assert_eq!(
-            generate_enum(&rules, false).to_string(),
+            generate_enum(&rules, false, false).to_string(),
             quote! {
                 #[allow(dead_code, non_camel_case_types, clippy::upper_case_acronyms)]
                 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
@@ -714,6 +1032,121 @@ mod tests {
         );
     }
 
+    #[test]
+    fn visitor() {
+        let rules = vec![
+            OptimizedRule {
+                name: "f".to_owned(),
+                ty: RuleType::Normal,
+                expr: OptimizedExpr::Ident("g".to_owned()),
+            },
+            OptimizedRule {
+                name: "g".to_owned(),
+                ty: RuleType::Silent,
+                expr: OptimizedExpr::Str("g".to_owned()),
+            },
+        ];
+
+        assert_eq!(
+            generate_visitor(&rules, true, false).to_string(),
+            quote! {
+                /// Walks a parse tree, calling the `visit_<rule>` method of every pair's rule.
+                #[allow(dead_code, non_snake_case)]
+                pub trait Visitor {
+                    /// Calls the `visit_<rule>` method of `pair`'s rule.
+                    fn visit(&mut self, pair: ::pest::iterators::Pair<Rule>) {
+                        match pair.as_rule() {
+                            Rule::EOI => self.visit_EOI(pair),
+                            Rule::f => self.visit_f(pair),
+                            Rule::g => unreachable!("silent rules do not produce pairs"),
+                        }
+                    }
+
+                    /// Visits the inner pairs of `pair` in order.
+                    fn walk(&mut self, pair: ::pest::iterators::Pair<Rule>) {
+                        for inner in pair.into_inner() {
+                            self.visit(inner);
+                        }
+                    }
+
+                    #[doc = "Visits a `EOI` pair. Walks its inner pairs by default."]
+                    fn visit_EOI(&mut self, pair: ::pest::iterators::Pair<Rule>) {
+                        self.walk(pair)
+                    }
+
+                    #[doc = "Visits a `f` pair. Walks its inner pairs by default."]
+                    fn visit_f(&mut self, pair: ::pest::iterators::Pair<Rule>) {
+                        self.walk(pair)
+                    }
+                }
+
+                /// Folds a parse tree into values, with one required `fold_<rule>` method per rule.
+                #[allow(dead_code, non_snake_case)]
+                pub trait Fold {
+                    /// The value every pair is folded into.
+                    type Output;
+
+                    /// Calls the `fold_<rule>` method of `pair`'s rule.
+                    fn fold(&mut self, pair: ::pest::iterators::Pair<Rule>) -> Self::Output {
+                        match pair.as_rule() {
+                            Rule::EOI => self.fold_EOI(pair),
+                            Rule::f => self.fold_f(pair),
+                            Rule::g => unreachable!("silent rules do not produce pairs"),
+                        }
+                    }
+
+                    #[doc = "Folds a `EOI` pair into a value."]
+                    fn fold_EOI(&mut self, pair: ::pest::iterators::Pair<Rule>) -> Self::Output;
+
+                    #[doc = "Folds a `f` pair into a value."]
+                    fn fold_f(&mut self, pair: ::pest::iterators::Pair<Rule>) -> Self::Output;
+                }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn left_recursive() {
+        let rule = |name: &str, expr| OptimizedRule {
+            name: name.to_owned(),
+            ty: RuleType::Normal,
+            expr,
+        };
+        let ident = |name: &str| Box::new(OptimizedExpr::Ident(name.to_owned()));
+        let rules = vec![
+            // a = { b? ~ c ~ "a" | "a" }
+            rule(
+                "a",
+                OptimizedExpr::Choice(
+                    Box::new(OptimizedExpr::Seq(
+                        Box::new(OptimizedExpr::Opt(ident("b"))),
+                        Box::new(OptimizedExpr::Seq(
+                            ident("c"),
+                            Box::new(OptimizedExpr::Str("a".to_owned())),
+                        )),
+                    )),
+                    Box::new(OptimizedExpr::Str("a".to_owned())),
+                ),
+            ),
+            // b = { "b" ~ a }
+            rule(
+                "b",
+                OptimizedExpr::Seq(Box::new(OptimizedExpr::Str("b".to_owned())), ident("a")),
+            ),
+            // c = { SOI ~ a* }
+            rule(
+                "c",
+                OptimizedExpr::Seq(ident("SOI"), Box::new(OptimizedExpr::Rep(ident("a")))),
+            ),
+        ];
+
+        let mut left_recursive: Vec<_> = left_recursive_rules(&rules).into_iter().collect();
+        left_recursive.sort();
+
+        assert_eq!(left_recursive, vec!["a", "c"]);
+    }
+
     #[test]
     fn sequence() {
         let expr = OptimizedExpr::Seq(
@@ -1001,7 +1434,7 @@ mod tests {
         let result = result_type();
         let box_ty = box_type();
         assert_eq!(
-            generate(name, &generics, Some(String::from("test.pest")), rules, defaults, true).to_string(),
+            generate(name, &generics, Some(String::from("test.pest")), rules, defaults, true, false).to_string(),
             quote! {
                 #[allow(non_upper_case_globals)]
                 const _PEST_GRAMMAR_MyParser: &'static str = include_str!("test.pest");
@@ -1021,6 +1454,25 @@ mod tests {
                         ::pest::iterators::Pairs<Rule>,
                         ::pest::error::Error<Rule>
                     > {
+                        Self::parse_with_config(rule, input, ::pest::ParserConfig::default())
+                    }
+
+                    fn parse_with_config(
+                        rule: Rule,
+                        input: ::std::sync::Arc<str>,
+                        config: ::pest::ParserConfig<Rule>,
+                    ) -> #result<
+                        ::pest::iterators::Pairs<Rule>,
+                        ::pest::error::Error<Rule>
+                    > {
+                        Self::parse_with_recovery(rule, input, config).into_result()
+                    }
+
+                    fn parse_with_recovery(
+                        rule: Rule,
+                        input: ::std::sync::Arc<str>,
+                        config: ::pest::ParserConfig<Rule>,
+                    ) -> ::pest::Recovered<Rule> {
                         mod rules {
                             #![allow(clippy::upper_case_acronyms)]
                             pub mod hidden {
@@ -1039,7 +1491,9 @@ mod tests {
                                 #[inline]
                                 #[allow(non_snake_case, unused_variables)]
                                 pub fn a(state: #box_ty<::pest::ParserState<Rule>>) -> ::pest::ParseResult<#box_ty<::pest::ParserState<Rule>>> {
-                                    state.match_string("b")
+                                    state.silent(|state| {
+                                        state.match_string("b")
+                                    })
                                 }
 
                                 #[inline]
@@ -1052,7 +1506,7 @@ mod tests {
                             pub use self::visible::*;
                         }
 
-                        ::pest::state(::std::sync::Arc::from(input), |state| {
+                        ::pest::state_with_recovery(::std::sync::Arc::from(input), config, |state| {
                             match rule {
                                 Rule::a => rules::a(state)
                             }
@@ -29,13 +29,16 @@ use syn::{Attribute, DeriveInput, Generics, Ident, Lit, Meta};
 
 #[macro_use]
 mod macros;
+mod from_pairs;
 mod generator;
 
+pub use from_pairs::derive_from_pairs;
 use pest_meta::parser::{self, Rule};
 use pest_meta::{optimizer, unwrap_or_report, validator};
 
 pub fn derive_parser(input: TokenStream, include_grammar: bool) -> TokenStream {
     let ast: DeriveInput = syn::parse2(input).unwrap();
+    let visitor = parse_visitor(&ast.attrs);
     let (name, generics, content) = parse_derive(ast);
 
     let (data, path) = match content {
@@ -63,6 +66,7 @@ pub fn derive_parser(input: TokenStream, include_grammar: bool) -> TokenStream {
             error.renamed_rules(|rule| match *rule {
                 Rule::grammar_rule => "rule".to_owned(),
                 Rule::_push => "PUSH".to_owned(),
+                Rule::_recover => "RECOVER".to_owned(),
                 Rule::assignment_operator => "`=`".to_owned(),
                 Rule::silent_modifier => "`_`".to_owned(),
                 Rule::atomic_modifier => "`@`".to_owned(),
@@ -96,7 +100,15 @@ pub fn derive_parser(input: TokenStream, include_grammar: bool) -> TokenStream {
     let ast = unwrap_or_report(parser::consume_rules(pairs));
     let optimized = optimizer::optimize(ast);
 
-    generator::generate(name, &generics, path, optimized, defaults, include_grammar)
+    generator::generate(
+        name,
+        &generics,
+        path,
+        optimized,
+        defaults,
+        include_grammar,
+        visitor,
+    )
 }
 
 fn read_file<P: AsRef<Path>>(path: P) -> io::Result<String> {
@@ -136,6 +148,17 @@ fn parse_derive(ast: DeriveInput) -> (Ident, Generics, GrammarSource) {
     (name, generics, argument)
 }
 
+// `#[visitor]` opts into generating the `Visitor` and `Fold` traits.
+fn parse_visitor(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| match attr.parse_meta() {
+        Ok(Meta::Path(path)) => path.is_ident("visitor"),
+        Ok(ref meta) if meta.path().is_ident("visitor") => {
+            panic!("visitor attribute must be of the form `#[visitor]`")
+        }
+        _ => false,
+    })
+}
+
 fn get_attribute(attr: &Attribute) -> GrammarSource {
     match attr.parse_meta() {
         Ok(Meta::NameValue(name_value)) => match name_value.lit {
@@ -155,6 +178,7 @@ fn get_attribute(attr: &Attribute) -> GrammarSource {
 #[cfg(test)]
 mod tests {
     use super::parse_derive;
+    use super::parse_visitor;
     use super::GrammarSource;
     use syn;
 
@@ -206,4 +230,34 @@ mod tests {
         let ast = syn::parse_str(definition).unwrap();
         parse_derive(ast);
     }
+
+    #[test]
+    fn derive_visitor() {
+        let definition = "
+            #[grammar = \"myfile.pest\"]
+            #[visitor]
+            pub struct MyParser<'a, T>;
+        ";
+        let ast: syn::DeriveInput = syn::parse_str(definition).unwrap();
+        assert!(parse_visitor(&ast.attrs));
+
+        let definition = "
+            #[grammar = \"myfile.pest\"]
+            pub struct MyParser<'a, T>;
+        ";
+        let ast: syn::DeriveInput = syn::parse_str(definition).unwrap();
+        assert!(!parse_visitor(&ast.attrs));
+    }
+
+    #[test]
+    #[should_panic(expected = "visitor attribute must be of the form `#[visitor]`")]
+    fn derive_visitor_with_arg() {
+        let definition = "
+            #[grammar = \"myfile.pest\"]
+            #[visitor = \"yes\"]
+            pub struct MyParser<'a, T>;
+        ";
+        let ast: syn::DeriveInput = syn::parse_str(definition).unwrap();
+        parse_visitor(&ast.attrs);
+    }
 }
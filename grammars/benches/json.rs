@@ -9,25 +9,90 @@
 
 #![feature(test)]
 
+extern crate fuel_pest_grammars as pest_grammars;
 extern crate pest;
-extern crate pest_grammars;
 extern crate test;
 
 use std::fs::File;
 use std::io::Read;
+use std::sync::Arc;
 
 use test::Bencher;
 
-use pest::Parser;
+use pest::{Edit, Parser, ParserConfig};
 
 use pest_grammars::json::*;
 
-#[bench]
-fn data(b: &mut Bencher) {
+fn read() -> Arc<str> {
     let mut file = File::open("benches/data.json").unwrap();
     let mut data = String::new();
 
     file.read_to_string(&mut data).unwrap();
 
-    b.iter(|| JsonParser::parse(Rule::json, &data).unwrap());
+    Arc::from(data)
+}
+
+#[bench]
+fn data(b: &mut Bencher) {
+    let data = read();
+
+    b.iter(|| JsonParser::parse(Rule::json, data.clone()).unwrap());
+}
+
+#[bench]
+fn data_memoized(b: &mut Bencher) {
+    let data = read();
+
+    b.iter(|| {
+        JsonParser::parse_with_config(Rule::json, data.clone(), ParserConfig::new().memoize_all())
+            .unwrap()
+    });
+}
+
+#[bench]
+fn data_iterated(b: &mut Bencher) {
+    let data = read();
+    let pairs = JsonParser::parse(Rule::json, data).unwrap();
+
+    // Every pair yielded shares the token queue of `pairs`.
+    b.iter(|| {
+        pairs
+            .clone()
+            .flatten()
+            .map(|pair| pair.as_str().len())
+            .sum::<usize>()
+    });
+}
+
+// 50 copies of the data, so that an edit only affects a small part of the input.
+fn read_copies() -> Arc<str> {
+    let data = read();
+
+    Arc::from(format!("[{}]", vec![&*data; 50].join(",")))
+}
+
+#[bench]
+fn copies(b: &mut Bencher) {
+    let data = read_copies();
+
+    b.iter(|| JsonParser::parse(Rule::json, data.clone()).unwrap());
+}
+
+#[bench]
+fn copies_reparsed(b: &mut Bencher) {
+    let data = read_copies();
+    let middle = data.len() / 2;
+    let pos = middle + data[middle..].find(|c: char| c.is_ascii_digit()).unwrap();
+    let config = ParserConfig::new().memoize(Rule::value);
+    let mut parse = Some(JsonParser::parse_incremental(Rule::json, data, config));
+    let mut digit = 0;
+
+    // Changes a digit in the middle of the input.
+    b.iter(|| {
+        digit = digit % 9 + 1;
+        let edit = Edit::new(pos..pos + 1, digit.to_string());
+        let reparsed = JsonParser::reparse(parse.take().unwrap(), &[edit]);
+        assert!(reparsed.result().is_ok());
+        parse = Some(reparsed);
+    });
 }
@@ -0,0 +1,50 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+#![feature(test)]
+
+extern crate fuel_pest_grammars as pest_grammars;
+extern crate pest;
+extern crate test;
+
+use std::fs::File;
+use std::io::Read;
+use std::sync::Arc;
+
+use test::Bencher;
+
+use pest::{Parser, ParserConfig};
+
+use pest_grammars::toml::*;
+
+fn read() -> Arc<str> {
+    let mut file = File::open("tests/examples.toml").unwrap();
+    let mut data = String::new();
+
+    file.read_to_string(&mut data).unwrap();
+
+    Arc::from(data)
+}
+
+#[bench]
+fn data(b: &mut Bencher) {
+    let data = read();
+
+    b.iter(|| TomlParser::parse(Rule::toml, data.clone()).unwrap());
+}
+
+#[bench]
+fn data_memoized(b: &mut Bencher) {
+    let data = read();
+
+    b.iter(|| {
+        TomlParser::parse_with_config(Rule::toml, data.clone(), ParserConfig::new().memoize_all())
+            .unwrap()
+    });
+}
@@ -0,0 +1,96 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+use std::sync::Arc;
+
+use pest::{Edit, IncrementalParse, Parser, ParserConfig, RuleType};
+
+// Xorshift, so that the edits are random but the same on every run.
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+}
+
+// Replaces up to 8 bytes of `input` with one of `texts`.
+fn random_edit(rng: &mut Rng, input: &str, texts: &[&str]) -> Edit {
+    let mut start = rng.below(input.len() + 1);
+    while !input.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (start + rng.below(8)).min(input.len());
+    while !input.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    Edit::new(start..end, texts[rng.below(texts.len())])
+}
+
+/// Reparses `input`, which `P` parses from `rule`, after random edits inserting `texts` and
+/// after undoing them, checking every result against a parse from scratch.
+pub fn assert_reparses<P, R>(rule: R, input: &str, config: ParserConfig<R>, texts: &[&str])
+where
+    P: Parser<R>,
+    R: RuleType,
+{
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    let mut parse = P::parse_incremental(rule, Arc::from(input), config);
+
+    for _ in 0..100 {
+        let mut text = parse.input().to_string();
+        let mut edits = vec![];
+        let mut undos = vec![];
+
+        for _ in 0..1 + rng.below(3) {
+            let edit = random_edit(&mut rng, &text, texts);
+            let range = edit.range();
+            undos.push(Edit::new(
+                range.start..range.start + edit.text().len(),
+                &text[range.clone()],
+            ));
+            text.replace_range(range, edit.text());
+            edits.push(edit);
+        }
+        undos.reverse();
+
+        parse = P::reparse(parse, &edits);
+        assert_same::<P, R>(rule, &parse);
+
+        // Undoing goes back to valid input, so that both successes and errors are compared.
+        parse = P::reparse(parse, &undos);
+        assert_same::<P, R>(rule, &parse);
+        assert!(parse.result().is_ok());
+    }
+}
+
+/// Checks that `parse` has the same result as parsing its input from scratch, without any
+/// memoization.
+pub fn assert_same<P, R>(rule: R, parse: &IncrementalParse<R>)
+where
+    P: Parser<R>,
+    R: RuleType,
+{
+    let full = P::parse(rule, parse.input().clone());
+
+    match (parse.result(), full) {
+        (Ok(pairs), Ok(full)) => {
+            assert_eq!(
+                pairs.clone().tokens().collect::<Vec<_>>(),
+                full.tokens().collect::<Vec<_>>()
+            );
+        }
+        (Err(error), Err(full)) => assert_eq!(error, &full),
+        (result, full) => panic!("{:?} != {:?}", result, full),
+    }
+}
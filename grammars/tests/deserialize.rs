@@ -0,0 +1,139 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+extern crate fuel_pest_grammars as pest_grammars;
+extern crate pest;
+extern crate serde_json;
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use pest::de::{self, Shape};
+use pest::error::LineColLocation;
+use pest::iterators::Pair;
+use pest::Parser;
+use serde_json::Value;
+
+use pest_grammars::{json, toml};
+
+fn json_shape(rule: json::Rule) -> Shape {
+    use json::Rule;
+
+    match rule {
+        Rule::json | Rule::value => Shape::Inner,
+        Rule::object => Shape::Map,
+        Rule::array | Rule::pair => Shape::Seq,
+        Rule::string => Shape::EscapedString,
+        Rule::number => Shape::Number,
+        Rule::bool => Shape::Bool,
+        Rule::null => Shape::Unit,
+        Rule::EOI | Rule::WHITESPACE => Shape::Skip,
+        Rule::inner | Rule::escape | Rule::unicode | Rule::int | Rule::exp => Shape::String,
+    }
+}
+
+fn toml_shape(rule: toml::Rule) -> Shape {
+    use toml::Rule;
+
+    match rule {
+        Rule::toml | Rule::inline_table => Shape::Map,
+        Rule::array | Rule::pair => Shape::Seq,
+        Rule::string => Shape::EscapedString,
+        Rule::literal => Shape::QuotedString,
+        Rule::integer | Rule::float => Shape::Number,
+        Rule::boolean => Shape::Bool,
+        Rule::EOI => Shape::Skip,
+        _ => Shape::String,
+    }
+}
+
+fn parse_json(input: &str) -> Pair<json::Rule> {
+    json::JsonParser::parse(json::Rule::json, Arc::from(input))
+        .unwrap()
+        .next()
+        .unwrap()
+}
+
+#[test]
+fn json() {
+    let input = r#"{
+        "name": "pest",
+        "keywords": ["parser", "peg"],
+        "version": 3,
+        "ratio": -0.5,
+        "stable": true,
+        "license": null,
+        "nested": { "empty": [], "object": {} }
+    }"#;
+
+    let value: Value = de::from_pair(parse_json(input), json_shape).unwrap();
+
+    assert_eq!(value, serde_json::from_str::<Value>(input).unwrap());
+}
+
+#[test]
+fn json_escapes() {
+    let input = r#"["a\"b", "c\\d\/e", "\b\f\n\r\t", "\u00e9\u4e2d", "\ud83d\ude00"]"#;
+
+    let value: Value = de::from_pair(parse_json(input), json_shape).unwrap();
+
+    assert_eq!(value, serde_json::from_str::<Value>(input).unwrap());
+    assert_eq!(value[0], "a\"b");
+}
+
+#[test]
+fn json_invalid_escape() {
+    let pair = parse_json(r#"["\ud83d"]"#);
+    let error = de::from_pair::<Vec<String>, _, _>(pair, json_shape).unwrap_err();
+
+    assert_eq!(error.variant.message(), "invalid escape");
+    assert_eq!(error.line_col, LineColLocation::Span((1, 2), (1, 10)));
+}
+
+#[test]
+fn json_typed() {
+    let map: BTreeMap<String, Option<Vec<u32>>> =
+        de::from_pair(parse_json(r#"{ "a": [1, 2], "b": null }"#), json_shape).unwrap();
+
+    assert_eq!(map["a"], Some(vec![1, 2]));
+    assert_eq!(map["b"], None);
+}
+
+#[test]
+fn json_error_spans_value() {
+    let pair = parse_json("{\n  \"a\": 1,\n  \"b\": \"x\"\n}");
+    let error = de::from_pair::<BTreeMap<String, u32>, _, _>(pair, json_shape).unwrap_err();
+
+    assert_eq!(
+        error.variant.message(),
+        "invalid type: string \"x\", expected u32"
+    );
+    assert_eq!(error.line_col, LineColLocation::Span((3, 8), (3, 11)));
+}
+
+#[test]
+fn toml() {
+    let input =
+        "title = 'pe\\st'\nports = [ 8000, 8001 ]\nowner = { name = \"T\\u00f6m\\t\", admin = false }\n";
+    let pair = toml::TomlParser::parse(toml::Rule::toml, Arc::from(input))
+        .unwrap()
+        .next()
+        .unwrap();
+
+    let value: Value = de::from_pair(pair, toml_shape).unwrap();
+
+    assert_eq!(
+        value,
+        serde_json::json!({
+            "title": "pe\\st",
+            "ports": [8000, 8001],
+            "owner": { "name": "T\u{f6}m\t", "admin": false },
+        })
+    );
+}
@@ -11,11 +11,17 @@
 extern crate pest;
 extern crate fuel_pest_grammars as pest_grammars;
 
+mod common;
+
 use std::fs::File;
 use std::io::Read;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 
-use pest::Parser;
+use pest::error::{DetailedAttempts, ErrorVariant, InputLocation, Limit, Terminal};
+use pest::profile::Profiler;
+use pest::trace::PrintTracer;
+use pest::{Edit, Parser, ParserConfig};
 
 use pest_grammars::json::*;
 
@@ -172,3 +178,374 @@ fn examples() {
 
     JsonParser::parse(Rule::json, Arc::from(data)).unwrap();
 }
+
+#[test]
+fn examples_memoized() {
+    let mut file = File::open("tests/examples.json").unwrap();
+    let mut data = String::new();
+
+    file.read_to_string(&mut data).unwrap();
+
+    let input: Arc<str> = Arc::from(data);
+    let plain = JsonParser::parse(Rule::json, input.clone()).unwrap();
+    let memoized =
+        JsonParser::parse_with_config(Rule::json, input, ParserConfig::new().memoize_all())
+            .unwrap();
+
+    assert_eq!(
+        plain.tokens().collect::<Vec<_>>(),
+        memoized.tokens().collect::<Vec<_>>()
+    );
+}
+
+const TEXTS: &[&str] = &[
+    "", " ", ",", ":", "[", "]", "{", "}", "\"", "1", "-0.5e3", "null",
+];
+
+fn assert_reparses(config: ParserConfig<Rule>) {
+    let mut file = File::open("tests/examples.json").unwrap();
+    let mut data = String::new();
+
+    file.read_to_string(&mut data).unwrap();
+
+    common::assert_reparses::<JsonParser, _>(Rule::json, &data, config, TEXTS);
+}
+
+#[test]
+fn reparse_memoized() {
+    assert_reparses(ParserConfig::new().memoize_all());
+}
+
+#[test]
+fn reparse_values() {
+    assert_reparses(ParserConfig::new().memoize(Rule::value));
+}
+
+#[test]
+fn reparse_append() {
+    let config = ParserConfig::new().memoize_all();
+    let parse = JsonParser::parse_incremental(Rule::json, Arc::from("[1]"), config);
+
+    let parse = JsonParser::reparse(parse, &[Edit::new(3..3, ",")]);
+    common::assert_same::<JsonParser, _>(Rule::json, &parse);
+    assert!(parse.result().is_err());
+
+    let parse = JsonParser::reparse(parse, &[Edit::new(3..4, "")]);
+    common::assert_same::<JsonParser, _>(Rule::json, &parse);
+    assert!(parse.result().is_ok());
+}
+
+#[test]
+fn reparse_not_memoized() {
+    assert_reparses(ParserConfig::new());
+}
+
+#[test]
+fn trace() {
+    let tracer = Arc::new(Mutex::new(PrintTracer::new(vec![])));
+    let config = ParserConfig::new().tracer(tracer.clone());
+
+    JsonParser::parse_with_config(Rule::json, Arc::from("[1]"), config).unwrap();
+
+    assert_eq!(
+        String::from_utf8(tracer.lock().unwrap().get_ref().clone()).unwrap(),
+        "json at 0
+  object at 0
+  object failed at 0
+  array at 0
+    value at 1
+      string at 1
+      string failed at 1
+      number at 1
+        int at 1
+        int matched 1..2
+        exp at 2
+        exp failed at 2
+      number matched 1..2
+    value matched 1..2
+  array matched 0..3
+  EOI at 3
+  EOI matched 3..3
+json matched 0..3
+"
+    );
+}
+
+#[test]
+fn profile() {
+    let mut file = File::open("tests/examples.json").unwrap();
+    let mut data = String::new();
+
+    file.read_to_string(&mut data).unwrap();
+
+    let profiler = Arc::new(Mutex::new(Profiler::new()));
+    let config = ParserConfig::new().tracer(profiler.clone());
+
+    JsonParser::parse_with_config(Rule::json, Arc::from(data.as_str()), config).unwrap();
+
+    let profiles = profiler.lock().unwrap().profiles();
+    for profile in &profiles {
+        assert_eq!(profile.attempts, profile.successes + profile.failures);
+        assert!(profile.exclusive <= profile.inclusive);
+    }
+
+    let json = profiles.iter().find(|profile| profile.rule == Rule::json);
+    assert_eq!(json.unwrap().bytes, data.len());
+}
+
+#[test]
+fn depth_limit() {
+    let depth = 100_000;
+    let input = format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+
+    let error = JsonParser::parse_with_config(
+        Rule::json,
+        Arc::from(input),
+        ParserConfig::new().max_depth(64),
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        error.variant,
+        ErrorVariant::LimitError {
+            limit: Limit::Depth(64)
+        }
+    );
+    // json > array > value > array > ... reaches the 64th rule right after the 31st bracket
+    assert_eq!(error.location, InputLocation::Pos(31));
+}
+
+#[test]
+fn depth_limit_not_reached() {
+    let input = "[[[[]]]]";
+
+    JsonParser::parse_with_config(
+        Rule::json,
+        Arc::from(input),
+        ParserConfig::new().max_depth(16),
+    )
+    .unwrap();
+}
+
+#[test]
+fn call_limit() {
+    let input = "[1, 2, 3, 4, 5, 6, 7, 8, 9]";
+
+    let error = JsonParser::parse_with_config(
+        Rule::json,
+        Arc::from(input),
+        ParserConfig::new().max_calls(10),
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        error.variant,
+        ErrorVariant::LimitError {
+            limit: Limit::Calls(10)
+        }
+    );
+}
+
+#[test]
+fn step_limit() {
+    let input = format!("[1{}]", "0".repeat(10_000));
+
+    JsonParser::parse_with_config(
+        Rule::json,
+        Arc::from(input.as_str()),
+        ParserConfig::new().max_calls(100),
+    )
+    .unwrap();
+
+    let error = JsonParser::parse_with_config(
+        Rule::json,
+        Arc::from(input.as_str()),
+        ParserConfig::new().max_steps(1_000),
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        error.variant,
+        ErrorVariant::LimitError {
+            limit: Limit::Steps(1_000)
+        }
+    );
+    // Every digit is one iteration of `int`'s repetition.
+    match error.location {
+        InputLocation::Pos(pos) => assert!(pos > 900 && pos < 1_000),
+        InputLocation::Span(_) => panic!("expected a position"),
+    }
+}
+
+#[test]
+fn cancelled() {
+    let flag = Arc::new(AtomicBool::new(true));
+
+    let error = JsonParser::parse_with_config(
+        Rule::json,
+        Arc::from("[1, 2, 3]"),
+        ParserConfig::new().cancel_on(flag.clone()),
+    )
+    .unwrap_err();
+
+    assert_eq!(error.variant, ErrorVariant::Cancelled);
+    assert_eq!(error.location, InputLocation::Pos(0));
+
+    flag.store(false, Ordering::Relaxed);
+
+    JsonParser::parse_with_config(
+        Rule::json,
+        Arc::from("[1, 2, 3]"),
+        ParserConfig::new().cancel_on(flag),
+    )
+    .unwrap();
+}
+
+#[test]
+fn cancelled_by_callback() {
+    let input = format!("[{}1]", "1, ".repeat(1_000));
+    let checks = Arc::new(AtomicUsize::new(0));
+    let counter = checks.clone();
+
+    let error = JsonParser::parse_with_config(
+        Rule::json,
+        Arc::from(input),
+        ParserConfig::new().cancel_if(move || counter.fetch_add(1, Ordering::Relaxed) + 1 == 3),
+    )
+    .unwrap_err();
+
+    assert_eq!(error.variant, ErrorVariant::Cancelled);
+    // The callback is called on the first step and then every `CANCEL_INTERVAL` steps.
+    assert_eq!(checks.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+fn expected_terminals() {
+    let input = "[1 2]";
+
+    let error = JsonParser::parse_with_config(
+        Rule::json,
+        Arc::from(input),
+        ParserConfig::new().track_terminals(),
+    )
+    .unwrap_err();
+
+    let literal = |string: &str| Terminal::Literal(string.to_owned());
+    assert_eq!(
+        error.variant,
+        ErrorVariant::DetailedParsingError(Box::new(DetailedAttempts {
+            positives: vec![],
+            negatives: vec![],
+            terminals: vec![
+                literal("\t"),
+                literal("\n"),
+                literal("\r"),
+                literal(" "),
+                literal(","),
+                literal("]"),
+            ],
+        }))
+    );
+    // At the 2, after the whitespace
+    assert_eq!(error.location, InputLocation::Pos(3));
+
+    let memoized = JsonParser::parse_with_config(
+        Rule::json,
+        Arc::from(input),
+        ParserConfig::new().track_terminals().memoize_all(),
+    )
+    .unwrap_err();
+
+    assert_eq!(memoized.variant, error.variant);
+}
+
+#[test]
+fn expected_terminals_in_atomic_rule() {
+    let input = "-x";
+
+    let error = JsonParser::parse_with_config(
+        Rule::number,
+        Arc::from(input),
+        ParserConfig::new().track_terminals(),
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        error.variant,
+        ErrorVariant::DetailedParsingError(Box::new(DetailedAttempts {
+            positives: vec![],
+            negatives: vec![],
+            terminals: vec![Terminal::Literal("0".to_owned()), Terminal::Range('1', '9')],
+        }))
+    );
+    // Without terminals, the error would only mention number, at 0
+    assert_eq!(error.location, InputLocation::Pos(1));
+}
+
+#[test]
+fn rule_stack() {
+    let input = "{\"a\": [1, {\"b\" 2}]}";
+
+    let error = JsonParser::parse_with_config(
+        Rule::json,
+        Arc::from(input),
+        ParserConfig::new().track_rule_stack(),
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        error.rule_stack(),
+        &[
+            Rule::json,
+            Rule::object,
+            Rule::pair,
+            Rule::value,
+            Rule::array,
+            Rule::value,
+            Rule::object,
+            Rule::pair
+        ]
+    );
+    assert_eq!(
+        format!("{}", error),
+        vec![
+            " --> 1:12",
+            "  |",
+            "1 | {\"a\": [1, {\"b\" 2}]}",
+            "  |            ^---",
+            "  |",
+            "  = expected pair",
+            "  = while parsing json > object > pair > value > array > value > object > pair",
+        ]
+        .join("\n")
+    );
+
+    // The failing object is replayed from the memoization table, in a different array.
+    let config = ParserConfig::new().track_rule_stack().memoize_all();
+    let parse = JsonParser::parse_incremental(Rule::json, Arc::from(input), config);
+    let parse = JsonParser::reparse(parse, &[Edit::new(6..7, "[[")]);
+    let reparsed = parse.result().unwrap_err();
+
+    assert_eq!(
+        reparsed.rule_stack(),
+        &[
+            Rule::json,
+            Rule::object,
+            Rule::pair,
+            Rule::value,
+            Rule::array,
+            Rule::value,
+            Rule::array,
+            Rule::value,
+            Rule::object,
+            Rule::pair
+        ]
+    );
+}
+
+#[test]
+fn rule_stack_not_tracked() {
+    let error = JsonParser::parse(Rule::json, Arc::from("[1 2]")).unwrap_err();
+
+    assert!(error.rule_stack().is_empty());
+}
@@ -11,11 +11,13 @@
 extern crate pest;
 extern crate fuel_pest_grammars as pest_grammars;
 
+mod common;
+
 use std::fs::File;
 use std::io::Read;
 use std::sync::Arc;
 
-use pest::Parser;
+use pest::{Parser, ParserConfig};
 
 use pest_grammars::toml::*;
 
@@ -283,3 +285,39 @@ fn examples() {
 
     TomlParser::parse(Rule::toml, Arc::from(data)).unwrap();
 }
+
+#[test]
+fn examples_memoized() {
+    let mut file = File::open("tests/examples.toml").unwrap();
+    let mut data = String::new();
+
+    file.read_to_string(&mut data).unwrap();
+
+    let input: Arc<str> = Arc::from(data);
+    let plain = TomlParser::parse(Rule::toml, input.clone()).unwrap();
+    let memoized =
+        TomlParser::parse_with_config(Rule::toml, input, ParserConfig::new().memoize_all())
+            .unwrap();
+
+    assert_eq!(
+        plain.tokens().collect::<Vec<_>>(),
+        memoized.tokens().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn reparse_memoized() {
+    const TEXTS: &[&str] = &["", " ", "\n", "=", "[", "]", "\"", "'", "1", "a", "# c\n"];
+
+    let mut file = File::open("tests/examples.toml").unwrap();
+    let mut data = String::new();
+
+    file.read_to_string(&mut data).unwrap();
+
+    common::assert_reparses::<TomlParser, _>(
+        Rule::toml,
+        &data,
+        ParserConfig::new().memoize_all(),
+        TEXTS,
+    );
+}
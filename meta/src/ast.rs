@@ -27,10 +27,21 @@ pub enum RuleType {
 pub enum Expr {
     /// Matches an exact string, e.g. `"a"`
     Str(String),
-    /// Matches an exact string, case insensitively (ASCII only), e.g. `^"a"`
+    /// Matches an exact string, case insensitively (ASCII only, unless parsing with Unicode case
+    /// folding), e.g. `^"a"`
+    ///
+    /// Unicode case folding is not locale-aware: it uses the default mappings, under which `I`
+    /// folds to `i` rather than to the Turkic dotless `ı`, and `İ` folds to `i̇` rather than to `i`.
     Insens(String),
+    /// Matches an exact string under full Unicode case folding, whatever the parser's
+    /// configuration, e.g. `^u"straße"`, which also matches `STRASSE`
+    InsensUnicode(String),
     /// Matches one character in the range, e.g. `'a'..'z'`
     Range(String, String),
+    /// Matches exact bytes, e.g. `b"\x89PNG"`
+    Bytes(Vec<u8>),
+    /// Matches one byte in the range, e.g. `b'\x00'..b'\x7F'`
+    ByteRange(u8, u8),
     /// Matches the rule with the given name, e.g. `a`
     Ident(String),
     /// Matches a custom part of the stack, e.g. `PEEK[..]`
@@ -61,6 +72,9 @@ pub enum Expr {
     Skip(Vec<String>),
     /// Matches an expression and pushes it to the stack, e.g. `push(e)`
     Push(Box<Expr>),
+    /// Matches an expression or, if it fails, skips input until the second expression matches,
+    /// e.g. `RECOVER(e, s)`
+    Recover(Box<Expr>, Box<Expr>),
 }
 
 impl Expr {
@@ -130,6 +144,11 @@ impl Expr {
                     let mapped = Box::new(map_internal(*expr, f));
                     Expr::Push(mapped)
                 }
+                Expr::Recover(expr, sync) => {
+                    let mapped_expr = Box::new(map_internal(*expr, f));
+                    let mapped_sync = Box::new(map_internal(*sync, f));
+                    Expr::Recover(mapped_expr, mapped_sync)
+                }
                 expr => expr,
             }
         }
@@ -197,6 +216,11 @@ impl Expr {
                     let mapped = Box::new(map_internal(*expr, f));
                     Expr::Push(mapped)
                 }
+                Expr::Recover(expr, sync) => {
+                    let mapped_expr = Box::new(map_internal(*expr, f));
+                    let mapped_sync = Box::new(map_internal(*sync, f));
+                    Expr::Recover(mapped_expr, mapped_sync)
+                }
                 expr => expr,
             };
 
@@ -235,6 +259,10 @@ impl ExprTopDownIterator {
                 self.right_branches.push(*rhs);
                 self.next = Some(*lhs);
             }
+            Expr::Recover(expr, sync) => {
+                self.right_branches.push(*sync);
+                self.next = Some(*expr);
+            }
             Expr::PosPred(expr)
             | Expr::NegPred(expr)
             | Expr::Rep(expr)
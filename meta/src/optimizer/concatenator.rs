@@ -21,6 +21,12 @@ pub fn concatenate(rule: Rule) -> Rule {
                     Expr::Seq(lhs, rhs) => match (*lhs, *rhs) {
                         (Expr::Str(lhs), Expr::Str(rhs)) => Expr::Str(lhs + &rhs),
                         (Expr::Insens(lhs), Expr::Insens(rhs)) => Expr::Insens(lhs + &rhs),
+                        // `InsensUnicode` is not concatenated, since a fold can span both sides,
+                        // like `ß` does `^u"s" ~ ^u"s"`.
+                        (Expr::Bytes(mut lhs), Expr::Bytes(rhs)) => {
+                            lhs.extend(rhs);
+                            Expr::Bytes(lhs)
+                        }
                         (lhs, rhs) => Expr::Seq(Box::new(lhs), Box::new(rhs)),
                     },
                     expr => expr,
@@ -50,7 +50,10 @@ fn rule_to_optimized_rule(rule: Rule) -> OptimizedRule {
         match expr {
             Expr::Str(string) => OptimizedExpr::Str(string),
             Expr::Insens(string) => OptimizedExpr::Insens(string),
+            Expr::InsensUnicode(string) => OptimizedExpr::InsensUnicode(string),
             Expr::Range(start, end) => OptimizedExpr::Range(start, end),
+            Expr::Bytes(bytes) => OptimizedExpr::Bytes(bytes),
+            Expr::ByteRange(start, end) => OptimizedExpr::ByteRange(start, end),
             Expr::Ident(ident) => OptimizedExpr::Ident(ident),
             Expr::PeekSlice(start, end) => OptimizedExpr::PeekSlice(start, end),
             Expr::PosPred(expr) => OptimizedExpr::PosPred(Box::new(to_optimized(*expr))),
@@ -65,6 +68,9 @@ fn rule_to_optimized_rule(rule: Rule) -> OptimizedRule {
             Expr::Rep(expr) => OptimizedExpr::Rep(Box::new(to_optimized(*expr))),
             Expr::Skip(strings) => OptimizedExpr::Skip(strings),
             Expr::Push(expr) => OptimizedExpr::Push(Box::new(to_optimized(*expr))),
+            Expr::Recover(expr, sync) => {
+                OptimizedExpr::Recover(Box::new(to_optimized(*expr)), Box::new(to_optimized(*sync)))
+            }
             Expr::RepOnce(expr) => OptimizedExpr::RepOnce(Box::new(to_optimized(*expr))),
             Expr::RepExact(..) | Expr::RepMin(..) | Expr::RepMax(..) | Expr::RepMinMax(..) => {
                 unreachable!("No valid transformation to OptimizedRule")
@@ -97,7 +103,10 @@ pub struct OptimizedRule {
 pub enum OptimizedExpr {
     Str(String),
     Insens(String),
+    InsensUnicode(String),
     Range(String, String),
+    Bytes(Vec<u8>),
+    ByteRange(u8, u8),
     Ident(String),
     PeekSlice(i32, Option<i32>),
     PosPred(Box<OptimizedExpr>),
@@ -110,6 +119,7 @@ pub enum OptimizedExpr {
     Skip(Vec<String>),
     Push(Box<OptimizedExpr>),
     RestoreOnErr(Box<OptimizedExpr>),
+    Recover(Box<OptimizedExpr>, Box<OptimizedExpr>),
 }
 
 impl OptimizedExpr {
@@ -159,6 +169,11 @@ impl OptimizedExpr {
                     let mapped = Box::new(map_internal(*expr, f));
                     OptimizedExpr::Push(mapped)
                 }
+                OptimizedExpr::Recover(expr, sync) => {
+                    let mapped_expr = Box::new(map_internal(*expr, f));
+                    let mapped_sync = Box::new(map_internal(*sync, f));
+                    OptimizedExpr::Recover(mapped_expr, mapped_sync)
+                }
                 expr => expr,
             }
         }
@@ -206,6 +221,11 @@ impl OptimizedExpr {
                     let mapped = Box::new(map_internal(*expr, f));
                     OptimizedExpr::Push(mapped)
                 }
+                OptimizedExpr::Recover(expr, sync) => {
+                    let mapped_expr = Box::new(map_internal(*expr, f));
+                    let mapped_sync = Box::new(map_internal(*sync, f));
+                    OptimizedExpr::Recover(mapped_expr, mapped_sync)
+                }
                 expr => expr,
             };
 
@@ -244,11 +264,17 @@ impl OptimizedExprTopDownIterator {
                 self.right_branches.push(*rhs);
                 self.next = Some(*lhs);
             }
+            OptimizedExpr::Recover(expr, sync) => {
+                self.right_branches.push(*sync);
+                self.next = Some(*expr);
+            }
             OptimizedExpr::PosPred(expr)
             | OptimizedExpr::NegPred(expr)
             | OptimizedExpr::Rep(expr)
+            | OptimizedExpr::RepOnce(expr)
             | OptimizedExpr::Opt(expr)
-            | OptimizedExpr::Push(expr) => {
+            | OptimizedExpr::Push(expr)
+            | OptimizedExpr::RestoreOnErr(expr) => {
                 self.next = Some(*expr);
             }
             _ => {
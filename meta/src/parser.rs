@@ -98,6 +98,10 @@ impl ParserNode {
                 ParserExpr::Push(node) => {
                     filter_internal(*node, f, result);
                 }
+                ParserExpr::Recover(node, sync) => {
+                    filter_internal(*node, f, result);
+                    filter_internal(*sync, f, result);
+                }
                 _ => (),
             }
         }
@@ -114,7 +118,10 @@ impl ParserNode {
 pub enum ParserExpr {
     Str(String),
     Insens(String),
+    InsensUnicode(String),
     Range(String, String),
+    Bytes(Vec<u8>),
+    ByteRange(u8, u8),
     Ident(String),
     PeekSlice(i32, Option<i32>),
     PosPred(Box<ParserNode>),
@@ -129,6 +136,7 @@ pub enum ParserExpr {
     RepMax(Box<ParserNode>, u32),
     RepMinMax(Box<ParserNode>, u32, u32),
     Push(Box<ParserNode>),
+    Recover(Box<ParserNode>, Box<ParserNode>),
 }
 
 fn convert_rule(rule: ParserRule) -> AstRule {
@@ -141,7 +149,10 @@ fn convert_node(node: ParserNode) -> Expr {
     match node.expr {
         ParserExpr::Str(string) => Expr::Str(string),
         ParserExpr::Insens(string) => Expr::Insens(string),
+        ParserExpr::InsensUnicode(string) => Expr::InsensUnicode(string),
         ParserExpr::Range(start, end) => Expr::Range(start, end),
+        ParserExpr::Bytes(bytes) => Expr::Bytes(bytes),
+        ParserExpr::ByteRange(start, end) => Expr::ByteRange(start, end),
         ParserExpr::Ident(ident) => Expr::Ident(ident),
         ParserExpr::PeekSlice(start, end) => Expr::PeekSlice(start, end),
         ParserExpr::PosPred(node) => Expr::PosPred(Box::new(convert_node(*node))),
@@ -164,6 +175,9 @@ fn convert_node(node: ParserNode) -> Expr {
             Expr::RepMinMax(Box::new(convert_node(*node)), min, max)
         }
         ParserExpr::Push(node) => Expr::Push(Box::new(convert_node(*node))),
+        ParserExpr::Recover(node, sync) => {
+            Expr::Recover(Box::new(convert_node(*node)), Box::new(convert_node(*sync)))
+        }
     }
 }
 
@@ -177,7 +191,9 @@ pub fn consume_rules(pairs: Pairs<Rule>) -> Result<Vec<AstRule>, Vec<Error<Rule>
     }
 }
 
-fn consume_rules_with_spans(pairs: Pairs<Rule>) -> Result<Vec<ParserRule>, Vec<Error<Rule>>> {
+pub(crate) fn consume_rules_with_spans(
+    pairs: Pairs<Rule>,
+) -> Result<Vec<ParserRule>, Vec<Error<Rule>>> {
     let climber = PrecClimber::new(vec![
         Operator::new(Rule::choice_operator, Assoc::Left),
         Operator::new(Rule::sequence_operator, Assoc::Left),
@@ -274,6 +290,22 @@ fn consume_expr(
                             span: start.span(&end),
                         }
                     }
+                    Rule::_recover => {
+                        let start = pair.clone().as_span().start_pos();
+                        let mut pairs = pair.into_inner();
+                        pairs.next().unwrap(); // opening_paren
+                        let pair = pairs.next().unwrap();
+                        let node = consume_expr(pair.into_inner().peekable(), climber)?;
+                        pairs.next().unwrap(); // comma
+                        let pair = pairs.next().unwrap();
+                        let sync = consume_expr(pair.into_inner().peekable(), climber)?;
+                        let end = sync.span.end_pos();
+
+                        ParserNode {
+                            expr: ParserExpr::Recover(Box::new(node), Box::new(sync)),
+                            span: start.span(&end),
+                        }
+                    }
                     Rule::peek_slice => {
                         let mut pairs = pair.clone().into_inner();
                         pairs.next().unwrap(); // opening_brack
@@ -318,6 +350,13 @@ fn consume_expr(
                             span: pair.clone().as_span(),
                         }
                     }
+                    Rule::insensitive_unicode_string => {
+                        let string = unescape(pair.as_str()).expect("incorrect string literal");
+                        ParserNode {
+                            expr: ParserExpr::InsensUnicode(string[3..string.len() - 1].to_owned()),
+                            span: pair.clone().as_span(),
+                        }
+                    }
                     Rule::range => {
                         let mut pairs = pair.into_inner();
                         let pair = pairs.next().unwrap();
@@ -336,6 +375,30 @@ fn consume_expr(
                             span: start_pos.span(&end_pos),
                         }
                     }
+                    Rule::byte_string => {
+                        let string = pair.as_str();
+                        let bytes = unescape_bytes(&string[2..string.len() - 1])
+                            .expect("incorrect byte string literal");
+                        ParserNode {
+                            expr: ParserExpr::Bytes(bytes),
+                            span: pair.clone().as_span(),
+                        }
+                    }
+                    Rule::byte_range => {
+                        let mut pairs = pair.into_inner();
+                        let pair = pairs.next().unwrap();
+                        let start = unescape_byte(pair.as_str()).expect("incorrect byte literal");
+                        let start_pos = pair.clone().as_span().start_pos();
+                        pairs.next();
+                        let pair = pairs.next().unwrap();
+                        let end = unescape_byte(pair.as_str()).expect("incorrect byte literal");
+                        let end_pos = pair.clone().as_span().end_pos();
+
+                        ParserNode {
+                            expr: ParserExpr::ByteRange(start, end),
+                            span: start_pos.span(&end_pos),
+                        }
+                    }
                     _ => unreachable!(),
                 };
 
@@ -616,6 +679,39 @@ fn unescape(string: &str) -> Option<String> {
     }
 }
 
+// Unescapes the contents of a byte string which, like in Rust, may only contain ASCII characters
+// and escapes other than `\u`.
+fn unescape_bytes(string: &str) -> Option<Vec<u8>> {
+    let mut result = vec![];
+    let mut chars = string.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                'x' => {
+                    let code = chars.as_str().get(0..2)?;
+                    result.push(u8::from_str_radix(code, 16).ok()?);
+                    chars.nth(1);
+                }
+                'u' => return None,
+                c => result.push(unescape(&format!("\\{}", c))?.chars().next()? as u8),
+            },
+            c if c.is_ascii() => result.push(c as u8),
+            _ => return None,
+        }
+    }
+
+    Some(result)
+}
+
+// Unescapes a byte literal such as `b'\x00'`.
+fn unescape_byte(string: &str) -> Option<u8> {
+    match *unescape_bytes(&string[2..string.len() - 1])? {
+        [byte] => Some(byte),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::unwrap_or_report;
@@ -828,6 +924,36 @@ mod tests {
         };
     }
 
+    #[test]
+    fn recover() {
+        parses_to! {
+            parser: PestParser,
+            input: Arc::from("RECOVER(a, \";\")"),
+            rule: Rule::_recover,
+            tokens: [
+                _recover(0, 15, [
+                    opening_paren(7, 8),
+                    expression(8, 9, [
+                        term(8, 9, [
+                            identifier(8, 9)
+                        ])
+                    ]),
+                    comma(9, 10),
+                    expression(11, 14, [
+                        term(11, 14, [
+                            string(11, 14, [
+                                quote(11, 12),
+                                inner_str(12, 13),
+                                quote(13, 14)
+                            ])
+                        ])
+                    ]),
+                    closing_paren(14, 15)
+                ])
+            ]
+        };
+    }
+
     #[test]
     fn peek_slice_all() {
         parses_to! {
@@ -942,6 +1068,24 @@ mod tests {
         };
     }
 
+    #[test]
+    fn insensitive_unicode_string() {
+        parses_to! {
+            parser: PestParser,
+            input: Arc::from("^u\"straße\""),
+            rule: Rule::insensitive_unicode_string,
+            tokens: [
+                insensitive_unicode_string(0, 11, [
+                    string(2, 11, [
+                        quote(2, 3),
+                        inner_str(3, 10),
+                        quote(10, 11)
+                    ])
+                ])
+            ]
+        };
+    }
+
     #[test]
     fn range() {
         parses_to! {
@@ -966,6 +1110,46 @@ mod tests {
         };
     }
 
+    #[test]
+    fn byte_string() {
+        parses_to! {
+            parser: PestParser,
+            input: Arc::from("b\"\\x89PNG\""),
+            rule: Rule::byte_string,
+            tokens: [
+                byte_string(0, 10, [
+                    quote(1, 2),
+                    inner_str(2, 9),
+                    quote(9, 10)
+                ])
+            ]
+        };
+    }
+
+    #[test]
+    fn byte_range() {
+        parses_to! {
+            parser: PestParser,
+            input: Arc::from("b'\\0' .. b'\\xff'"),
+            rule: Rule::byte_range,
+            tokens: [
+                byte_range(0, 16, [
+                    byte(0, 5, [
+                        single_quote(1, 2),
+                        inner_chr(2, 4),
+                        single_quote(4, 5)
+                    ]),
+                    range_operator(6, 8),
+                    byte(9, 16, [
+                        single_quote(10, 11),
+                        inner_chr(11, 15),
+                        single_quote(15, 16)
+                    ])
+                ])
+            ]
+        };
+    }
+
     #[test]
     fn character() {
         parses_to! {
@@ -1143,9 +1327,13 @@ mod tests {
                 Rule::positive_predicate_operator,
                 Rule::negative_predicate_operator,
                 Rule::_push,
+                Rule::_recover,
                 Rule::peek_slice,
                 Rule::identifier,
                 Rule::insensitive_string,
+                Rule::insensitive_unicode_string,
+                Rule::byte_string,
+                Rule::byte,
                 Rule::quote,
                 Rule::single_quote
             ],
@@ -1262,6 +1450,75 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ast_recover() {
+        let input: Arc<str> = Arc::from("rule = { RECOVER(a ~ \";\", \";\" | \"}\") }");
+
+        let pairs = PestParser::parse(Rule::grammar_rules, input).unwrap();
+        let ast = consume_rules_with_spans(pairs).unwrap();
+        let ast: Vec<_> = ast.into_iter().map(|rule| convert_rule(rule)).collect();
+
+        assert_eq!(
+            ast,
+            vec![AstRule {
+                name: "rule".to_owned(),
+                ty: RuleType::Normal,
+                expr: Expr::Recover(
+                    Box::new(Expr::Seq(
+                        Box::new(Expr::Ident("a".to_owned())),
+                        Box::new(Expr::Str(";".to_owned()))
+                    )),
+                    Box::new(Expr::Choice(
+                        Box::new(Expr::Str(";".to_owned())),
+                        Box::new(Expr::Str("}".to_owned()))
+                    ))
+                )
+            },]
+        );
+    }
+
+    #[test]
+    fn ast_bytes() {
+        let input: Arc<str> = Arc::from("rule = { b\"\\xCA\\xFE\" ~ b'\\x01'..b'\\x7F' }");
+
+        let pairs = PestParser::parse(Rule::grammar_rules, input).unwrap();
+        let ast = consume_rules_with_spans(pairs).unwrap();
+        let ast: Vec<_> = ast.into_iter().map(|rule| convert_rule(rule)).collect();
+
+        assert_eq!(
+            ast,
+            vec![AstRule {
+                name: "rule".to_owned(),
+                ty: RuleType::Normal,
+                expr: Expr::Seq(
+                    Box::new(Expr::Bytes(vec![0xCA, 0xFE])),
+                    Box::new(Expr::ByteRange(0x01, 0x7F)),
+                )
+            }],
+        );
+    }
+
+    #[test]
+    fn ast_insensitive_unicode() {
+        let input: Arc<str> = Arc::from("rule = { ^u\"ß\" ~ ^\"ss\" }");
+
+        let pairs = PestParser::parse(Rule::grammar_rules, input).unwrap();
+        let ast = consume_rules_with_spans(pairs).unwrap();
+        let ast: Vec<_> = ast.into_iter().map(|rule| convert_rule(rule)).collect();
+
+        assert_eq!(
+            ast,
+            vec![AstRule {
+                name: "rule".to_owned(),
+                ty: RuleType::Normal,
+                expr: Expr::Seq(
+                    Box::new(Expr::InsensUnicode("ß".to_owned())),
+                    Box::new(Expr::Insens("ss".to_owned()))
+                ),
+            }]
+        );
+    }
+
     #[test]
     fn ast_peek_slice() {
         let input: Arc<str> = Arc::from("rule = _{ PEEK[-04..] ~ PEEK[..3] }");
@@ -1487,4 +1744,32 @@ mod tests {
 
         assert_eq!(unescape(string), None);
     }
+
+    #[test]
+    fn unescape_bytes_all() {
+        let string = r"a\x00\xFF\n\\x";
+
+        assert_eq!(unescape_bytes(string), Some(b"a\x00\xFF\n\\x".to_vec()));
+    }
+
+    #[test]
+    fn unescape_bytes_non_ascii() {
+        let string = "\u{e9}";
+
+        assert_eq!(unescape_bytes(string), None);
+    }
+
+    #[test]
+    fn unescape_bytes_unicode() {
+        let string = r"\u{61}";
+
+        assert_eq!(unescape_bytes(string), None);
+    }
+
+    #[test]
+    fn unescape_byte_too_long() {
+        let string = r"b'ab'";
+
+        assert_eq!(unescape_byte(string), None);
+    }
 }
@@ -84,6 +84,7 @@ pub fn validate_pairs(pairs: Pairs<Rule>) -> Result<Vec<Span>, Vec<Error<Rule>>>
     pest_keywords.insert("POP");
     pest_keywords.insert("POP_ALL");
     pest_keywords.insert("PUSH");
+    pest_keywords.insert("RECOVER");
     pest_keywords.insert("SOI");
 
     let mut builtins = HashSet::new();
@@ -125,6 +126,15 @@ pub fn validate_pairs(pairs: Pairs<Rule>) -> Result<Vec<Span>, Vec<Error<Rule>>>
         })
         .collect();
 
+    // `ERROR` is only generated, and therefore only reserved, in grammars that use `RECOVER`.
+    let uses_recover = pairs
+        .clone()
+        .flatten()
+        .any(|pair| pair.as_rule() == Rule::_recover);
+    if uses_recover {
+        pest_keywords.insert("ERROR");
+    }
+
     let mut errors = vec![];
 
     errors.extend(validate_rust_keywords(&definitions, &rust_keywords));
@@ -251,6 +261,7 @@ pub fn validate_ast<'a>(rules: &'a Vec<ParserRule>) -> Vec<Error<Rule>> {
     errors.extend(validate_repetition(rules));
     errors.extend(validate_choices(rules));
     errors.extend(validate_whitespace_comment(rules));
+    #[cfg(not(feature = "left-recursion"))]
     errors.extend(validate_left_recursion(rules));
 
     errors.sort_by_key(|error| match error.location {
@@ -268,6 +279,7 @@ fn is_non_progressing(
 ) -> bool {
     match *expr {
         ParserExpr::Str(ref string) => string.is_empty(),
+        ParserExpr::Bytes(ref bytes) => bytes.is_empty(),
         ParserExpr::Ident(ref ident) => {
             if ident == "soi" || ident == "eoi" {
                 return true;
@@ -287,6 +299,7 @@ fn is_non_progressing(
         }
         ParserExpr::PosPred(_) => true,
         ParserExpr::NegPred(_) => true,
+        ParserExpr::Recover(ref node, _) => is_non_progressing(&node.expr, rules, trace),
         ParserExpr::Seq(ref lhs, ref rhs) => {
             is_non_progressing(&lhs.expr, rules, trace)
                 && is_non_progressing(&rhs.expr, rules, trace)
@@ -306,6 +319,7 @@ fn is_non_failing(
 ) -> bool {
     match *expr {
         ParserExpr::Str(ref string) => string.is_empty(),
+        ParserExpr::Bytes(ref bytes) => bytes.is_empty(),
         ParserExpr::Ident(ref ident) => {
             if !trace.contains(ident) {
                 if let Some(node) = rules.get(ident) {
@@ -321,6 +335,7 @@ fn is_non_failing(
         }
         ParserExpr::Opt(_) => true,
         ParserExpr::Rep(_) => true,
+        ParserExpr::Recover(ref node, _) => is_non_failing(&node.expr, rules, trace),
         ParserExpr::Seq(ref lhs, ref rhs) => {
             is_non_failing(&lhs.expr, rules, trace) && is_non_failing(&rhs.expr, rules, trace)
         }
@@ -449,6 +464,8 @@ fn validate_whitespace_comment<'a>(rules: &'a [ParserRule]) -> Vec<Error<Rule>>
         .collect()
 }
 
+// Only used without the `left-recursion` feature, but always compiled so that it is tested.
+#[cfg_attr(feature = "left-recursion", allow(dead_code))]
 fn validate_left_recursion<'a>(rules: &'a [ParserRule]) -> Vec<Error<Rule>> {
     left_recursion(to_hash_map(rules))
 }
@@ -515,6 +532,7 @@ fn left_recursion<'a>(rules: HashMap<String, &'a ParserNode>) -> Vec<Error<Rule>
             ParserExpr::PosPred(ref node) => check_expr(&node, rules, trace),
             ParserExpr::NegPred(ref node) => check_expr(&node, rules, trace),
             ParserExpr::Push(ref node) => check_expr(&node, rules, trace),
+            ParserExpr::Recover(ref node, _) => check_expr(node, rules, trace),
             _ => None,
         }
     }
@@ -534,7 +552,7 @@ fn left_recursion<'a>(rules: HashMap<String, &'a ParserNode>) -> Vec<Error<Rule>
 
 #[cfg(test)]
 mod tests {
-    use super::super::parser::{consume_rules, PestParser};
+    use super::super::parser::{consume_rules, consume_rules_with_spans, PestParser};
     use super::super::unwrap_or_report;
     use super::*;
     use pest::Parser;
@@ -574,6 +592,30 @@ mod tests {
     #[test]
     #[should_panic(expected = "grammar error
 
+ --> 1:1
+  |
+1 | ERROR = { \"e\" } a = { RECOVER(ERROR, \"a\") }
+  | ^---^
+  |
+  = ERROR is a pest keyword")]
+    fn error_keyword_with_recover() {
+        let input: Arc<str> = Arc::from("ERROR = { \"e\" } a = { RECOVER(ERROR, \"a\") }");
+        unwrap_or_report(validate_pairs(
+            PestParser::parse(Rule::grammar_rules, input).unwrap(),
+        ));
+    }
+
+    #[test]
+    fn error_without_recover() {
+        let input: Arc<str> = Arc::from("ERROR = { \"e\" }");
+        unwrap_or_report(validate_pairs(
+            PestParser::parse(Rule::grammar_rules, input).unwrap(),
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "grammar error
+
  --> 1:13
   |
 1 | a = { \"a\" } a = { \"a\" }
@@ -733,10 +775,7 @@ mod tests {
   |
   = rule a is left-recursive (a -> a); pest::prec_climber might be useful in this case")]
     fn simple_left_recursion() {
-        let input: Arc<str> = Arc::from("a = { a }");
-        unwrap_or_report(consume_rules(
-            PestParser::parse(Rule::grammar_rules, input).unwrap(),
-        ));
+        report_left_recursion("a = { a }");
     }
 
     #[test]
@@ -756,10 +795,7 @@ mod tests {
   |
   = rule a is left-recursive (a -> b -> a); pest::prec_climber might be useful in this case")]
     fn indirect_left_recursion() {
-        let input: Arc<str> = Arc::from("a = { b } b = { a }");
-        unwrap_or_report(consume_rules(
-            PestParser::parse(Rule::grammar_rules, input).unwrap(),
-        ));
+        report_left_recursion("a = { b } b = { a }");
     }
 
     #[test]
@@ -772,10 +808,7 @@ mod tests {
   |
   = rule a is left-recursive (a -> a); pest::prec_climber might be useful in this case")]
     fn non_failing_left_recursion() {
-        let input: Arc<str> = Arc::from("a = { \"\" ~ \"a\"? ~ \"a\"* ~ (\"a\" | \"\") ~ a }");
-        unwrap_or_report(consume_rules(
-            PestParser::parse(Rule::grammar_rules, input).unwrap(),
-        ));
+        report_left_recursion("a = { \"\" ~ \"a\"? ~ \"a\"* ~ (\"a\" | \"\") ~ a }");
     }
 
     #[test]
@@ -788,10 +821,34 @@ mod tests {
   |
   = rule a is left-recursive (a -> a); pest::prec_climber might be useful in this case")]
     fn non_primary_choice_left_recursion() {
-        let input: Arc<str> = Arc::from("a = { \"a\" | a }");
-        unwrap_or_report(consume_rules(
-            PestParser::parse(Rule::grammar_rules, input).unwrap(),
-        ));
+        report_left_recursion("a = { \"a\" | a }");
+    }
+
+    // Reports the errors of `validate_left_recursion`, which only runs without the
+    // `left-recursion` feature, so that they are tested either way.
+    fn report_left_recursion(input: &str) {
+        let pairs = PestParser::parse(Rule::grammar_rules, Arc::from(input)).unwrap();
+        let rules = unwrap_or_report(consume_rules_with_spans(pairs));
+        let mut errors = validate_left_recursion(&rules);
+        errors.sort_by_key(|error| match error.location {
+            InputLocation::Span(span) => span,
+            _ => unreachable!(),
+        });
+
+        unwrap_or_report(if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "left-recursion")]
+    fn left_recursion() {
+        let input: Arc<str> = Arc::from("a = { b ~ \"a\" | \"a\" } b = { a }");
+        let rules = consume_rules(PestParser::parse(Rule::grammar_rules, input).unwrap());
+
+        assert_eq!(rules.unwrap().len(), 2);
     }
 
     #[test]
@@ -0,0 +1,392 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Types for parsing byte inputs which need not be valid UTF-8.
+//!
+//! A byte input is parsed as the `&str` where every byte stands for the `char` of the same value,
+//! so that the whole of the parsing machinery applies to it. Literals and character ranges match
+//! their UTF-8 encoding, `ANY` matches a single byte, and byte literals and ranges, like
+//! `b"\x89PNG"` and `b'\x00'..b'\x7F'`, match bytes of any value. The positions, spans and pairs
+//! of this module translate the result back to offsets in, and slices of, the original bytes.
+//!
+//! Errors are located at offsets in the bytes too. The lines they show are decoded from UTF-8,
+//! with invalid sequences replaced by `U+FFFD`, and their columns count `char`s of those lines.
+
+use alloc::vec::Vec;
+use std::fmt;
+use std::sync::Arc;
+
+use error::{Error, InputLocation};
+use iterators::{Pair, Pairs};
+use parser_config::ParserConfig;
+use parser_state::{self, ParseResult, ParserState};
+use position::Position;
+use span::Span;
+use RuleType;
+
+/// Bytes along with the `&str` they are parsed as.
+pub(crate) struct ByteInput {
+    bytes: Arc<[u8]>,
+    text: Arc<str>,
+    // Offsets in `text` of the `char`s standing for bytes above 0x7F, which take two bytes.
+    wide: Vec<usize>,
+}
+
+impl ByteInput {
+    pub(crate) fn new(bytes: Arc<[u8]>) -> ByteInput {
+        let mut text = alloc::string::String::with_capacity(bytes.len());
+        let mut wide = Vec::new();
+
+        for &byte in bytes.iter() {
+            if byte > 0x7F {
+                wide.push(text.len());
+            }
+            text.push(char::from(byte));
+        }
+
+        ByteInput {
+            bytes,
+            text: Arc::from(text),
+            wide,
+        }
+    }
+
+    pub(crate) fn text(&self) -> &Arc<str> {
+        &self.text
+    }
+
+    // Translates an offset in `text` to one in `bytes`.
+    fn offset(&self, pos: usize) -> usize {
+        pos - self.wide.partition_point(|&wide| wide < pos)
+    }
+
+    // Translates the location of an error from `text` to `bytes`, and its lines to the UTF-8
+    // they encode.
+    pub(crate) fn error<R: RuleType>(&self, mut error: Error<R>) -> Error<R> {
+        error.location = match error.location {
+            InputLocation::Pos(pos) => InputLocation::Pos(self.offset(pos)),
+            InputLocation::Span((start, end)) => {
+                InputLocation::Span((self.offset(start), self.offset(end)))
+            }
+        };
+
+        error.decode_latin1_lines()
+    }
+}
+
+impl fmt::Debug for ByteInput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ByteInput")
+            .field("bytes", &self.bytes)
+            .finish()
+    }
+}
+
+/// Parses `input` as bytes, handing the `&str` standing for them to `f`.
+pub(crate) fn parse<R: RuleType, F>(
+    input: Arc<[u8]>,
+    config: ParserConfig<R>,
+    f: F,
+) -> Result<BytePairs<R>, Error<R>>
+where
+    F: FnOnce(Arc<str>, ParserConfig<R>) -> Result<Pairs<R>, Error<R>>,
+{
+    let input = Arc::new(ByteInput::new(input));
+
+    match f(input.text().clone(), config.with_byte_input()) {
+        Ok(pairs) => Ok(BytePairs { input, pairs }),
+        Err(error) => Err(input.error(error)),
+    }
+}
+
+/// Creates a `ParserState` over bytes configured with `config`, supplying it to a closure `f`.
+///
+/// # Examples
+///
+/// ```
+/// # use pest;
+/// # use pest::ParserConfig;
+/// # use std::sync::Arc;
+/// let input: Arc<[u8]> = Arc::from(&b"\xFF\x00"[..]);
+/// let pairs = pest::state_bytes::<(), _>(input, ParserConfig::new(), |s| {
+///     s.match_bytes(b"\xFF\x00")
+/// })
+/// .unwrap();
+/// ```
+pub fn state_bytes<R: RuleType, F>(
+    input: Arc<[u8]>,
+    config: ParserConfig<R>,
+    f: F,
+) -> Result<BytePairs<R>, Error<R>>
+where
+    F: FnOnce(Box<ParserState<R>>) -> ParseResult<Box<ParserState<R>>>,
+{
+    parse(input, config, |input, config| {
+        parser_state::state_with_config(input, config, f)
+    })
+}
+
+/// A position in a byte input.
+#[derive(Clone)]
+pub struct BytePosition {
+    input: Arc<ByteInput>,
+    position: Position,
+}
+
+impl BytePosition {
+    /// Returns the offset of the `BytePosition` in the input.
+    pub fn pos(&self) -> usize {
+        self.input.offset(self.position.pos())
+    }
+
+    /// Returns the line and column number of the `BytePosition`, lines being separated by `\n`
+    /// and columns counted in bytes.
+    pub fn line_col(&self) -> (usize, usize) {
+        self.position.line_col()
+    }
+}
+
+impl fmt::Debug for BytePosition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BytePosition")
+            .field("pos", &self.pos())
+            .finish()
+    }
+}
+
+impl PartialEq for BytePosition {
+    fn eq(&self, other: &BytePosition) -> bool {
+        self.position == other.position
+    }
+}
+
+impl Eq for BytePosition {}
+
+/// A span over a byte input.
+#[derive(Clone)]
+pub struct ByteSpan {
+    input: Arc<ByteInput>,
+    span: Span,
+}
+
+impl ByteSpan {
+    /// Returns the offset of the start of the `ByteSpan` in the input.
+    pub fn start(&self) -> usize {
+        self.input.offset(self.span.start())
+    }
+
+    /// Returns the offset of the end of the `ByteSpan` in the input.
+    pub fn end(&self) -> usize {
+        self.input.offset(self.span.end())
+    }
+
+    /// Returns the `BytePosition` at the start of the `ByteSpan`.
+    pub fn start_pos(&self) -> BytePosition {
+        BytePosition {
+            input: self.input.clone(),
+            position: self.span.start_pos(),
+        }
+    }
+
+    /// Returns the `BytePosition` at the end of the `ByteSpan`.
+    pub fn end_pos(&self) -> BytePosition {
+        BytePosition {
+            input: self.input.clone(),
+            position: self.span.end_pos(),
+        }
+    }
+
+    /// Returns the bytes of the `ByteSpan`.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.input.bytes[self.start()..self.end()]
+    }
+}
+
+impl fmt::Debug for ByteSpan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ByteSpan")
+            .field("bytes", &self.as_bytes())
+            .field("start", &self.start())
+            .field("end", &self.end())
+            .finish()
+    }
+}
+
+impl PartialEq for ByteSpan {
+    fn eq(&self, other: &ByteSpan) -> bool {
+        self.span == other.span
+    }
+}
+
+impl Eq for ByteSpan {}
+
+/// A matching pair of tokens over a byte input, like a [`Pair`].
+///
+/// [`Pair`]: ../iterators/struct.Pair.html
+#[derive(Clone)]
+pub struct BytePair<R> {
+    input: Arc<ByteInput>,
+    pair: Pair<R>,
+}
+
+impl<R: RuleType> BytePair<R> {
+    /// Returns the `Rule` of the `BytePair`.
+    pub fn as_rule(&self) -> R {
+        self.pair.as_rule()
+    }
+
+    /// Returns the bytes matched by the `BytePair`.
+    pub fn as_bytes(&self) -> &[u8] {
+        let span = self.pair.as_span();
+
+        &self.input.bytes[self.input.offset(span.start())..self.input.offset(span.end())]
+    }
+
+    /// Returns the `ByteSpan` matched by the `BytePair`.
+    pub fn as_span(&self) -> ByteSpan {
+        ByteSpan {
+            input: self.input.clone(),
+            span: self.pair.as_span(),
+        }
+    }
+
+    /// Returns the inner `BytePairs` between the `BytePair`, consuming it.
+    pub fn into_inner(self) -> BytePairs<R> {
+        BytePairs {
+            input: self.input,
+            pairs: self.pair.into_inner(),
+        }
+    }
+}
+
+impl<R: RuleType> fmt::Debug for BytePair<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BytePair")
+            .field("rule", &self.as_rule())
+            .field("span", &self.as_span())
+            .field("inner", &self.clone().into_inner().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// An iterator over [`BytePair`]s, like [`Pairs`].
+///
+/// [`BytePair`]: struct.BytePair.html
+/// [`Pairs`]: ../iterators/struct.Pairs.html
+#[derive(Clone)]
+pub struct BytePairs<R> {
+    input: Arc<ByteInput>,
+    pairs: Pairs<R>,
+}
+
+impl<R: RuleType> BytePairs<R> {
+    /// Returns the next `BytePair`, if any, without consuming it.
+    pub fn peek(&self) -> Option<BytePair<R>> {
+        self.pairs.peek().map(|pair| BytePair {
+            input: self.input.clone(),
+            pair,
+        })
+    }
+}
+
+impl<R: RuleType> Iterator for BytePairs<R> {
+    type Item = BytePair<R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pair = self.pairs.next()?;
+
+        Some(BytePair {
+            input: self.input.clone(),
+            pair,
+        })
+    }
+}
+
+impl<R: RuleType> DoubleEndedIterator for BytePairs<R> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let pair = self.pairs.next_back()?;
+
+        Some(BytePair {
+            input: self.input.clone(),
+            pair,
+        })
+    }
+}
+
+impl<R: RuleType> fmt::Debug for BytePairs<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets() {
+        let input = ByteInput::new(Arc::from(&b"\xFFa\x80\x80b"[..]));
+
+        assert_eq!(&*input.text, "\u{FF}a\u{80}\u{80}b");
+        assert_eq!(input.offset(0), 0);
+        assert_eq!(input.offset(2), 1);
+        assert_eq!(input.offset(3), 2);
+        assert_eq!(input.offset(5), 3);
+        assert_eq!(input.offset(7), 4);
+        assert_eq!(input.offset(8), 5);
+    }
+
+    #[test]
+    fn state() {
+        let input: Arc<[u8]> = Arc::from(&b"\x01\xFFhi\xC3\xA9\x00"[..]);
+        let pairs = state_bytes(input, ParserConfig::new(), |state| {
+            state.sequence(|s| {
+                s.rule("tag", |s| s.match_byte_range(0x01..0x7F))
+                    .and_then(|s| s.rule("byte", |s| s.match_bytes(b"\xFF")))
+                    .and_then(|s| s.rule("text", |s| s.match_insensitive("HIé")))
+                    .and_then(|s| s.match_bytes(b"\x00"))
+                    .and_then(|s| s.end_of_input())
+            })
+        })
+        .unwrap();
+
+        let pairs: Vec<_> = pairs
+            .map(|pair| {
+                (
+                    pair.as_rule(),
+                    pair.as_span().start(),
+                    pair.as_bytes().to_vec(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            pairs,
+            vec![
+                ("tag", 0, b"\x01".to_vec()),
+                ("byte", 1, b"\xFF".to_vec()),
+                ("text", 2, b"hi\xC3\xA9".to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn error() {
+        let input: Arc<[u8]> = Arc::from(&b"\xFF\xFEa"[..]);
+        let error = state_bytes(input, ParserConfig::new(), |state| {
+            state
+                .match_bytes(b"\xFF\xFE")
+                .and_then(|s| s.rule("b", |s| s.match_string("b")))
+        })
+        .unwrap_err();
+
+        assert_eq!(error.location, InputLocation::Pos(2));
+        assert_eq!(error.line_col, ::error::LineColLocation::Pos((1, 3)));
+    }
+}
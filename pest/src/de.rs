@@ -0,0 +1,673 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! A serde [`Deserializer`] over pairs, which deserializes formats written in pest directly into
+//! Rust types.
+//!
+//! [`Deserializer`]: struct.Deserializer.html
+
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use std::fmt;
+use std::str::Chars;
+
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+
+use error::{Error, ErrorVariant};
+use iterators::{Pair, Pairs};
+use span::Span;
+use RuleType;
+
+/// The shape in the serde data model that the pairs of a rule deserialize as.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Shape {
+    /// A map whose entries are the inner pairs, each with a key and a value as inner pairs. The
+    /// shapes of the entries themselves are not used.
+    Map,
+    /// A sequence whose elements are the inner pairs
+    Seq,
+    /// A string of the text of the pair, taken verbatim
+    String,
+    /// A string of the text of the pair without its first and last `char`, such as quotes
+    QuotedString,
+    /// A `QuotedString` whose escapes are replaced, with `\"`, `\\`, `\/`, `\b`, `\f`, `\n`,
+    /// `\r`, and `\t` as in JSON, `\uXXXX` with UTF-16 surrogate pairs, and `\UXXXXXXXX`
+    EscapedString,
+    /// A number parsed from the text of the pair, as a `u64`, `i64`, or `f64`
+    Number,
+    /// A `bool` of the text of the pair, which must be `true` or `false`
+    Bool,
+    /// The unit, or `None` when deserializing an `Option`
+    Unit,
+    /// Whatever the single inner pair deserializes as
+    Inner,
+    /// Not deserialized, leaving it out of maps, sequences, and `Inner`
+    Skip,
+}
+
+/// Deserializes a `T` from `pair`, with `shape` mapping rules to the shapes they deserialize as.
+/// Errors span the pair that failed to deserialize.
+///
+/// # Examples
+///
+/// ```
+/// # use pest;
+/// # use pest::de::{self, Shape};
+/// # use std::collections::HashMap;
+/// # use std::sync::Arc;
+/// # #[allow(non_camel_case_types)]
+/// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+/// # enum Rule { object, entry, key, number }
+/// // object = { (entry ~ ";")* }, entry = { key ~ "=" ~ number }
+/// let input: Arc<str> = Arc::from("a=1;b=2;");
+/// let pair = pest::state(input, |state| {
+///     state.rule(Rule::object, |s| {
+///         s.repeat(|s| {
+///             s.rule(Rule::entry, |s| {
+///                 s.rule(Rule::key, |s| s.match_range('a'..'z'))
+///                     .and_then(|s| s.match_string("="))
+///                     .and_then(|s| s.rule(Rule::number, |s| s.match_range('0'..'9')))
+///             })
+///             .and_then(|s| s.match_string(";"))
+///         })
+///     })
+/// })
+/// .unwrap()
+/// .next()
+/// .unwrap();
+///
+/// let object: HashMap<String, u32> = de::from_pair(pair, |rule| match rule {
+///     Rule::object => Shape::Map,
+///     Rule::entry => Shape::Seq,
+///     Rule::key => Shape::String,
+///     Rule::number => Shape::Number,
+/// })?;
+///
+/// assert_eq!(object["a"], 1);
+/// assert_eq!(object["b"], 2);
+/// # Ok::<(), pest::error::Error<Rule>>(())
+/// ```
+pub fn from_pair<T, R, F>(pair: Pair<R>, shape: F) -> Result<T, Error<R>>
+where
+    T: DeserializeOwned,
+    R: RuleType,
+    F: Fn(R) -> Shape,
+{
+    let span = pair.as_span();
+
+    T::deserialize(Deserializer::new(pair, &shape)).map_err(|error| error.into_error(span))
+}
+
+/// A serde `Deserializer` of a `Pair`, deserializing it as its rule's [`Shape`].
+///
+/// [`Shape`]: enum.Shape.html
+pub struct Deserializer<'s, R> {
+    pair: Pair<R>,
+    shape: &'s dyn Fn(R) -> Shape,
+}
+
+impl<'s, R: RuleType> Deserializer<'s, R> {
+    /// Creates a new `Deserializer` of `pair`, with `shape` mapping rules to their shapes.
+    pub fn new(pair: Pair<R>, shape: &'s dyn Fn(R) -> Shape) -> Deserializer<'s, R> {
+        Deserializer { pair, shape }
+    }
+
+    // Follows `Inner` shapes down to the pair that is actually deserialized.
+    fn resolve(self) -> Result<(Deserializer<'s, R>, Shape), DeError<R>> {
+        let mut deserializer = self;
+
+        loop {
+            let shape = (deserializer.shape)(deserializer.pair.as_rule());
+
+            if shape != Shape::Inner {
+                return Ok((deserializer, shape));
+            }
+
+            let mut inner = deserializer.children(deserializer.pair.clone());
+            deserializer.pair = match (inner.next(), inner.next()) {
+                (Some(pair), None) => pair,
+                _ => return Err(deserializer.error("expected exactly one inner pair")),
+            };
+        }
+    }
+
+    fn children(&self, pair: Pair<R>) -> Children<'s, R> {
+        Children {
+            pairs: pair.into_inner(),
+            shape: self.shape,
+        }
+    }
+
+    fn error(&self, message: &str) -> DeError<R> {
+        DeError::unlocated(message.to_string()).at(&self.pair.as_span())
+    }
+
+    fn deserialize_shape<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError<R>> {
+        let (deserializer, shape) = self.resolve()?;
+        let pair = deserializer.pair.clone();
+        let text = pair.as_str();
+
+        let result = match shape {
+            Shape::Map => visitor.visit_map(MapAccess {
+                entries: deserializer.children(pair.clone()),
+                value: None,
+            }),
+            Shape::Seq => visitor.visit_seq(SeqAccess {
+                elements: deserializer.children(pair.clone()),
+            }),
+            Shape::String => visitor.visit_str(text),
+            Shape::QuotedString => visitor.visit_str(unquote(text)),
+            Shape::EscapedString => match unescape(unquote(text)) {
+                Some(string) => visitor.visit_string(string),
+                None => return Err(deserializer.error("invalid escape")),
+            },
+            Shape::Number => {
+                if let Ok(number) = text.parse() {
+                    visitor.visit_u64(number)
+                } else if let Ok(number) = text.parse() {
+                    visitor.visit_i64(number)
+                } else if let Ok(number) = text.parse() {
+                    visitor.visit_f64(number)
+                } else {
+                    return Err(deserializer.error("invalid number"));
+                }
+            }
+            Shape::Bool => match text {
+                "true" => visitor.visit_bool(true),
+                "false" => visitor.visit_bool(false),
+                _ => return Err(deserializer.error("expected `true` or `false`")),
+            },
+            Shape::Unit => visitor.visit_unit(),
+            Shape::Inner | Shape::Skip => {
+                return Err(deserializer.error("pair cannot be deserialized"))
+            }
+        };
+
+        result.map_err(|error| error.at(&pair.as_span()))
+    }
+}
+
+impl<'de, 's, R: RuleType> de::Deserializer<'de> for Deserializer<'s, R> {
+    type Error = DeError<R>;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_shape(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let (deserializer, shape) = self.resolve()?;
+        let span = deserializer.pair.as_span();
+
+        match shape {
+            Shape::Unit => visitor.visit_none(),
+            _ => visitor.visit_some(deserializer),
+        }
+        .map_err(|error| error.at(&span))
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let span = self.pair.as_span();
+
+        visitor
+            .visit_newtype_struct(self)
+            .map_err(|error| error.at(&span))
+    }
+
+    // Enums are either strings naming unit variants, or maps from a variant to its content.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let (deserializer, shape) = self.resolve()?;
+        let pair = deserializer.pair.clone();
+
+        let result = match shape {
+            Shape::String | Shape::QuotedString | Shape::EscapedString => {
+                let variant: String = de::Deserialize::deserialize(deserializer)?;
+                visitor.visit_enum(variant.into_deserializer())
+            }
+            Shape::Map => {
+                let mut entries = deserializer.children(pair.clone());
+                let entry = match (entries.next(), entries.next()) {
+                    (Some(entry), None) => entry,
+                    _ => return Err(deserializer.error("expected a map with a single entry")),
+                };
+                let (key, value) = entries.entry(entry)?;
+
+                visitor.visit_enum(EnumAccess {
+                    key: Deserializer::new(key, deserializer.shape),
+                    value: Deserializer::new(value, deserializer.shape),
+                })
+            }
+            _ => return Err(deserializer.error("expected a string or a map")),
+        };
+
+        result.map_err(|error| error.at(&pair.as_span()))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+// Strips the first and last `char` of `text`.
+fn unquote(text: &str) -> &str {
+    let mut chars = text.chars();
+    chars.next();
+    chars.next_back();
+    chars.as_str()
+}
+
+// Replaces the escapes of an `EscapedString`, returning `None` if one is invalid.
+fn unescape(text: &str) -> Option<String> {
+    fn hex(chars: &mut Chars, digits: usize) -> Option<u32> {
+        let string = chars.as_str().get(..digits)?;
+        if !string.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return None;
+        }
+        let code = u32::from_str_radix(string, 16).ok()?;
+        *chars = chars.as_str()[digits..].chars();
+
+        Some(code)
+    }
+
+    let mut string = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            string.push(c);
+            continue;
+        }
+
+        let c = match chars.next()? {
+            '"' => '"',
+            '\\' => '\\',
+            '/' => '/',
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' => {
+                let code = hex(&mut chars, 4)?;
+
+                if (0xD800..0xDC00).contains(&code) {
+                    if !chars.as_str().starts_with("\\u") {
+                        return None;
+                    }
+                    chars.nth(1);
+
+                    let low = hex(&mut chars, 4)?;
+                    if !(0xDC00..0xE000).contains(&low) {
+                        return None;
+                    }
+
+                    char::from_u32(0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00))?
+                } else {
+                    char::from_u32(code)?
+                }
+            }
+            'U' => char::from_u32(hex(&mut chars, 8)?)?,
+            _ => return None,
+        };
+
+        string.push(c);
+    }
+
+    Some(string)
+}
+
+// The inner pairs of a pair which are not skipped.
+struct Children<'s, R> {
+    pairs: Pairs<R>,
+    shape: &'s dyn Fn(R) -> Shape,
+}
+
+impl<'s, R: RuleType> Children<'s, R> {
+    // Splits an entry of a map into its key and value.
+    fn entry(&self, entry: Pair<R>) -> Result<(Pair<R>, Pair<R>), DeError<R>> {
+        let span = entry.as_span();
+        let mut inner = Children {
+            pairs: entry.into_inner(),
+            shape: self.shape,
+        };
+
+        match (inner.next(), inner.next(), inner.next()) {
+            (Some(key), Some(value), None) => Ok((key, value)),
+            _ => Err(DeError::unlocated("expected a key and a value".to_string()).at(&span)),
+        }
+    }
+}
+
+impl<'s, R: RuleType> Iterator for Children<'s, R> {
+    type Item = Pair<R>;
+
+    fn next(&mut self) -> Option<Pair<R>> {
+        let shape = self.shape;
+
+        self.pairs
+            .by_ref()
+            .find(|pair| shape(pair.as_rule()) != Shape::Skip)
+    }
+}
+
+struct SeqAccess<'s, R> {
+    elements: Children<'s, R>,
+}
+
+impl<'de, 's, R: RuleType> de::SeqAccess<'de> for SeqAccess<'s, R> {
+    type Error = DeError<R>;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        match self.elements.next() {
+            Some(pair) => seed
+                .deserialize(Deserializer::new(pair, self.elements.shape))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+struct MapAccess<'s, R> {
+    entries: Children<'s, R>,
+    value: Option<Pair<R>>,
+}
+
+impl<'de, 's, R: RuleType> de::MapAccess<'de> for MapAccess<'s, R> {
+    type Error = DeError<R>;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let entry = match self.entries.next() {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        let (key, value) = self.entries.entry(entry)?;
+        self.value = Some(value);
+
+        seed.deserialize(Deserializer::new(key, self.entries.shape))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let value = self
+            .value
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+
+        seed.deserialize(Deserializer::new(value, self.entries.shape))
+    }
+}
+
+struct EnumAccess<'s, R> {
+    key: Deserializer<'s, R>,
+    value: Deserializer<'s, R>,
+}
+
+impl<'de, 's, R: RuleType> de::EnumAccess<'de> for EnumAccess<'s, R> {
+    type Error = DeError<R>;
+    type Variant = Deserializer<'s, R>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        Ok((seed.deserialize(self.key)?, self.value))
+    }
+}
+
+impl<'de, 's, R: RuleType> de::VariantAccess<'de> for Deserializer<'s, R> {
+    type Error = DeError<R>;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        de::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _: usize, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_shape(visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_shape(visitor)
+    }
+}
+
+/// An error of a [`Deserializer`], which spans the pair that failed to deserialize once it
+/// leaves the `Deserializer`.
+///
+/// [`Deserializer`]: struct.Deserializer.html
+#[derive(Debug)]
+pub struct DeError<R> {
+    message: String,
+    // Boxed to keep results small
+    error: Option<Box<Error<R>>>,
+}
+
+impl<R: RuleType> DeError<R> {
+    /// Returns the `Error` spanning the pair that failed to deserialize, or spanning `span` if
+    /// the error did not come from a pair.
+    pub fn into_error(self, span: Span) -> Error<R> {
+        match self.error {
+            Some(error) => *error,
+            None => *DeError::<R>::unlocated(self.message)
+                .at(&span)
+                .error
+                .unwrap(),
+        }
+    }
+
+    fn unlocated(message: String) -> DeError<R> {
+        DeError {
+            message,
+            error: None,
+        }
+    }
+
+    // Locates the error at `span`, unless it is already located at an inner pair.
+    fn at(self, span: &Span) -> DeError<R> {
+        if self.error.is_some() {
+            return self;
+        }
+
+        let error = Error::new_from_span(
+            ErrorVariant::CustomError {
+                message: self.message.clone(),
+            },
+            span.clone(),
+        );
+
+        DeError {
+            message: self.message,
+            error: Some(Box::new(error)),
+        }
+    }
+}
+
+impl<R: RuleType> fmt::Display for DeError<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.error {
+            Some(ref error) => write!(f, "{}", error),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl<R: RuleType> std::error::Error for DeError<R> {}
+
+impl<R: RuleType> de::Error for DeError<R> {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        DeError::unlocated(message.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use error::InputLocation;
+    use parser_state::{ParseResult, ParserState};
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    #[allow(non_camel_case_types)]
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    enum Rule {
+        map,
+        entry,
+        key,
+        number,
+        list,
+        comment,
+    }
+
+    fn shape(rule: Rule) -> Shape {
+        match rule {
+            Rule::map => Shape::Map,
+            Rule::entry => Shape::Seq,
+            Rule::key => Shape::String,
+            Rule::number => Shape::Number,
+            Rule::list => Shape::Seq,
+            Rule::comment => Shape::Skip,
+        }
+    }
+
+    fn word(state: Box<ParserState<Rule>>) -> ParseResult<Box<ParserState<Rule>>> {
+        state.repeat(|state| {
+            state.match_char_by(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+        })
+    }
+
+    // map = { (entry ~ ";")* }, entry = { key ~ "=" ~ (number | list) ~ comment? },
+    // list = { "[" ~ number ~ ("," ~ number)* ~ "]" }, comment = { "#" ~ word }
+    fn parse(input: &str) -> Pair<Rule> {
+        let input: Arc<str> = Arc::from(input);
+        let number = |state: Box<ParserState<Rule>>| state.rule(Rule::number, word);
+
+        ::state(input, |state| {
+            state.rule(Rule::map, |state| {
+                state.repeat(|state| {
+                    state
+                        .rule(Rule::entry, |state| {
+                            state
+                                .rule(Rule::key, word)
+                                .and_then(|state| state.match_string("="))
+                                .and_then(|state| {
+                                    state
+                                        .rule(Rule::list, |state| {
+                                            state
+                                                .match_string("[")
+                                                .and_then(number)
+                                                .and_then(|state| {
+                                                    state.repeat(|state| {
+                                                        state.match_string(",").and_then(number)
+                                                    })
+                                                })
+                                                .and_then(|state| state.match_string("]"))
+                                        })
+                                        .or_else(number)
+                                })
+                                .and_then(|state| {
+                                    state.optional(|state| {
+                                        state.rule(Rule::comment, |state| {
+                                            state.match_string("#").and_then(word)
+                                        })
+                                    })
+                                })
+                        })
+                        .and_then(|state| state.match_string(";"))
+                })
+            })
+        })
+        .unwrap()
+        .next()
+        .unwrap()
+    }
+
+    #[test]
+    fn map() {
+        let map: BTreeMap<String, Vec<f64>> =
+            from_pair(parse("a=[1,-2,0.5]#x;b=[3];"), shape).unwrap();
+
+        assert_eq!(map["a"], vec![1.0, -2.0, 0.5]);
+        assert_eq!(map["b"], vec![3.0]);
+    }
+
+    #[test]
+    fn error_spans_pair() {
+        let error =
+            from_pair::<BTreeMap<String, Vec<u8>>, _, _>(parse("a=[1,300];"), shape).unwrap_err();
+
+        assert_eq!(
+            error.variant.message(),
+            "invalid value: integer `300`, expected u8"
+        );
+        assert_eq!(error.location, InputLocation::Span((5, 8)));
+    }
+
+    #[test]
+    fn invalid_number() {
+        let error = from_pair::<BTreeMap<String, u8>, _, _>(parse("a=x;"), shape).unwrap_err();
+
+        assert_eq!(error.variant.message(), "invalid number");
+        assert_eq!(error.location, InputLocation::Span((2, 3)));
+    }
+
+    #[test]
+    fn unescape_escapes() {
+        assert_eq!(
+            unescape(r#"a\"b\\c\/\n\t\u00e9\ud83d\ude00\U0001F600"#),
+            Some("a\"b\\c/\n\t\u{e9}\u{1f600}\u{1f600}".to_string())
+        );
+        assert_eq!(unescape(r"\x"), None);
+        assert_eq!(unescape(r"\u12"), None);
+        assert_eq!(unescape(r"\ud83d"), None);
+        assert_eq!(unescape(r"\ud83d\u0041"), None);
+        assert_eq!(unescape("\\"), None);
+    }
+
+    #[test]
+    fn wrong_shape() {
+        let error = from_pair::<Vec<u8>, _, _>(parse("a=1;"), shape).unwrap_err();
+
+        assert_eq!(
+            error.variant.message(),
+            "invalid type: map, expected a sequence"
+        );
+        assert_eq!(error.location, InputLocation::Span((0, 4)));
+    }
+}
@@ -11,16 +11,24 @@
 
 use alloc::borrow::Cow;
 use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
 use alloc::format;
 use alloc::string::String;
 use alloc::string::ToString;
+use alloc::vec;
 use alloc::vec::Vec;
+use std::ascii;
 use std::cmp;
 use std::fmt;
 use std::mem;
 #[cfg(test)]
 use std::sync::Arc;
 
+#[cfg(feature = "pretty-print")]
+use serde::ser::SerializeStruct;
+#[cfg(feature = "pretty-print")]
+use serde_json::json;
+
 use position::Position;
 use span::Span;
 use RuleType;
@@ -34,13 +42,24 @@ pub struct Error<R> {
     pub location: InputLocation,
     /// Line/column within the input string
     pub line_col: LineColLocation,
+    details: Box<Details<R>>,
+}
+
+// Everything but the public fields of an `Error`, boxed since `Error` is returned by every parse.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct Details<R> {
     path: Option<String>,
     line: String,
     continued_line: Option<String>,
+    rule_stack: Vec<R>,
+    labels: Vec<Label>,
+    notes: Vec<String>,
+    help: Vec<String>,
 }
 
 /// Different kinds of parsing errors.
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
 pub enum ErrorVariant<R> {
     /// Generated parsing error with expected and unexpected `Rule`s
     ParsingError {
@@ -49,11 +68,70 @@ pub enum ErrorVariant<R> {
         /// Negative attempts
         negatives: Vec<R>,
     },
+    /// Generated parsing error which also lists the terminals that failed to match, produced when
+    /// [`ParserConfig::track_terminals`] is enabled
+    ///
+    /// [`ParserConfig::track_terminals`]: ../struct.ParserConfig.html#method.track_terminals
+    DetailedParsingError(Box<DetailedAttempts<R>>),
     /// Custom error with a message
     CustomError {
         /// Short explanation
         message: String,
     },
+    /// Parsing was aborted because a [`ParserConfig`] limit was exceeded
+    ///
+    /// [`ParserConfig`]: ../struct.ParserConfig.html
+    LimitError {
+        /// The limit which was exceeded
+        limit: Limit,
+    },
+    /// Parsing was aborted because it was cancelled through [`ParserConfig::cancel_on`] or
+    /// [`ParserConfig::cancel_if`]
+    ///
+    /// [`ParserConfig::cancel_on`]: ../struct.ParserConfig.html#method.cancel_on
+    /// [`ParserConfig::cancel_if`]: ../struct.ParserConfig.html#method.cancel_if
+    Cancelled,
+}
+
+/// The rules and terminals attempted at the position of an [`ErrorVariant::DetailedParsingError`].
+///
+/// [`ErrorVariant::DetailedParsingError`]: enum.ErrorVariant.html#variant.DetailedParsingError
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct DetailedAttempts<R> {
+    /// Positive attempts
+    pub positives: Vec<R>,
+    /// Negative attempts
+    pub negatives: Vec<R>,
+    /// Terminals attempted at the error position
+    pub terminals: Vec<Terminal>,
+}
+
+/// A terminal expression attempted by a `ParserState`.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Terminal {
+    /// String matched with `match_string`
+    Literal(String),
+    /// String matched case-insensitively with `match_insensitive`
+    Insensitive(String),
+    /// Character range matched with `match_range`, inclusive
+    Range(char, char),
+    /// Character matched by a predicate with `match_char_by`
+    Predicate,
+    /// Bytes matched with `match_bytes`
+    Bytes(Vec<u8>),
+    /// Byte range matched with `match_byte_range`, inclusive
+    ByteRange(u8, u8),
+}
+
+/// Resource limits of a `ParserState`, along with their configured maximum.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Limit {
+    /// Maximum number of nested rule calls
+    Depth(usize),
+    /// Maximum total number of rule calls
+    Calls(usize),
+    /// Maximum total number of rule calls and repetitions
+    Steps(usize),
 }
 
 /// Where an `Error` has occurred.
@@ -74,6 +152,21 @@ pub enum LineColLocation {
     Span((usize, usize), (usize, usize)),
 }
 
+/// A secondary location attached to an `Error` with [`Error::with_label()`], shown along with
+/// the main location when formatted with `Display`.
+///
+/// [`Error::with_label()`]: struct.Error.html#method.with_label
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Label {
+    /// Location within the input string
+    pub location: InputLocation,
+    /// Line/column within the input string
+    pub line_col: LineColLocation,
+    /// Message shown next to the location
+    pub message: String,
+    line: String,
+}
+
 impl<R: RuleType> Error<R> {
     /// Creates `Error` from `ErrorVariant` and `Position`.
     ///
@@ -104,13 +197,18 @@ impl<R: RuleType> Error<R> {
     /// ```
     #[allow(clippy::needless_pass_by_value)]
     pub fn new_from_pos(variant: ErrorVariant<R>, pos: Position) -> Error<R> {
+        let index = pos.line_index();
+        let input = pos.input();
+        let line_col = index.line_col(input, pos.pos());
+
         Error {
             variant,
             location: InputLocation::Pos(pos.pos()),
-            path: None,
-            line: visualize_whitespace(pos.line_of()),
-            continued_line: None,
-            line_col: LineColLocation::Pos(pos.line_col()),
+            line_col: LineColLocation::Pos(line_col),
+            details: Details::new(
+                visualize_whitespace(index.line_text(input, line_col.0).unwrap()),
+                None,
+            ),
         }
     }
 
@@ -145,14 +243,16 @@ impl<R: RuleType> Error<R> {
     /// ```
     #[allow(clippy::needless_pass_by_value)]
     pub fn new_from_span(variant: ErrorVariant<R>, span: Span) -> Error<R> {
+        let index = span.line_index();
         let end = span.end_pos();
+        let input = span.input();
 
-        let mut end_line_col = end.line_col();
+        let mut end_line_col = index.line_col(input, end.pos());
         // end position is after a \n, so we want to point to the visual lf symbol
         if end_line_col.1 == 1 {
             let mut visual_end = end.clone();
             visual_end.skip_back(1);
-            let lc = visual_end.line_col();
+            let lc = index.line_col(input, visual_end.pos());
             end_line_col = (lc.0, lc.1 + 1);
         };
 
@@ -163,10 +263,8 @@ impl<R: RuleType> Error<R> {
         Error {
             variant,
             location: InputLocation::Span((span.start(), end.pos())),
-            path: None,
-            line: start_line,
-            continued_line,
-            line_col: LineColLocation::Span(span.start_pos().line_col(), end_line_col),
+            line_col: LineColLocation::Span(index.line_col(input, span.start()), end_line_col),
+            details: Details::new(start_line, continued_line),
         }
     }
 
@@ -196,7 +294,7 @@ impl<R: RuleType> Error<R> {
     /// ).with_path("file.rs");
     /// ```
     pub fn with_path(mut self, path: &str) -> Error<R> {
-        self.path = Some(path.to_owned());
+        self.details.path = Some(path.to_owned());
 
         self
     }
@@ -228,15 +326,185 @@ impl<R: RuleType> Error<R> {
     /// assert_eq!(Some("file.rs"), error.path());
     /// ```
     pub fn path(&self) -> Option<&str> {
-        self.path.as_deref()
+        self.details.path.as_deref()
+    }
+
+    /// Returns `Error` with the stack of rules, outermost first, that were being parsed where it
+    /// occurred. It is shown as a note when formatted with `Display`, using the `Debug`
+    /// representation of the rules.
+    ///
+    /// Parsing errors carry this stack when [`ParserConfig::track_rule_stack`] is enabled.
+    ///
+    /// [`ParserConfig::track_rule_stack`]: ../struct.ParserConfig.html#method.track_rule_stack
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::error::{Error, ErrorVariant};
+    /// # use pest::Position;
+    /// # use std::sync::Arc;
+    /// # #[allow(non_camel_case_types)]
+    /// # #[allow(dead_code)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// # enum Rule {
+    /// #     file,
+    /// #     item,
+    /// #     open_paren
+    /// # }
+    /// # let input: Arc<str> = Arc::from("");
+    /// # let pos = Position::from_start(input);
+    /// let error = Error::new_from_pos(
+    ///     ErrorVariant::ParsingError {
+    ///         positives: vec![Rule::open_paren],
+    ///         negatives: vec![]
+    ///     },
+    ///     pos
+    /// ).with_rule_stack(vec![Rule::file, Rule::item]);
+    ///
+    /// assert_eq!(error.rule_stack(), &[Rule::file, Rule::item]);
+    /// ```
+    pub fn with_rule_stack(mut self, rule_stack: Vec<R>) -> Error<R> {
+        self.details.rule_stack = rule_stack;
+
+        self
+    }
+
+    /// Returns the stack of rules set using [`Error::with_rule_stack()`], outermost first.
+    pub fn rule_stack(&self) -> &[R] {
+        &self.details.rule_stack
+    }
+
+    /// Returns `Error` with an additional `span` labeled with `message`, like the opening brace
+    /// of a mismatched closing one. Labels are shown along with the main location when formatted
+    /// with `Display`, in input order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::error::{Error, ErrorVariant};
+    /// # use pest::Span;
+    /// # use std::sync::Arc;
+    /// # #[allow(non_camel_case_types)]
+    /// # #[allow(dead_code)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// # enum Rule {
+    /// #     block
+    /// # }
+    /// let input: Arc<str> = Arc::from("{\n    a\n)");
+    /// let open = Span::new(input.clone(), 0, 1).unwrap();
+    /// let close = Span::new(input, 8, 9).unwrap();
+    ///
+    /// let error: Error<Rule> = Error::new_from_span(
+    ///     ErrorVariant::CustomError {
+    ///         message: "mismatched closing delimiter".to_owned()
+    ///     },
+    ///     close
+    /// )
+    /// .with_label(open, "unclosed delimiter")
+    /// .with_help("replace `)` with `}`");
+    ///
+    /// assert_eq!(
+    ///     error.to_string(),
+    ///     [
+    ///         " --> 3:1",
+    ///         "  |",
+    ///         "1 | {␊",
+    ///         "  | ^ unclosed delimiter",
+    ///         "  | ...",
+    ///         "3 | )",
+    ///         "  | ^",
+    ///         "  |",
+    ///         "  = mismatched closing delimiter",
+    ///         "  = help: replace `)` with `}`",
+    ///     ]
+    ///     .join("\n")
+    /// );
+    /// ```
+    pub fn with_label(mut self, span: Span, message: &str) -> Error<R> {
+        let input = span.input();
+        let index = span.line_index();
+        let start = index.line_col(input, span.start());
+        let end = index.line_col(input, span.end());
+        let line = index.line_text(input, start.0).unwrap();
+
+        self.details.labels.push(Label {
+            location: InputLocation::Span((span.start(), span.end())),
+            line_col: LineColLocation::Span(start, end),
+            message: message.to_owned(),
+            line: visualize_whitespace(line),
+        });
+
+        self
+    }
+
+    /// Returns `Error` with an additional note, shown after the message when formatted with
+    /// `Display`.
+    pub fn with_note(mut self, note: &str) -> Error<R> {
+        self.details.notes.push(note.to_owned());
+
+        self
+    }
+
+    /// Returns `Error` with an additional help line, shown after the notes when formatted with
+    /// `Display`.
+    pub fn with_help(mut self, help: &str) -> Error<R> {
+        self.details.help.push(help.to_owned());
+
+        self
+    }
+
+    /// Returns the labels added using [`Error::with_label()`].
+    pub fn labels(&self) -> &[Label] {
+        &self.details.labels
+    }
+
+    /// Returns the notes added using [`Error::with_note()`].
+    pub fn notes(&self) -> &[String] {
+        &self.details.notes
+    }
+
+    /// Returns the help lines added using [`Error::with_help()`].
+    pub fn help(&self) -> &[String] {
+        &self.details.help
+    }
+
+    /// Generates a string that stores the `Error` in a pretty-printed JSON format. The fields are:
+    ///
+    /// * `variant`: name of the [`ErrorVariant`], like `"ParsingError"`
+    /// * `message`: message of the `ErrorVariant`
+    /// * `path`: path set using [`Error::with_path()`], or `null`
+    /// * `location`: `start` and `end` byte positions, equal for errors at a position
+    /// * `line_col`: `start` and `end` objects with a `line` and a `column`
+    /// * `expected` and `unexpected`: expected and unexpected `Rule`s formatted with `Debug`,
+    ///   followed by the expected terminals
+    /// * `rule_stack`: rules formatted with `Debug`, outermost first
+    /// * `labels`: objects with a `location`, a `line_col`, and a `message`
+    /// * `notes` and `help`: arrays of strings
+    ///
+    /// [`ErrorVariant`]: enum.ErrorVariant.html
+    #[cfg(feature = "pretty-print")]
+    pub fn to_json(&self) -> String {
+        ::serde_json::to_string_pretty(self).expect("Failed to pretty-print Error to json.")
+    }
+
+    #[cfg(feature = "pretty-print")]
+    pub(crate) fn variant_name(&self) -> &'static str {
+        match self.variant {
+            ErrorVariant::ParsingError { .. } => "ParsingError",
+            ErrorVariant::DetailedParsingError(..) => "DetailedParsingError",
+            ErrorVariant::CustomError { .. } => "CustomError",
+            ErrorVariant::LimitError { .. } => "LimitError",
+            ErrorVariant::Cancelled => "Cancelled",
+        }
     }
 
-    /// Renames all `Rule`s if this is a [`ParsingError`]. It does nothing when called on a
-    /// [`CustomError`].
+    /// Renames all `Rule`s if this is a [`ParsingError`] or a [`DetailedParsingError`]. It does
+    /// nothing when called on a [`CustomError`] or any other variant.
     ///
     /// Useful in order to rename verbose rules or have detailed per-`Rule` formatting.
     ///
     /// [`ParsingError`]: enum.ErrorVariant.html#variant.ParsingError
+    /// [`DetailedParsingError`]: enum.ErrorVariant.html#variant.DetailedParsingError
     /// [`CustomError`]: enum.ErrorVariant.html#variant.CustomError
     ///
     /// # Examples
@@ -276,7 +544,16 @@ impl<R: RuleType> Error<R> {
                 positives,
                 negatives,
             } => {
-                let message = Error::parsing_error_message(&positives, &negatives, f);
+                let message = Error::parsing_error_message(&positives, &negatives, &[], f);
+                ErrorVariant::CustomError { message }
+            }
+            ErrorVariant::DetailedParsingError(detailed) => {
+                let message = Error::parsing_error_message(
+                    &detailed.positives,
+                    &detailed.negatives,
+                    &detailed.terminals,
+                    f,
+                );
                 ErrorVariant::CustomError { message }
             }
             variant => variant,
@@ -287,6 +564,36 @@ impl<R: RuleType> Error<R> {
         self
     }
 
+    // Decodes the lines of an error in an input where every `char` stands for the byte of the
+    // same value, from the UTF-8 they encode, and counts columns in `char`s of the decoded lines.
+    pub(crate) fn decode_latin1_lines(mut self) -> Error<R> {
+        self.line_col = match self.line_col {
+            LineColLocation::Pos((line, col)) => {
+                LineColLocation::Pos((line, decoded_col(&self.details.line, col)))
+            }
+            LineColLocation::Span((start_line, start_col), (end_line, end_col)) => {
+                let end_text = self
+                    .details
+                    .continued_line
+                    .as_ref()
+                    .unwrap_or(&self.details.line);
+
+                LineColLocation::Span(
+                    (start_line, decoded_col(&self.details.line, start_col)),
+                    (end_line, decoded_col(end_text, end_col)),
+                )
+            }
+        };
+        self.details.line = decode_latin1(&self.details.line);
+        self.details.continued_line = self
+            .details
+            .continued_line
+            .as_ref()
+            .map(|line| decode_latin1(line));
+
+        self
+    }
+
     fn start(&self) -> (usize, usize) {
         match self.line_col {
             LineColLocation::Pos(line_col) => line_col,
@@ -299,6 +606,12 @@ impl<R: RuleType> Error<R> {
             LineColLocation::Pos((line, _)) => line,
             LineColLocation::Span((start_line, _), (end_line, _)) => cmp::max(start_line, end_line),
         };
+        let line = self
+            .details
+            .labels
+            .iter()
+            .map(|label| label.start().0)
+            .fold(line, cmp::max);
 
         let line_str_len = format!("{}", line).len();
 
@@ -311,161 +624,225 @@ impl<R: RuleType> Error<R> {
     }
 
     fn underline(&self) -> String {
-        let mut underline = String::new();
-
-        let mut start = self.start().1;
         let end = match self.line_col {
-            LineColLocation::Span(_, (_, mut end)) => {
-                let inverted_cols = start > end;
-                if inverted_cols {
-                    mem::swap(&mut start, &mut end);
-                    start -= 1;
-                    end += 1;
-                }
-
-                Some(end)
-            }
+            LineColLocation::Span(_, (_, end)) => Some(end),
             _ => None,
         };
-        let offset = start - 1;
-        let line_chars = self.line.chars();
-
-        for c in line_chars.take(offset) {
-            match c {
-                '\t' => underline.push('\t'),
-                _ => underline.push(' '),
-            }
-        }
-
-        if let Some(end) = end {
-            if end - start > 1 {
-                underline.push('^');
-                for _ in 2..(end - start) {
-                    underline.push('-');
-                }
-                underline.push('^');
-            } else {
-                underline.push('^');
-            }
-        } else {
-            underline.push_str("^---")
-        }
 
-        underline
+        underline(&self.details.line, self.start().1, end)
     }
 
     fn message(&self) -> String {
         self.variant.message().to_string()
     }
 
-    fn parsing_error_message<F>(positives: &[R], negatives: &[R], mut f: F) -> String
+    fn parsing_error_message<F>(
+        positives: &[R],
+        negatives: &[R],
+        terminals: &[Terminal],
+        mut f: F,
+    ) -> String
     where
         F: FnMut(&R) -> String,
     {
-        match (negatives.is_empty(), positives.is_empty()) {
+        let expected: Vec<_> = positives
+            .iter()
+            .map(&mut f)
+            .chain(terminals.iter().map(|terminal| terminal.to_string()))
+            .collect();
+        let unexpected: Vec<_> = negatives.iter().map(&mut f).collect();
+
+        match (unexpected.is_empty(), expected.is_empty()) {
             (false, false) => format!(
                 "unexpected {}; expected {}",
-                Error::enumerate(negatives, &mut f),
-                Error::enumerate(positives, &mut f)
+                Error::<R>::enumerate(&unexpected),
+                Error::<R>::enumerate(&expected)
             ),
-            (false, true) => format!("unexpected {}", Error::enumerate(negatives, &mut f)),
-            (true, false) => format!("expected {}", Error::enumerate(positives, &mut f)),
+            (false, true) => format!("unexpected {}", Error::<R>::enumerate(&unexpected)),
+            (true, false) => format!("expected {}", Error::<R>::enumerate(&expected)),
             (true, true) => "unknown parsing error".to_owned(),
         }
     }
 
-    fn enumerate<F>(rules: &[R], f: &mut F) -> String
-    where
-        F: FnMut(&R) -> String,
-    {
-        match rules.len() {
-            1 => f(&rules[0]),
-            2 => format!("{} or {}", f(&rules[0]), f(&rules[1])),
-            l => {
-                let separated = rules
+    fn enumerate(items: &[String]) -> String {
+        match items.len() {
+            1 => items[0].clone(),
+            2 => format!("{} or {}", items[0], items[1]),
+            l => format!("{}, or {}", items[..l - 1].join(", "), items[l - 1]),
+        }
+    }
+
+    pub(crate) fn format(&self) -> String {
+        let spacing = self.spacing();
+        let mut formatted = self.format_location();
+
+        if !self.details.rule_stack.is_empty() {
+            formatted = format!(
+                "{}\n{} = while parsing {}",
+                formatted,
+                spacing,
+                self.details
+                    .rule_stack
                     .iter()
-                    .take(l - 1)
-                    .map(|r| f(r))
+                    .map(|rule| format!("{:?}", rule))
                     .collect::<Vec<_>>()
-                    .join(", ");
-                format!("{}, or {}", separated, f(&rules[l - 1]))
-            }
+                    .join(" > ")
+            );
+        }
+
+        for note in &self.details.notes {
+            formatted = format!("{}\n{}", formatted, footer(&spacing, "note", note));
+        }
+
+        for help in &self.details.help {
+            formatted = format!("{}\n{}", formatted, footer(&spacing, "help", help));
         }
+
+        formatted
     }
 
-    pub(crate) fn format(&self) -> String {
+    fn format_location(&self) -> String {
         let spacing = self.spacing();
         let path = self
+            .details
             .path
             .as_ref()
             .map(|path| format!("{}:", path))
             .unwrap_or_default();
 
-        let pair = (self.line_col.clone(), &self.continued_line);
+        // Every snippet with its first and last line, in input order.
+        let mut snippets = vec![self.snippet(&spacing)];
+        snippets.extend(
+            self.details
+                .labels
+                .iter()
+                .map(|label| label.snippet(&spacing)),
+        );
+        snippets.sort_by_key(|&(first, _, _)| first);
+
+        let mut body = String::new();
+        let mut last = None;
+
+        for (first, end, snippet) in snippets {
+            match last {
+                Some(last) if first > last + 1 => body.push_str(&format!("\n{} | ...\n", spacing)),
+                Some(_) => body.push('\n'),
+                None => {}
+            }
+
+            body.push_str(&snippet);
+            last = Some(end);
+        }
+
+        format!(
+            "{s}--> {p}{l}:{c}\n\
+             {s} |\n\
+             {body}\n\
+             {s} |\n\
+             {s} = {message}",
+            s = spacing,
+            p = path,
+            l = self.start().0,
+            c = self.start().1,
+            body = body,
+            message = self.message()
+        )
+    }
+
+    // The lines of the main location, with their first and last line numbers.
+    fn snippet(&self, spacing: &str) -> (usize, usize, String) {
+        let start = self.start().0;
+        let pair = (self.line_col.clone(), &self.details.continued_line);
+
         if let (LineColLocation::Span(_, end), &Some(ref continued_line)) = pair {
-            let has_line_gap = end.0 - self.start().0 > 1;
-            if has_line_gap {
-                format!(
-                    "{s    }--> {p}{ls}:{c}\n\
-                     {s    } |\n\
-                     {ls:w$} | {line}\n\
-                     {s    } | ...\n\
-                     {le:w$} | {continued_line}\n\
-                     {s    } | {underline}\n\
-                     {s    } |\n\
-                     {s    } = {message}",
-                    s = spacing,
-                    w = spacing.len(),
-                    p = path,
-                    ls = self.start().0,
-                    le = end.0,
-                    c = self.start().1,
-                    line = self.line,
-                    continued_line = continued_line,
-                    underline = self.underline(),
-                    message = self.message()
-                )
+            let gap = if end.0 - start > 1 {
+                format!("{s} | ...\n", s = spacing)
             } else {
-                format!(
-                    "{s    }--> {p}{ls}:{c}\n\
-                     {s    } |\n\
-                     {ls:w$} | {line}\n\
-                     {le:w$} | {continued_line}\n\
-                     {s    } | {underline}\n\
-                     {s    } |\n\
-                     {s    } = {message}",
-                    s = spacing,
-                    w = spacing.len(),
-                    p = path,
-                    ls = self.start().0,
-                    le = end.0,
-                    c = self.start().1,
-                    line = self.line,
-                    continued_line = continued_line,
-                    underline = self.underline(),
-                    message = self.message()
-                )
-            }
+                String::new()
+            };
+
+            let snippet = format!(
+                "{ls:w$} | {line}\n\
+                 {gap}\
+                 {le:w$} | {continued_line}\n\
+                 {s} | {underline}",
+                s = spacing,
+                w = spacing.len(),
+                ls = start,
+                le = end.0,
+                gap = gap,
+                line = self.details.line,
+                continued_line = continued_line,
+                underline = self.underline()
+            );
+
+            (start, end.0, snippet)
         } else {
-            format!(
-                "{s}--> {p}{l}:{c}\n\
-                 {s} |\n\
-                 {l} | {line}\n\
-                 {s} | {underline}\n\
-                 {s} |\n\
-                 {s} = {message}",
+            let snippet = format!(
+                "{l:w$} | {line}\n\
+                 {s} | {underline}",
                 s = spacing,
-                p = path,
-                l = self.start().0,
-                c = self.start().1,
-                line = self.line,
-                underline = self.underline(),
-                message = self.message()
-            )
+                w = spacing.len(),
+                l = start,
+                line = self.details.line,
+                underline = self.underline()
+            );
+
+            (start, start, snippet)
         }
     }
 }
 
+impl<R> Details<R> {
+    fn new(line: String, continued_line: Option<String>) -> Box<Details<R>> {
+        Box::new(Details {
+            path: None,
+            line,
+            continued_line,
+            rule_stack: Vec::new(),
+            labels: Vec::new(),
+            notes: Vec::new(),
+            help: Vec::new(),
+        })
+    }
+}
+
+impl Label {
+    fn start(&self) -> (usize, usize) {
+        match self.line_col {
+            LineColLocation::Pos(line_col) | LineColLocation::Span(line_col, _) => line_col,
+        }
+    }
+
+    // The line of the label, with its line number twice for symmetry with `Error::snippet`.
+    fn snippet(&self, spacing: &str) -> (usize, usize, String) {
+        let (line, col) = self.start();
+        // Labels only show their first line, so ones going further are underlined to its end.
+        let end = match self.line_col {
+            LineColLocation::Span(_, (end_line, end)) if end_line == line => Some(end),
+            LineColLocation::Span(..) => Some(self.line.chars().count() + 1),
+            LineColLocation::Pos(_) => None,
+        };
+        let mut underline = underline(&self.line, col, end);
+
+        if !self.message.is_empty() {
+            underline = format!("{} {}", underline, self.message);
+        }
+
+        let snippet = format!(
+            "{l:w$} | {line}\n\
+             {s} | {underline}",
+            s = spacing,
+            w = spacing.len(),
+            l = line,
+            line = self.line,
+            underline = underline
+        );
+
+        (line, line, snippet)
+    }
+}
+
 impl<R: RuleType> ErrorVariant<R> {
     ///
     /// Returns the error message for [`ErrorVariant`]
@@ -494,10 +871,27 @@ impl<R: RuleType> ErrorVariant<R> {
             ErrorVariant::ParsingError {
                 ref positives,
                 ref negatives,
-            } => Cow::Owned(Error::parsing_error_message(positives, negatives, |r| {
-                format!("{:?}", r)
-            })),
+            } => Cow::Owned(Error::parsing_error_message(
+                positives,
+                negatives,
+                &[],
+                |r| format!("{:?}", r),
+            )),
+            ErrorVariant::DetailedParsingError(ref detailed) => {
+                Cow::Owned(Error::parsing_error_message(
+                    &detailed.positives,
+                    &detailed.negatives,
+                    &detailed.terminals,
+                    |r| format!("{:?}", r),
+                ))
+            }
             ErrorVariant::CustomError { ref message } => Cow::Borrowed(message),
+            ErrorVariant::LimitError { limit } => Cow::Owned(match limit {
+                Limit::Depth(max) => format!("exceeded the maximum rule depth of {}", max),
+                Limit::Calls(max) => format!("exceeded the budget of {} rule calls", max),
+                Limit::Steps(max) => format!("exceeded the budget of {} parsing steps", max),
+            }),
+            ErrorVariant::Cancelled => Cow::Borrowed("parsing was cancelled"),
         }
     }
 }
@@ -508,14 +902,212 @@ impl<R: RuleType> fmt::Display for Error<R> {
     }
 }
 
+impl fmt::Display for Terminal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Terminal::Literal(ref string) => write!(f, "{:?}", string),
+            Terminal::Insensitive(ref string) => write!(f, "^{:?}", string),
+            Terminal::Range(start, end) => write!(f, "{:?}..{:?}", start, end),
+            Terminal::Predicate => write!(f, "a character matching a predicate"),
+            Terminal::Bytes(ref bytes) => write!(f, "b\"{}\"", escape_bytes(bytes)),
+            Terminal::ByteRange(start, end) => write!(
+                f,
+                "b'{}'..b'{}'",
+                escape_bytes(&[start]),
+                escape_bytes(&[end])
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "pretty-print")]
+impl<R: RuleType> ::serde::Serialize for Error<R> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        let rule = |rule: &R| format!("{:?}", rule);
+        let (expected, unexpected) = match self.variant {
+            ErrorVariant::ParsingError {
+                ref positives,
+                ref negatives,
+            } => (
+                positives.iter().map(rule).collect(),
+                negatives.iter().map(rule).collect(),
+            ),
+            ErrorVariant::DetailedParsingError(ref detailed) => (
+                detailed
+                    .positives
+                    .iter()
+                    .map(rule)
+                    .chain(
+                        detailed
+                            .terminals
+                            .iter()
+                            .map(|terminal| terminal.to_string()),
+                    )
+                    .collect(),
+                detailed.negatives.iter().map(rule).collect(),
+            ),
+            _ => (vec![], vec![]),
+        };
+        let labels: Vec<_> = self
+            .details
+            .labels
+            .iter()
+            .map(|label| {
+                json!({
+                    "location": location_json(&label.location),
+                    "line_col": line_col_json(&label.line_col),
+                    "message": label.message,
+                })
+            })
+            .collect();
+        let rule_stack: Vec<_> = self.details.rule_stack.iter().map(rule).collect();
+
+        let mut ser = serializer.serialize_struct("Error", 11)?;
+        ser.serialize_field("variant", self.variant_name())?;
+        ser.serialize_field("message", &self.message())?;
+        ser.serialize_field("path", &self.details.path)?;
+        ser.serialize_field("location", &location_json(&self.location))?;
+        ser.serialize_field("line_col", &line_col_json(&self.line_col))?;
+        ser.serialize_field::<Vec<String>>("expected", &expected)?;
+        ser.serialize_field::<Vec<String>>("unexpected", &unexpected)?;
+        ser.serialize_field("rule_stack", &rule_stack)?;
+        ser.serialize_field("labels", &labels)?;
+        ser.serialize_field("notes", &self.details.notes)?;
+        ser.serialize_field("help", &self.details.help)?;
+        ser.end()
+    }
+}
+
+#[cfg(feature = "pretty-print")]
+fn location_json(location: &InputLocation) -> ::serde_json::Value {
+    let (start, end) = match *location {
+        InputLocation::Pos(pos) => (pos, pos),
+        InputLocation::Span(span) => span,
+    };
+
+    json!({ "start": start, "end": end })
+}
+
+#[cfg(feature = "pretty-print")]
+fn line_col_json(line_col: &LineColLocation) -> ::serde_json::Value {
+    let (start, end) = match *line_col {
+        LineColLocation::Pos(pos) => (pos, pos),
+        LineColLocation::Span(start, end) => (start, end),
+    };
+
+    json!({
+        "start": { "line": start.0, "column": start.1 },
+        "end": { "line": end.0, "column": end.1 },
+    })
+}
+
 #[cfg(feature = "std")]
 impl<'i, R: RuleType> std::error::Error for Error<R> {
     fn description(&self) -> &str {
         match self.variant {
-            ErrorVariant::ParsingError { .. } => "parsing error",
+            ErrorVariant::ParsingError { .. } | ErrorVariant::DetailedParsingError(..) => {
+                "parsing error"
+            }
             ErrorVariant::CustomError { ref message } => message,
+            ErrorVariant::LimitError { .. } => "limit error",
+            ErrorVariant::Cancelled => "parsing was cancelled",
+        }
+    }
+}
+
+fn escape_bytes(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .flat_map(|&byte| ascii::escape_default(byte))
+        .map(char::from)
+        .collect()
+}
+
+// Decodes the UTF-8 encoded by `line`, where every `char` below `U+0100` stands for the byte of
+// the same value. Other `char`s, such as the ones `visualize_whitespace` adds, are kept as they
+// are.
+fn decode_latin1(line: &str) -> String {
+    let mut decoded = String::new();
+    let mut bytes = Vec::new();
+
+    for c in line.chars() {
+        if (c as u32) < 0x100 {
+            bytes.push(c as u8);
+        } else {
+            decoded.push_str(&String::from_utf8_lossy(&bytes));
+            decoded.push(c);
+            bytes.clear();
+        }
+    }
+
+    decoded.push_str(&String::from_utf8_lossy(&bytes));
+
+    decoded
+}
+
+// Translates the 1-based column `col` in `line` to the one in `decode_latin1(line)`.
+fn decoded_col(line: &str, col: usize) -> usize {
+    let prefix: String = line.chars().take(col - 1).collect();
+    let len = prefix.chars().count();
+
+    decode_latin1(&prefix).chars().count() + col - len
+}
+
+// Underlines `line` from column `start` to the exclusive column `end`, or marks `start` if there
+// is no `end`.
+fn underline(line: &str, mut start: usize, end: Option<usize>) -> String {
+    let mut underline = String::new();
+
+    let end = end.map(|mut end| {
+        let inverted_cols = start > end;
+        if inverted_cols {
+            mem::swap(&mut start, &mut end);
+            start -= 1;
+            end += 1;
+        }
+
+        end
+    });
+    let offset = start - 1;
+    let line_chars = line.chars();
+
+    for c in line_chars.take(offset) {
+        match c {
+            '\t' => underline.push('\t'),
+            _ => underline.push(' '),
+        }
+    }
+
+    if let Some(end) = end {
+        if end - start > 1 {
+            underline.push('^');
+            for _ in 2..(end - start) {
+                underline.push('-');
+            }
+            underline.push('^');
+        } else {
+            underline.push('^');
         }
+    } else {
+        underline.push_str("^---")
     }
+
+    underline
+}
+
+// Formats a `= kind: text` line, aligning the lines of `text` after the first under it.
+fn footer(spacing: &str, kind: &str, text: &str) -> String {
+    let indent = format!("\n{} {}", spacing, " ".repeat(kind.len() + 4));
+
+    format!(
+        "{} = {}: {}",
+        spacing,
+        kind,
+        text.lines().collect::<Vec<_>>().join(&indent)
+    )
 }
 
 fn visualize_whitespace(input: &str) -> String {
@@ -657,6 +1249,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn display_limit_pos() {
+        let input: Arc<str> = Arc::from("ab\ncd\nef");
+        let pos = position::Position::new(input, 4).unwrap();
+        let error: Error<&str> = Error::new_from_pos(
+            ErrorVariant::LimitError {
+                limit: Limit::Depth(64),
+            },
+            pos,
+        );
+
+        assert_eq!(
+            format!("{}", error),
+            vec![
+                " --> 2:2",
+                "  |",
+                "2 | cd␊",
+                "  |  ^---",
+                "  |",
+                "  = exceeded the maximum rule depth of 64",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn display_cancelled_pos() {
+        let input: Arc<str> = Arc::from("ab\ncd\nef");
+        let pos = position::Position::new(input, 4).unwrap();
+        let error: Error<&str> = Error::new_from_pos(ErrorVariant::Cancelled, pos);
+
+        assert_eq!(
+            format!("{}", error),
+            vec![
+                " --> 2:2",
+                "  |",
+                "2 | cd␊",
+                "  |  ^---",
+                "  |",
+                "  = parsing was cancelled",
+            ]
+            .join("\n")
+        );
+    }
+
     #[test]
     fn display_custom_span_two_lines() {
         let input: Arc<str> = Arc::from("ab\ncd\nefgh");
@@ -797,6 +1434,121 @@ mod tests {
         );
     }
 
+    #[test]
+    fn display_detailed_parsing_error() {
+        let input: Arc<str> = Arc::from("ab\ncd\nef");
+        let pos = position::Position::new(input, 4).unwrap();
+        let error: Error<u32> = Error::new_from_pos(
+            ErrorVariant::DetailedParsingError(Box::new(DetailedAttempts {
+                positives: vec![1],
+                negatives: vec![4],
+                terminals: vec![
+                    Terminal::Literal("}".to_owned()),
+                    Terminal::Insensitive("e".to_owned()),
+                    Terminal::Range('0', '9'),
+                    Terminal::Predicate,
+                ],
+            })),
+            pos,
+        );
+
+        assert_eq!(
+            format!("{}", error),
+            vec![
+                " --> 2:2",
+                "  |",
+                "2 | cd␊",
+                "  |  ^---",
+                "  |",
+                "  = unexpected 4; expected 1, \"}\", ^\"e\", '0'..'9', or a character matching a \
+                 predicate",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn display_detailed_parsing_error_terminals() {
+        let input: Arc<str> = Arc::from("ab\ncd\nef");
+        let pos = position::Position::new(input, 4).unwrap();
+        let error: Error<u32> = Error::new_from_pos(
+            ErrorVariant::DetailedParsingError(Box::new(DetailedAttempts {
+                positives: vec![],
+                negatives: vec![],
+                terminals: vec![
+                    Terminal::Literal(",".to_owned()),
+                    Terminal::Literal("\n".to_owned()),
+                ],
+            })),
+            pos,
+        );
+
+        assert_eq!(
+            format!("{}", error),
+            vec![
+                " --> 2:2",
+                "  |",
+                "2 | cd␊",
+                "  |  ^---",
+                "  |",
+                "  = expected \",\" or \"\\n\"",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn mapped_detailed_parsing_error() {
+        let input: Arc<str> = Arc::from("ab\ncd\nef");
+        let pos = position::Position::new(input, 4).unwrap();
+        let error: Error<u32> = Error::new_from_pos(
+            ErrorVariant::DetailedParsingError(Box::new(DetailedAttempts {
+                positives: vec![1],
+                negatives: vec![],
+                terminals: vec![Terminal::Range('a', 'z')],
+            })),
+            pos,
+        )
+        .renamed_rules(|n| format!("{}", n + 1));
+
+        assert_eq!(
+            error.variant,
+            ErrorVariant::CustomError {
+                message: "expected 2 or 'a'..'z'".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn display_rule_stack() {
+        let input: Arc<str> = Arc::from("ab\ncd\nef");
+        let start = position::Position::new(input.clone(), 1).unwrap();
+        let end = position::Position::new(input, 4).unwrap();
+        let error: Error<u32> = Error::new_from_span(
+            ErrorVariant::ParsingError {
+                positives: vec![3],
+                negatives: vec![],
+            },
+            start.span(&end),
+        )
+        .with_rule_stack(vec![1, 2, 3]);
+
+        assert_eq!(
+            format!("{}", error),
+            vec![
+                " --> 1:2",
+                "  |",
+                "1 | ab␊",
+                "2 | cd␊",
+                "  |  ^",
+                "  |",
+                "  = expected 3",
+                "  = while parsing 1 > 2 > 3",
+            ]
+            .join("\n")
+        );
+    }
+
     #[test]
     fn mapped_parsing_error() {
         let input: Arc<str> = Arc::from("ab\ncd\nef");
@@ -877,4 +1629,200 @@ mod tests {
             .join("\n")
         );
     }
+
+    #[test]
+    fn display_labels() {
+        let input: Arc<str> = Arc::from("{\n  a\n)\n");
+        let close = Span::new(input.clone(), 6, 7).unwrap();
+        let error: Error<u32> = Error::new_from_span(
+            ErrorVariant::CustomError {
+                message: "mismatched closing delimiter".to_owned(),
+            },
+            close,
+        )
+        .with_label(Span::new(input.clone(), 4, 5).unwrap(), "")
+        .with_label(Span::new(input, 0, 1).unwrap(), "unclosed delimiter");
+
+        assert_eq!(
+            format!("{}", error),
+            vec![
+                " --> 3:1",
+                "  |",
+                "1 | {␊",
+                "  | ^ unclosed delimiter",
+                "2 |   a␊",
+                "  |   ^",
+                "3 | )␊",
+                "  | ^",
+                "  |",
+                "  = mismatched closing delimiter",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn display_labels_align_gutter() {
+        let input: Arc<str> = Arc::from("a\nb\nc\nd\ne\nf\ng\nh\ni\nj(k)");
+        let pos = position::Position::new(input.clone(), 2).unwrap();
+        let error: Error<u32> = Error::new_from_pos(
+            ErrorVariant::CustomError {
+                message: "error".to_owned(),
+            },
+            pos,
+        )
+        .with_label(Span::new(input, 19, 22).unwrap(), "here");
+
+        assert_eq!(
+            format!("{}", error),
+            vec![
+                "  --> 2:1",
+                "   |",
+                " 2 | b␊",
+                "   | ^---",
+                "   | ...",
+                "10 | j(k)",
+                "   |  ^-^ here",
+                "   |",
+                "   = error",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn display_multi_line_label() {
+        let input: Arc<str> = Arc::from("ab\ncd\nef");
+        let pos = position::Position::new(input.clone(), 7).unwrap();
+        let error: Error<u32> = Error::new_from_pos(
+            ErrorVariant::CustomError {
+                message: "error".to_owned(),
+            },
+            pos,
+        )
+        .with_label(Span::new(input, 1, 4).unwrap(), "started here");
+
+        assert_eq!(
+            format!("{}", error),
+            vec![
+                " --> 3:2",
+                "  |",
+                "1 | ab␊",
+                "  |  ^^ started here",
+                "  | ...",
+                "3 | ef",
+                "  |  ^---",
+                "  |",
+                "  = error",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn display_notes_and_help() {
+        let input: Arc<str> = Arc::from("ab\ncd\nef");
+        let pos = position::Position::new(input, 4).unwrap();
+        let error: Error<u32> = Error::new_from_pos(
+            ErrorVariant::CustomError {
+                message: "error".to_owned(),
+            },
+            pos,
+        )
+        .with_rule_stack(vec![1, 2])
+        .with_help("try this")
+        .with_note("first\nsecond")
+        .with_note("third");
+
+        assert_eq!(
+            error.notes(),
+            &["first\nsecond".to_owned(), "third".to_owned()]
+        );
+        assert_eq!(
+            format!("{}", error),
+            vec![
+                " --> 2:2",
+                "  |",
+                "2 | cd␊",
+                "  |  ^---",
+                "  |",
+                "  = error",
+                "  = while parsing 1 > 2",
+                "  = note: first",
+                "          second",
+                "  = note: third",
+                "  = help: try this",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "pretty-print")]
+    fn to_json() {
+        let input: Arc<str> = Arc::from("ab\ncd");
+        let pos = position::Position::new(input.clone(), 4).unwrap();
+        let error: Error<u32> = Error::new_from_pos(
+            ErrorVariant::DetailedParsingError(Box::new(DetailedAttempts {
+                positives: vec![1],
+                negatives: vec![2],
+                terminals: vec![Terminal::Literal("e".to_owned())],
+            })),
+            pos,
+        )
+        .with_path("file.rs")
+        .with_rule_stack(vec![3])
+        .with_label(Span::new(input, 0, 2).unwrap(), "label")
+        .with_note("note");
+
+        let json: ::serde_json::Value = ::serde_json::from_str(&error.to_json()).unwrap();
+
+        assert_eq!(
+            json,
+            json!({
+                "variant": "DetailedParsingError",
+                "message": "unexpected 2; expected 1 or \"e\"",
+                "path": "file.rs",
+                "location": { "start": 4, "end": 4 },
+                "line_col": {
+                    "start": { "line": 2, "column": 2 },
+                    "end": { "line": 2, "column": 2 },
+                },
+                "expected": ["1", "\"e\""],
+                "unexpected": ["2"],
+                "rule_stack": ["3"],
+                "labels": [{
+                    "location": { "start": 0, "end": 2 },
+                    "line_col": {
+                        "start": { "line": 1, "column": 1 },
+                        "end": { "line": 1, "column": 3 },
+                    },
+                    "message": "label",
+                }],
+                "notes": ["note"],
+                "help": [],
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "pretty-print")]
+    fn to_json_custom_span() {
+        let input: Arc<str> = Arc::from("ab\ncd");
+        let span = Span::new(input, 1, 5).unwrap();
+        let error: Error<u32> = Error::new_from_span(
+            ErrorVariant::CustomError {
+                message: "error".to_owned(),
+            },
+            span,
+        );
+
+        let json: ::serde_json::Value = ::serde_json::from_str(&error.to_json()).unwrap();
+
+        assert_eq!(json["variant"], "CustomError");
+        assert_eq!(json["path"], ::serde_json::Value::Null);
+        assert_eq!(json["location"], json!({ "start": 1, "end": 5 }));
+        assert_eq!(json["line_col"]["end"], json!({ "line": 2, "column": 3 }));
+        assert_eq!(json["expected"], json!([]));
+    }
 }
@@ -0,0 +1,311 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Conversion of parse trees into typed syntax trees, usually derived with `#[derive(FromPairs)]`
+//! from the `pest_derive` crate.
+//!
+//! A type converts from the pairs at the front of a `Pairs`, which lets fields be filled in order
+//! from the inner pairs of a pair: a `T` field takes exactly one `T`, an `Option<T>` field takes a
+//! `T` if the next pair starts one, and a `Vec<T>` field takes as many `T`s as there are. Pairs
+//! that do not fit are reported as an `Error` spanning them.
+
+use alloc::boxed::Box;
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use error::{Error, ErrorVariant};
+use iterators::{Pair, Pairs};
+use span::Span;
+use RuleType;
+
+/// A type that can be converted from the pairs at the front of a `Pairs`.
+pub trait FromPairs<R: RuleType>: Sized {
+    /// Returns the rules of the pairs that `Self` can start with.
+    fn rules() -> Vec<R>;
+
+    /// Converts the pairs at the front of `pairs` into `Self`, advancing past them. `span` is the
+    /// span of the pair that `pairs` are the inner pairs of, and locates pairs that are missing.
+    fn from_pairs(pairs: &mut Pairs<R>, span: &Span) -> Result<Self, Error<R>>;
+
+    /// Converts `pair` into `Self`.
+    fn from_pair(pair: Pair<R>) -> Result<Self, Error<R>> {
+        let span = pair.as_span();
+        let mut pairs = Pairs::single(pair);
+        let result = Self::from_pairs(&mut pairs, &span)?;
+
+        finish(pairs)?;
+
+        Ok(result)
+    }
+}
+
+impl<R: RuleType, T: FromPairs<R>> FromPairs<R> for Box<T> {
+    fn rules() -> Vec<R> {
+        T::rules()
+    }
+
+    fn from_pairs(pairs: &mut Pairs<R>, span: &Span) -> Result<Self, Error<R>> {
+        T::from_pairs(pairs, span).map(Box::new)
+    }
+}
+
+impl<R: RuleType, T: FromPairs<R>> FromPairs<R> for Option<T> {
+    fn rules() -> Vec<R> {
+        T::rules()
+    }
+
+    fn from_pairs(pairs: &mut Pairs<R>, span: &Span) -> Result<Self, Error<R>> {
+        if starts::<R, T>(pairs) {
+            T::from_pairs(pairs, span).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl<R: RuleType, T: FromPairs<R>> FromPairs<R> for Vec<T> {
+    fn rules() -> Vec<R> {
+        T::rules()
+    }
+
+    fn from_pairs(pairs: &mut Pairs<R>, span: &Span) -> Result<Self, Error<R>> {
+        let mut result = Vec::new();
+
+        while starts::<R, T>(pairs) {
+            result.push(T::from_pairs(pairs, span)?);
+        }
+
+        Ok(result)
+    }
+}
+
+/// Returns `true` if the next pair of `pairs` can start a `T`.
+pub fn starts<R: RuleType, T: FromPairs<R>>(pairs: &Pairs<R>) -> bool {
+    matches!(pairs.peek(), Some(pair) if T::rules().contains(&pair.as_rule()))
+}
+
+/// Returns the next pair of `pairs` if it is of `rule`, or an `Error` expecting `rule` otherwise.
+pub fn next_pair<R: RuleType>(
+    pairs: &mut Pairs<R>,
+    span: &Span,
+    rule: R,
+) -> Result<Pair<R>, Error<R>> {
+    match pairs.peek() {
+        Some(ref pair) if pair.as_rule() == rule => Ok(pairs.next().unwrap()),
+        _ => Err(expected(vec![rule], pairs, span)),
+    }
+}
+
+/// Returns an `Error` expecting one of `rules` in place of the next pair of `pairs`, or at the
+/// end of `span` if there are no more pairs.
+pub fn expected<R: RuleType>(rules: Vec<R>, pairs: &Pairs<R>, span: &Span) -> Error<R> {
+    match pairs.peek() {
+        Some(pair) => Error::new_from_span(
+            ErrorVariant::ParsingError {
+                positives: rules,
+                negatives: vec![pair.as_rule()],
+            },
+            pair.as_span(),
+        ),
+        None => Error::new_from_pos(
+            ErrorVariant::ParsingError {
+                positives: rules,
+                negatives: vec![],
+            },
+            span.end_pos(),
+        ),
+    }
+}
+
+/// Returns an `Error` spanning the first pair left in `pairs`, if any.
+pub fn finish<R: RuleType>(mut pairs: Pairs<R>) -> Result<(), Error<R>> {
+    match pairs.next() {
+        Some(pair) => Err(Error::new_from_span(
+            ErrorVariant::ParsingError {
+                positives: vec![],
+                negatives: vec![pair.as_rule()],
+            },
+            pair.as_span(),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Parses the text of `pair`, reporting failures as an `Error` spanning it.
+pub fn parse_text<R: RuleType, T>(pair: &Pair<R>) -> Result<T, Error<R>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    pair.as_str().parse().map_err(|error: T::Err| {
+        Error::new_from_span(
+            ErrorVariant::CustomError {
+                message: error.to_string(),
+            },
+            pair.as_span(),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::borrow::ToOwned;
+    use alloc::string::String;
+    use parser_state::{ParseResult, ParserState};
+    use std::sync::Arc;
+
+    #[allow(non_camel_case_types)]
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    enum Rule {
+        list,
+        number,
+        word,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Number(u32);
+
+    impl FromPairs<Rule> for Number {
+        fn rules() -> Vec<Rule> {
+            vec![Rule::number]
+        }
+
+        fn from_pairs(pairs: &mut Pairs<Rule>, span: &Span) -> Result<Self, Error<Rule>> {
+            let pair = next_pair(pairs, span, Rule::number)?;
+
+            Ok(Number(parse_text(&pair)?))
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct List(Option<Number>, Vec<Number>);
+
+    impl FromPairs<Rule> for List {
+        fn rules() -> Vec<Rule> {
+            vec![Rule::list]
+        }
+
+        fn from_pairs(pairs: &mut Pairs<Rule>, span: &Span) -> Result<Self, Error<Rule>> {
+            let pair = next_pair(pairs, span, Rule::list)?;
+            let span = pair.as_span();
+            let mut inner = pair.into_inner();
+            let result = List(
+                FromPairs::from_pairs(&mut inner, &span)?,
+                FromPairs::from_pairs(&mut inner, &span)?,
+            );
+
+            finish(inner)?;
+
+            Ok(result)
+        }
+    }
+
+    fn item(state: Box<ParserState<Rule>>) -> ParseResult<Box<ParserState<Rule>>> {
+        state
+            .rule(Rule::number, |state| {
+                state
+                    .match_char_by(|c| c.is_ascii_digit())
+                    .and_then(|state| {
+                        state.repeat(|state| state.match_char_by(|c| c.is_ascii_digit()))
+                    })
+            })
+            .or_else(|state| {
+                state.rule(Rule::word, |state| {
+                    state.match_char_by(|c| c.is_ascii_lowercase())
+                })
+            })
+    }
+
+    // list = { item ~ ("," ~ item)* }
+    fn parse(input: &str) -> Pair<Rule> {
+        let input: Arc<str> = Arc::from(input);
+
+        ::state(input, |state| {
+            state.rule(Rule::list, |state| {
+                item(state)
+                    .and_then(|state| state.repeat(|state| state.match_string(",").and_then(item)))
+            })
+        })
+        .unwrap()
+        .next()
+        .unwrap()
+    }
+
+    fn message(error: Error<Rule>) -> (String, (usize, usize)) {
+        let location = match error.location {
+            ::error::InputLocation::Pos(pos) => (pos, pos),
+            ::error::InputLocation::Span(span) => span,
+        };
+
+        (error.variant.message().into_owned(), location)
+    }
+
+    #[test]
+    fn convert() {
+        assert_eq!(
+            List::from_pair(parse("1,2,3")),
+            Ok(List(Some(Number(1)), vec![Number(2), Number(3)]))
+        );
+    }
+
+    #[test]
+    fn unexpected_pair() {
+        assert_eq!(
+            message(List::from_pair(parse("1,2,x,3")).unwrap_err()),
+            ("unexpected word".to_owned(), (4, 5))
+        );
+    }
+
+    #[test]
+    fn wrong_rule() {
+        assert_eq!(
+            message(Number::from_pair(parse("1")).unwrap_err()),
+            ("unexpected list; expected number".to_owned(), (0, 1))
+        );
+    }
+
+    #[test]
+    fn missing_pair() {
+        struct Two;
+
+        impl FromPairs<Rule> for Two {
+            fn rules() -> Vec<Rule> {
+                vec![Rule::list]
+            }
+
+            fn from_pairs(pairs: &mut Pairs<Rule>, span: &Span) -> Result<Self, Error<Rule>> {
+                let pair = next_pair(pairs, span, Rule::list)?;
+                let span = pair.as_span();
+                let mut inner = pair.into_inner();
+
+                Number::from_pairs(&mut inner, &span)?;
+                Number::from_pairs(&mut inner, &span)?;
+
+                Ok(Two)
+            }
+        }
+
+        assert_eq!(
+            message(Two::from_pair(parse("12")).err().unwrap()),
+            ("expected number".to_owned(), (2, 2))
+        );
+    }
+
+    #[test]
+    fn parse_failure() {
+        assert_eq!(
+            message(List::from_pair(parse("1,99999999999")).unwrap_err()),
+            ("number too large to fit in target type".to_owned(), (2, 13))
+        );
+    }
+}
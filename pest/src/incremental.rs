@@ -0,0 +1,143 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+use alloc::string::String;
+use std::mem;
+use std::ops::Range;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use error::Error;
+use iterators::Pairs;
+use memo::Memo;
+use parser_config::ParserConfig;
+use RuleType;
+
+/// The replacement of a byte range of the input by a new text, as sent by an editor.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Edit {
+    range: Range<usize>,
+    text: String,
+}
+
+impl Edit {
+    /// Creates an `Edit` replacing the bytes in `range` by `text`. An empty `range` inserts `text`
+    /// and an empty `text` deletes `range`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::Edit;
+    /// let edit = Edit::new(3..5, "abc");
+    ///
+    /// assert_eq!(edit.range(), 3..5);
+    /// assert_eq!(edit.text(), "abc");
+    /// ```
+    pub fn new<S: Into<String>>(range: Range<usize>, text: S) -> Edit {
+        Edit {
+            range,
+            text: text.into(),
+        }
+    }
+
+    /// Returns the replaced byte range.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Returns the replacement text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+/// The result of a parse which can be brought up to date with edits of its input by
+/// [`Parser::reparse`], without parsing the parts of the input the edits did not affect again.
+///
+/// Along with the result, it keeps the memoization table of the parse. When reparsing, the cached
+/// outcome of every rule which did not look at the edited input is reused, so that only the rules
+/// enclosing the edits are run again. The result is always the same as the one of a full parse.
+///
+/// [`Parser::reparse`]: trait.Parser.html#method.reparse
+#[derive(Debug)]
+pub struct IncrementalParse<R: RuleType> {
+    rule: R,
+    input: Arc<str>,
+    config: ParserConfig<R>,
+    memo: Memo<R>,
+    result: Result<Pairs<R>, Error<R>>,
+}
+
+impl<R: RuleType> IncrementalParse<R> {
+    pub(crate) fn new<F>(
+        rule: R,
+        input: Arc<str>,
+        config: ParserConfig<R>,
+        memo: Memo<R>,
+        parse: F,
+    ) -> IncrementalParse<R>
+    where
+        F: FnOnce(R, Arc<str>, ParserConfig<R>) -> Result<Pairs<R>, Error<R>>,
+    {
+        let memo = Arc::new(Mutex::new(memo));
+        let result = parse(rule, input.clone(), config.clone().with_memo(memo.clone()));
+        let memo = mem::replace(
+            &mut *memo.lock().unwrap_or_else(PoisonError::into_inner),
+            Memo::new(),
+        );
+
+        IncrementalParse {
+            rule,
+            input,
+            config,
+            memo,
+            result,
+        }
+    }
+
+    pub(crate) fn reparse<F>(self, edits: &[Edit], parse: F) -> IncrementalParse<R>
+    where
+        F: FnOnce(R, Arc<str>, ParserConfig<R>) -> Result<Pairs<R>, Error<R>>,
+    {
+        let IncrementalParse {
+            rule,
+            input,
+            config,
+            mut memo,
+            ..
+        } = self;
+        let mut text = String::from(&*input);
+
+        for edit in edits {
+            memo.edit(text.len(), edit.range.clone(), edit.text.len());
+            text.replace_range(edit.range.clone(), &edit.text);
+        }
+
+        IncrementalParse::new(rule, Arc::from(text), config, memo, parse)
+    }
+
+    /// Returns the rule parsing started from.
+    pub fn rule(&self) -> R {
+        self.rule
+    }
+
+    /// Returns the parsed input, with every edit applied.
+    pub fn input(&self) -> &Arc<str> {
+        &self.input
+    }
+
+    /// Returns the result of the parse.
+    pub fn result(&self) -> Result<&Pairs<R>, &Error<R>> {
+        self.result.as_ref()
+    }
+
+    /// Converts the `IncrementalParse` into the result of the parse.
+    pub fn into_result(self) -> Result<Pairs<R>, Error<R>> {
+        self.result
+    }
+}
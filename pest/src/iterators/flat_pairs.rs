@@ -7,14 +7,14 @@
 // option. All files in the project carrying such notice may not be copied,
 // modified, or distributed except according to those terms.
 
-use alloc::rc::Rc;
 use alloc::vec::Vec;
 use std::fmt;
 use std::sync::Arc;
 
 use super::pair::{self, Pair};
-use super::queueable_token::QueueableToken;
+use super::queueable_token::{self, QueueableToken};
 use super::tokens::{self, Tokens};
+use line_index::SharedLineIndex;
 use RuleType;
 
 /// An iterator over [`Pair`]s. It is created by [`Pairs::flatten`].
@@ -25,8 +25,9 @@ pub struct FlatPairs<R> {
     /// # Safety
     ///
     /// All `QueueableToken`s' `input_pos` must be valid character boundary indices into `input`.
-    queue: Rc<Vec<QueueableToken<R>>>,
+    queue: Arc<Vec<QueueableToken<R>>>,
     input: Arc<str>,
+    lines: SharedLineIndex,
     start: usize,
     end: usize,
 }
@@ -35,14 +36,16 @@ pub struct FlatPairs<R> {
 ///
 /// All `QueueableToken`s' `input_pos` must be valid character boundary indices into `input`.
 pub unsafe fn new<R: RuleType>(
-    queue: Rc<Vec<QueueableToken<R>>>,
+    queue: Arc<Vec<QueueableToken<R>>>,
     input: Arc<str>,
+    lines: SharedLineIndex,
     start: usize,
     end: usize,
 ) -> FlatPairs<R> {
     FlatPairs {
         queue,
         input,
+        lines,
         start,
         end,
     }
@@ -54,7 +57,6 @@ impl<R: RuleType> FlatPairs<R> {
     /// # Examples
     ///
     /// ```
-    /// # use std::rc::Rc;
     /// # use pest;
     /// # use std::sync::Arc;
     /// # #[allow(non_camel_case_types)]
@@ -74,7 +76,7 @@ impl<R: RuleType> FlatPairs<R> {
     /// ```
     #[inline]
     pub fn tokens(self) -> Tokens<R> {
-        tokens::new(self.queue, self.input, self.start, self.end)
+        tokens::new(self.queue, self.input, self.lines, self.start, self.end)
     }
 
     fn next_start(&mut self) {
@@ -88,14 +90,15 @@ impl<R: RuleType> FlatPairs<R> {
     fn next_start_from_end(&mut self) {
         self.end -= 1;
 
-        while self.end >= self.start && !self.is_start(self.end) {
+        while self.end > self.start && !self.is_start(self.end) {
             self.end -= 1;
         }
     }
 
+    // Returns whether a pair other than trivia starts at `index`.
     fn is_start(&self, index: usize) -> bool {
         match self.queue[index] {
-            QueueableToken::Start { .. } => true,
+            QueueableToken::Start { .. } => !queueable_token::is_trivia(&self.queue, index),
             QueueableToken::End { .. } => false,
         }
     }
@@ -105,11 +108,22 @@ impl<R: RuleType> Iterator for FlatPairs<R> {
     type Item = Pair<R>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.start < self.end && !self.is_start(self.start) {
+            self.next_start();
+        }
+
         if self.start >= self.end {
             return None;
         }
 
-        let pair = unsafe { pair::new(Rc::clone(&self.queue), self.input.clone(), self.start) };
+        let pair = unsafe {
+            pair::new(
+                Arc::clone(&self.queue),
+                self.input.clone(),
+                self.lines.clone(),
+                self.start,
+            )
+        };
 
         self.next_start();
 
@@ -125,7 +139,18 @@ impl<R: RuleType> DoubleEndedIterator for FlatPairs<R> {
 
         self.next_start_from_end();
 
-        let pair = unsafe { pair::new(Rc::clone(&self.queue), self.input.clone(), self.end) };
+        if !self.is_start(self.end) {
+            return None;
+        }
+
+        let pair = unsafe {
+            pair::new(
+                Arc::clone(&self.queue),
+                self.input.clone(),
+                self.lines.clone(),
+                self.end,
+            )
+        };
 
         Some(pair)
     }
@@ -142,8 +167,9 @@ impl<R: RuleType> fmt::Debug for FlatPairs<R> {
 impl<R: Clone> Clone for FlatPairs<R> {
     fn clone(&self) -> FlatPairs<R> {
         FlatPairs {
-            queue: Rc::clone(&self.queue),
+            queue: Arc::clone(&self.queue),
             input: self.input.clone(),
+            lines: self.lines.clone(),
             start: self.start,
             end: self.end,
         }
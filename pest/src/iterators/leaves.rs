@@ -0,0 +1,307 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+use std::fmt;
+use std::sync::Arc;
+
+use super::pair::{self, Pair};
+use super::queueable_token::{self, QueueableToken};
+use line_index::SharedLineIndex;
+use span::{self, Span};
+use RuleType;
+
+/// A leaf of the concrete syntax tree yielded by [`Leaves`].
+///
+/// [`Leaves`]: struct.Leaves.html
+#[derive(Clone, Eq, Hash, PartialEq)]
+pub enum Leaf<R> {
+    /// A `Pair` without inner pairs.
+    Pair(Pair<R>),
+    /// A trivia `Pair`, matched by `WHITESPACE` or `COMMENT`.
+    Trivia(Pair<R>),
+    /// Input matched outside of any pair, like a literal or `ANY`.
+    Text(Span),
+}
+
+impl<R: RuleType> Leaf<R> {
+    /// Returns the `Span` of the `Leaf`.
+    #[inline]
+    pub fn as_span(&self) -> Span {
+        match *self {
+            Leaf::Pair(ref pair) | Leaf::Trivia(ref pair) => pair.as_span(),
+            Leaf::Text(ref span) => span.clone(),
+        }
+    }
+
+    /// Captures a slice from the `&str` defined by the `Leaf`.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        match *self {
+            Leaf::Pair(ref pair) | Leaf::Trivia(ref pair) => pair.as_str(),
+            Leaf::Text(ref span) => span.as_str(),
+        }
+    }
+}
+
+impl<R: RuleType> fmt::Debug for Leaf<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Leaf::Pair(ref pair) => f.debug_tuple("Pair").field(pair).finish(),
+            Leaf::Trivia(ref pair) => f.debug_tuple("Trivia").field(pair).finish(),
+            Leaf::Text(ref span) => f.debug_tuple("Text").field(span).finish(),
+        }
+    }
+}
+
+/// An iterator over the leaves of the concrete syntax tree, in input order.
+///
+/// Every byte between the start and end of the iterated pairs belongs to exactly one leaf, so
+/// concatenating the leaves gives back the input they span. Trivia only show up when parsing with
+/// [`ParserConfig::track_trivia`].
+///
+/// [`ParserConfig::track_trivia`]: ../struct.ParserConfig.html#method.track_trivia
+#[derive(Clone)]
+pub struct Leaves<R> {
+    /// # Safety
+    ///
+    /// All `QueueableToken`s' `input_pos` must be valid character boundary indices into `input`.
+    queue: Arc<Vec<QueueableToken<R>>>,
+    input: Arc<str>,
+    lines: SharedLineIndex,
+    index: usize,
+    end: usize,
+    pos: usize,
+    // A leaf to yield right after the text before it.
+    pending: Option<Leaf<R>>,
+}
+
+/// # Safety
+///
+/// All `QueueableToken`s' `input_pos` must be valid character boundary indices into `input`.
+pub unsafe fn new<R: RuleType>(
+    queue: Arc<Vec<QueueableToken<R>>>,
+    input: Arc<str>,
+    lines: SharedLineIndex,
+    start: usize,
+    end: usize,
+) -> Leaves<R> {
+    let pos = if start < end { pos(&queue, start) } else { 0 };
+
+    Leaves {
+        queue,
+        input,
+        lines,
+        index: start,
+        end,
+        pos,
+        pending: None,
+    }
+}
+
+impl<R: RuleType> Leaves<R> {
+    // Returns the text from the last leaf up to `until`, if any, moving past it.
+    fn text(&mut self, until: usize) -> Option<Leaf<R>> {
+        if until > self.pos {
+            // Generated positions always come from Positions and are UTF-8 borders.
+            let span = unsafe {
+                span::Span::new_shared_unchecked(
+                    self.input.clone(),
+                    self.lines.clone(),
+                    self.pos,
+                    until,
+                )
+            };
+            self.pos = until;
+
+            Some(Leaf::Text(span))
+        } else {
+            None
+        }
+    }
+}
+
+impl<R: RuleType> Iterator for Leaves<R> {
+    type Item = Leaf<R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(leaf) = self.pending.take() {
+            return Some(leaf);
+        }
+
+        while self.index < self.end {
+            let index = self.index;
+
+            match self.queue[index] {
+                QueueableToken::Start {
+                    end_token_index,
+                    input_pos,
+                } => {
+                    let text = self.text(input_pos);
+
+                    if end_token_index == index + 1 {
+                        let pair = unsafe {
+                            pair::new(
+                                Arc::clone(&self.queue),
+                                self.input.clone(),
+                                self.lines.clone(),
+                                index,
+                            )
+                        };
+                        let leaf = if queueable_token::is_trivia(&self.queue, index) {
+                            Leaf::Trivia(pair)
+                        } else {
+                            Leaf::Pair(pair)
+                        };
+
+                        self.index = end_token_index + 1;
+                        self.pos = pos(&self.queue, end_token_index);
+
+                        if text.is_none() {
+                            return Some(leaf);
+                        }
+
+                        self.pending = Some(leaf);
+                    } else {
+                        self.index += 1;
+                    }
+
+                    if text.is_some() {
+                        return text;
+                    }
+                }
+                QueueableToken::End { input_pos, .. } => {
+                    self.index += 1;
+
+                    if let Some(text) = self.text(input_pos) {
+                        return Some(text);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<R: RuleType> fmt::Debug for Leaves<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+fn pos<R>(queue: &[QueueableToken<R>], index: usize) -> usize {
+    match queue[index] {
+        QueueableToken::Start { input_pos, .. } | QueueableToken::End { input_pos, .. } => {
+            input_pos
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::{state_with_config, ParseResult, ParserConfig, ParserState, Token};
+    use super::Leaf;
+    use alloc::boxed::Box;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use std::sync::Arc;
+
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    enum Rule {
+        a,
+        b,
+        WHITESPACE,
+    }
+
+    fn space(state: Box<ParserState<Rule>>) -> ParseResult<Box<ParserState<Rule>>> {
+        state.trivia(Rule::WHITESPACE, |s| s.match_string(" "))
+    }
+
+    // Parses ` a ( b b ) ` with every space recorded as trivia.
+    fn parse() -> super::super::Pairs<Rule> {
+        let input: Arc<str> = Arc::from(" a ( b b ) ");
+
+        state_with_config(input, ParserConfig::new().track_trivia(), |state| {
+            space(state)
+                .and_then(|s| s.rule(Rule::a, |s| s.match_string("a")))
+                .and_then(space)
+                .and_then(|s| {
+                    s.rule(Rule::a, |s| {
+                        s.match_string("(")
+                            .and_then(space)
+                            .and_then(|s| s.rule(Rule::b, |s| s.match_string("b")))
+                            .and_then(space)
+                            .and_then(|s| s.rule(Rule::b, |s| s.match_string("b")))
+                            .and_then(space)
+                            .and_then(|s| s.match_string(")"))
+                    })
+                })
+                .and_then(space)
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn pairs_skip_trivia() {
+        let pairs = parse();
+        let rules: Vec<_> = pairs.clone().map(|pair| pair.as_rule()).collect();
+        let rev: Vec<_> = pairs.clone().rev().map(|pair| pair.as_rule()).collect();
+
+        assert_eq!(rules, vec![Rule::a, Rule::a]);
+        assert_eq!(rev, vec![Rule::a, Rule::a]);
+        assert_eq!(pairs.as_str(), "a ( b b )");
+        assert_eq!(pairs.peek().unwrap().as_str(), "a");
+    }
+
+    #[test]
+    fn flat_pairs_skip_trivia() {
+        let pairs = parse();
+        let rules: Vec<_> = pairs.clone().flatten().map(|pair| pair.as_rule()).collect();
+        let rev: Vec<_> = pairs.flatten().rev().map(|pair| pair.as_rule()).collect();
+
+        assert_eq!(rules, vec![Rule::a, Rule::a, Rule::b, Rule::b]);
+        assert_eq!(rev, vec![Rule::b, Rule::b, Rule::a, Rule::a]);
+    }
+
+    #[test]
+    fn tokens_skip_trivia() {
+        let pairs = parse();
+        let tokens: Vec<_> = pairs.clone().tokens().collect();
+        let mut rev: Vec<_> = pairs.tokens().rev().collect();
+        rev.reverse();
+
+        assert_eq!(tokens.len(), 8);
+        assert_eq!(tokens, rev);
+        assert!(tokens.iter().all(|token| match *token {
+            Token::Start { rule, .. } | Token::End { rule, .. } => rule != Rule::WHITESPACE,
+        }));
+    }
+
+    #[test]
+    fn leaves() {
+        let leaves: Vec<_> = parse().leaves().collect();
+
+        assert_eq!(
+            leaves.iter().map(|leaf| leaf.as_str()).collect::<String>(),
+            " a ( b b ) "
+        );
+        assert_eq!(
+            leaves
+                .iter()
+                .map(|leaf| match *leaf {
+                    Leaf::Pair(_) => 'p',
+                    Leaf::Trivia(_) => 't',
+                    Leaf::Text(_) => 'x',
+                })
+                .collect::<String>(),
+            "tptxtptptxt"
+        );
+    }
+}
@@ -10,12 +10,14 @@
 //! Types and iterators for parser output.
 
 mod flat_pairs;
+mod leaves;
 mod pair;
 pub(crate) mod pairs;
 mod queueable_token;
 mod tokens;
 
 pub use self::flat_pairs::FlatPairs;
+pub use self::leaves::{Leaf, Leaves};
 pub use self::pair::Pair;
 pub use self::pairs::Pairs;
 pub(crate) use self::queueable_token::QueueableToken;
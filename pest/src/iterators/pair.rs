@@ -8,7 +8,6 @@
 // modified, or distributed except according to those terms.
 
 use alloc::format;
-use alloc::rc::Rc;
 #[cfg(feature = "pretty-print")]
 use alloc::string::String;
 use alloc::vec::Vec;
@@ -20,9 +19,11 @@ use std::sync::Arc;
 #[cfg(feature = "pretty-print")]
 use serde::ser::SerializeStruct;
 
+use super::leaves::{self, Leaves};
 use super::pairs::{self, Pairs};
-use super::queueable_token::QueueableToken;
+use super::queueable_token::{self, QueueableToken};
 use super::tokens::{self, Tokens};
+use line_index::SharedLineIndex;
 use span::{self, Span};
 use RuleType;
 
@@ -39,8 +40,9 @@ pub struct Pair<R> {
     /// # Safety
     ///
     /// All `QueueableToken`s' `input_pos` must be valid character boundary indices into `input`.
-    queue: Rc<Vec<QueueableToken<R>>>,
+    queue: Arc<Vec<QueueableToken<R>>>,
     input: Arc<str>,
+    lines: SharedLineIndex,
     /// Token index into `queue`.
     start: usize,
 }
@@ -49,13 +51,15 @@ pub struct Pair<R> {
 ///
 /// All `QueueableToken`s' `input_pos` must be valid character boundary indices into `input`.
 pub unsafe fn new<R: RuleType>(
-    queue: Rc<Vec<QueueableToken<R>>>,
+    queue: Arc<Vec<QueueableToken<R>>>,
     input: Arc<str>,
+    lines: SharedLineIndex,
     start: usize,
 ) -> Pair<R> {
     Pair {
         queue,
         input,
+        lines,
         start,
     }
 }
@@ -66,7 +70,6 @@ impl<R: RuleType> Pair<R> {
     /// # Examples
     ///
     /// ```
-    /// # use std::rc::Rc;
     /// # use pest;
     /// # use std::sync::Arc;
     /// # #[allow(non_camel_case_types)]
@@ -96,7 +99,6 @@ impl<R: RuleType> Pair<R> {
     /// # Examples
     ///
     /// ```
-    /// # use std::rc::Rc;
     /// # use pest;
     /// # use std::sync::Arc;
     /// # #[allow(non_camel_case_types)]
@@ -127,7 +129,6 @@ impl<R: RuleType> Pair<R> {
     /// # Examples
     ///
     /// ```
-    /// # use std::rc::Rc;
     /// # use pest;
     /// # use std::sync::Arc;
     /// # #[allow(non_camel_case_types)]
@@ -155,7 +156,6 @@ impl<R: RuleType> Pair<R> {
     /// # Examples
     ///
     /// ```
-    /// # use std::rc::Rc;
     /// # use pest;
     /// # use std::sync::Arc;
     /// # #[allow(non_camel_case_types)]
@@ -178,7 +178,9 @@ impl<R: RuleType> Pair<R> {
         let end = self.pos(self.pair());
 
         // Generated positions always come from Positions and are UTF-8 borders.
-        unsafe { span::Span::new_unchecked(self.input.clone(), start, end) }
+        unsafe {
+            span::Span::new_shared_unchecked(self.input.clone(), self.lines.clone(), start, end)
+        }
     }
 
     /// Returns the inner `Pairs` between the `Pair`, consuming it.
@@ -186,7 +188,6 @@ impl<R: RuleType> Pair<R> {
     /// # Examples
     ///
     /// ```
-    /// # use std::rc::Rc;
     /// # use pest;
     /// # use std::sync::Arc;
     /// # #[allow(non_camel_case_types)]
@@ -207,7 +208,7 @@ impl<R: RuleType> Pair<R> {
     pub fn into_inner(self) -> Pairs<R> {
         let pair = self.pair();
 
-        pairs::new(self.queue, self.input, self.start + 1, pair)
+        pairs::new(self.queue, self.input, self.lines, self.start + 1, pair)
     }
 
     /// Returns the `Tokens` for the `Pair`.
@@ -215,7 +216,6 @@ impl<R: RuleType> Pair<R> {
     /// # Examples
     ///
     /// ```
-    /// # use std::rc::Rc;
     /// # use pest;
     /// # use std::sync::Arc;
     /// # #[allow(non_camel_case_types)]
@@ -237,7 +237,82 @@ impl<R: RuleType> Pair<R> {
     pub fn tokens(self) -> Tokens<R> {
         let end = self.pair();
 
-        tokens::new(self.queue, self.input, self.start, end + 1)
+        tokens::new(self.queue, self.input, self.lines, self.start, end + 1)
+    }
+
+    /// Returns the trivia right before the `Pair`, like the comments documenting it. Trivia are
+    /// only recorded when parsing with [`ParserConfig::track_trivia`].
+    ///
+    /// [`ParserConfig::track_trivia`]: ../struct.ParserConfig.html#method.track_trivia
+    pub fn leading_trivia(&self) -> Vec<Pair<R>> {
+        let mut trivia = Vec::new();
+        let mut index = self.start;
+        let mut pos = self.pos(index);
+
+        while index > 0 {
+            match self.queue[index - 1] {
+                QueueableToken::End {
+                    start_token_index,
+                    input_pos,
+                    trivia: true,
+                    ..
+                } if input_pos == pos => {
+                    trivia.push(unsafe {
+                        new(
+                            Arc::clone(&self.queue),
+                            self.input.clone(),
+                            self.lines.clone(),
+                            start_token_index,
+                        )
+                    });
+                    index = start_token_index;
+                    pos = self.pos(index);
+                }
+                _ => break,
+            }
+        }
+
+        trivia.reverse();
+        trivia
+    }
+
+    /// Returns the trivia right after the `Pair`, like a comment ending its line. Trivia running
+    /// up to the next `Pair` are the leading trivia of that one instead.
+    pub fn trailing_trivia(&self) -> Vec<Pair<R>> {
+        let mut trivia = Vec::new();
+        let mut index = self.pair() + 1;
+        let mut pos = self.pos(self.pair());
+
+        while index < self.queue.len()
+            && queueable_token::is_trivia(&self.queue, index)
+            && self.pos(index) == pos
+        {
+            trivia.push(unsafe {
+                new(
+                    Arc::clone(&self.queue),
+                    self.input.clone(),
+                    self.lines.clone(),
+                    index,
+                )
+            });
+            pos = self.pos(index + 1);
+            index += 2;
+        }
+
+        match self.queue.get(index) {
+            Some(&QueueableToken::Start { input_pos, .. }) if input_pos == pos => Vec::new(),
+            _ => trivia,
+        }
+    }
+
+    /// Returns the [`Leaves`] of the `Pair`, which concatenate back to `as_str`.
+    ///
+    /// [`Leaves`]: struct.Leaves.html
+    #[inline]
+    pub fn leaves(self) -> Leaves<R> {
+        let end = self.pair();
+
+        unsafe { leaves::new(self.queue, self.input, self.lines, self.start, end + 1) }
     }
 
     /// Generates a string that stores the lexical information of `self` in
@@ -269,7 +344,7 @@ impl<R: RuleType> Pairs<R> {
     /// Create a new `Pairs` iterator containing just the single `Pair`.
     pub fn single(pair: Pair<R>) -> Self {
         let end = pair.pair();
-        pairs::new(pair.queue, pair.input, pair.start, end)
+        pairs::new(pair.queue, pair.input, pair.lines, pair.start, end)
     }
 }
 
@@ -310,7 +385,7 @@ impl<R: RuleType> fmt::Display for Pair<R> {
 
 impl<R: PartialEq> PartialEq for Pair<R> {
     fn eq(&self, other: &Pair<R>) -> bool {
-        Rc::ptr_eq(&self.queue, &other.queue)
+        Arc::ptr_eq(&self.queue, &other.queue)
             && Arc::ptr_eq(&self.input, &other.input)
             && self.start == other.start
     }
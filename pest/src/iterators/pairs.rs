@@ -8,7 +8,6 @@
 // modified, or distributed except according to those terms.
 
 use alloc::format;
-use alloc::rc::Rc;
 use alloc::string::String;
 use alloc::vec::Vec;
 use std::fmt;
@@ -20,9 +19,11 @@ use std::sync::Arc;
 use serde::ser::SerializeStruct;
 
 use super::flat_pairs::{self, FlatPairs};
+use super::leaves::{self, Leaves};
 use super::pair::{self, Pair};
-use super::queueable_token::QueueableToken;
+use super::queueable_token::{self, QueueableToken};
 use super::tokens::{self, Tokens};
+use line_index::SharedLineIndex;
 use RuleType;
 
 /// An iterator over [`Pair`]s. It is created by [`pest::state`] and [`Pair::into_inner`].
@@ -32,21 +33,24 @@ use RuleType;
 /// [`Pair::into_inner`]: struct.Pair.html#method.into_inner
 #[derive(Clone)]
 pub struct Pairs<R> {
-    queue: Rc<Vec<QueueableToken<R>>>,
+    queue: Arc<Vec<QueueableToken<R>>>,
     input: Arc<str>,
+    lines: SharedLineIndex,
     start: usize,
     end: usize,
 }
 
 pub fn new<R: RuleType>(
-    queue: Rc<Vec<QueueableToken<R>>>,
+    queue: Arc<Vec<QueueableToken<R>>>,
     input: Arc<str>,
+    lines: SharedLineIndex,
     start: usize,
     end: usize,
 ) -> Pairs<R> {
     Pairs {
         queue,
         input,
+        lines,
         start,
         end,
     }
@@ -60,7 +64,6 @@ impl<R: RuleType> Pairs<R> {
     /// # Examples
     ///
     /// ```
-    /// # use std::rc::Rc;
     /// # use pest;
     /// # use std::sync::Arc;
     /// # #[allow(non_camel_case_types)]
@@ -81,9 +84,11 @@ impl<R: RuleType> Pairs<R> {
     /// ```
     #[inline]
     pub fn as_str(&self) -> &str {
-        if self.start < self.end {
-            let start = self.pos(self.start);
-            let end = self.pos(self.end - 1);
+        let (first, last) = self.bounds();
+
+        if first < last {
+            let start = self.pos(first);
+            let end = self.pos(last - 1);
             // Generated positions always come from Positions and are UTF-8 borders.
             &self.input[start..end]
         } else {
@@ -97,7 +102,6 @@ impl<R: RuleType> Pairs<R> {
     /// # Examples
     ///
     /// ```
-    /// # use std::rc::Rc;
     /// # use pest;
     /// # use std::sync::Arc;
     /// # #[allow(non_camel_case_types)]
@@ -127,7 +131,6 @@ impl<R: RuleType> Pairs<R> {
     /// # Examples
     ///
     /// ```
-    /// # use std::rc::Rc;
     /// # use pest;
     /// # use std::sync::Arc;
     /// # #[allow(non_camel_case_types)]
@@ -150,7 +153,7 @@ impl<R: RuleType> Pairs<R> {
     /// ```
     #[inline]
     pub fn flatten(self) -> FlatPairs<R> {
-        unsafe { flat_pairs::new(self.queue, self.input, self.start, self.end) }
+        unsafe { flat_pairs::new(self.queue, self.input, self.lines, self.start, self.end) }
     }
 
     /// Returns the `Tokens` for the `Pairs`.
@@ -158,7 +161,6 @@ impl<R: RuleType> Pairs<R> {
     /// # Examples
     ///
     /// ```
-    /// # use std::rc::Rc;
     /// # use pest;
     /// # use std::sync::Arc;
     /// # #[allow(non_camel_case_types)]
@@ -178,19 +180,47 @@ impl<R: RuleType> Pairs<R> {
     /// ```
     #[inline]
     pub fn tokens(self) -> Tokens<R> {
-        tokens::new(self.queue, self.input, self.start, self.end)
+        tokens::new(self.queue, self.input, self.lines, self.start, self.end)
+    }
+
+    #[inline]
+    pub(crate) fn input(&self) -> &Arc<str> {
+        &self.input
+    }
+
+    #[inline]
+    pub(crate) fn lines(&self) -> &SharedLineIndex {
+        &self.lines
     }
 
     /// Peek at the first inner `Pair` without changing the position of this iterator.
     #[inline]
     pub fn peek(&self) -> Option<Pair<R>> {
-        if self.start < self.end {
-            Some(unsafe { pair::new(Rc::clone(&self.queue), self.input.clone(), self.start) })
+        let start = queueable_token::skip_trivia(&self.queue, self.start, self.end);
+
+        if start < self.end {
+            Some(unsafe {
+                pair::new(
+                    Arc::clone(&self.queue),
+                    self.input.clone(),
+                    self.lines.clone(),
+                    start,
+                )
+            })
         } else {
             None
         }
     }
 
+    /// Returns the [`Leaves`] of the `Pairs`, trivia around them included, from the start of the
+    /// first one to the end of the last one.
+    ///
+    /// [`Leaves`]: struct.Leaves.html
+    #[inline]
+    pub fn leaves(self) -> Leaves<R> {
+        unsafe { leaves::new(self.queue, self.input, self.lines, self.start, self.end) }
+    }
+
     /// Generates a string that stores the lexical information of `self` in
     /// a pretty-printed JSON format.
     #[cfg(feature = "pretty-print")]
@@ -198,6 +228,14 @@ impl<R: RuleType> Pairs<R> {
         ::serde_json::to_string_pretty(self).expect("Failed to pretty-print Pairs to json.")
     }
 
+    // Returns the range of tokens without the trivia at either end.
+    fn bounds(&self) -> (usize, usize) {
+        let start = queueable_token::skip_trivia(&self.queue, self.start, self.end);
+        let end = queueable_token::skip_trivia_back(&self.queue, start, self.end);
+
+        (start, end)
+    }
+
     fn pair(&self) -> usize {
         match self.queue[self.start] {
             QueueableToken::Start {
@@ -229,6 +267,8 @@ impl<R: RuleType> Iterator for Pairs<R> {
     type Item = Pair<R>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        self.start = queueable_token::skip_trivia(&self.queue, self.start, self.end);
+
         let pair = self.peek()?;
         self.start = self.pair() + 1;
         Some(pair)
@@ -237,13 +277,22 @@ impl<R: RuleType> Iterator for Pairs<R> {
 
 impl<R: RuleType> DoubleEndedIterator for Pairs<R> {
     fn next_back(&mut self) -> Option<Self::Item> {
+        self.end = queueable_token::skip_trivia_back(&self.queue, self.start, self.end);
+
         if self.end <= self.start {
             return None;
         }
 
         self.end = self.pair_from_end();
 
-        let pair = unsafe { pair::new(Rc::clone(&self.queue), self.input.clone(), self.end) };
+        let pair = unsafe {
+            pair::new(
+                Arc::clone(&self.queue),
+                self.input.clone(),
+                self.lines.clone(),
+                self.end,
+            )
+        };
 
         Some(pair)
     }
@@ -270,7 +319,7 @@ impl<R: RuleType> fmt::Display for Pairs<R> {
 
 impl<R: PartialEq> PartialEq for Pairs<R> {
     fn eq(&self, other: &Pairs<R>) -> bool {
-        Rc::ptr_eq(&self.queue, &other.queue)
+        Arc::ptr_eq(&self.queue, &other.queue)
             && Arc::ptr_eq(&self.input, &other.input)
             && self.start == other.start
             && self.end == other.end
@@ -294,8 +343,9 @@ impl<R: RuleType> ::serde::Serialize for Pairs<R> {
     where
         S: ::serde::Serializer,
     {
-        let start = self.pos(self.start);
-        let end = self.pos(self.end - 1);
+        let (first, last) = self.bounds();
+        let start = self.pos(first);
+        let end = self.pos(last - 1);
         let pairs = self.clone().collect::<Vec<_>>();
 
         let mut ser = serializer.serialize_struct("Pairs", 2)?;
@@ -309,6 +359,7 @@ impl<R: RuleType> ::serde::Serialize for Pairs<R> {
 mod tests {
     use super::super::super::macros::tests::*;
     use super::super::super::Parser;
+    use super::super::{FlatPairs, Pair, Pairs, Tokens};
     use alloc::borrow::ToOwned;
     use alloc::format;
     use alloc::vec;
@@ -428,4 +479,24 @@ mod tests {
             vec![Rule::c, Rule::a]
         );
     }
+
+    #[test]
+    fn pairs_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<Pairs<Rule>>();
+        assert_send_sync::<Pair<Rule>>();
+        assert_send_sync::<FlatPairs<Rule>>();
+        assert_send_sync::<Tokens<Rule>>();
+    }
+
+    #[test]
+    fn pairs_across_threads() {
+        let pairs = AbcParser::parse(Rule::a, Arc::from("abcde")).unwrap();
+        let rules = std::thread::spawn(move || pairs.map(|p| p.as_rule()).collect::<Vec<Rule>>())
+            .join()
+            .unwrap();
+
+        assert_eq!(rules, vec![Rule::a, Rule::c]);
+    }
 }
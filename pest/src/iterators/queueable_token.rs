@@ -23,5 +23,48 @@ pub enum QueueableToken<R> {
         start_token_index: usize,
         rule: R,
         input_pos: usize,
+        // Whether the pair is trivia recorded by `ParserState::trivia`, which is skipped over by
+        // the iterators and has no inner tokens.
+        trivia: bool,
     },
 }
+
+// Returns whether the pair whose `Start` token is at `index` is trivia.
+#[inline]
+pub fn is_trivia<R>(queue: &[QueueableToken<R>], index: usize) -> bool {
+    match queue[index] {
+        QueueableToken::Start {
+            end_token_index, ..
+        } => match queue[end_token_index] {
+            QueueableToken::End { trivia, .. } => trivia,
+            _ => unreachable!(),
+        },
+        QueueableToken::End { .. } => false,
+    }
+}
+
+// Returns the index of the first token from `start` on which is not part of a trivia pair, or
+// `end` if there is none.
+#[inline]
+pub fn skip_trivia<R>(queue: &[QueueableToken<R>], mut start: usize, end: usize) -> usize {
+    while start < end && is_trivia(queue, start) {
+        // Trivia pairs have no inner tokens.
+        start += 2;
+    }
+
+    start
+}
+
+// Returns the index right after the last token before `end` which is not part of a trivia pair,
+// or `start` if there is none.
+#[inline]
+pub fn skip_trivia_back<R>(queue: &[QueueableToken<R>], start: usize, mut end: usize) -> usize {
+    while end > start {
+        match queue[end - 1] {
+            QueueableToken::End { trivia: true, .. } => end -= 2,
+            _ => break,
+        }
+    }
+
+    end
+}
@@ -7,13 +7,13 @@
 // option. All files in the project carrying such notice may not be copied,
 // modified, or distributed except according to those terms.
 
-use alloc::rc::Rc;
 use alloc::vec::Vec;
 use std::fmt;
 use std::str;
 use std::sync::Arc;
 
-use super::queueable_token::QueueableToken;
+use super::queueable_token::{self, QueueableToken};
+use line_index::SharedLineIndex;
 use position;
 use token::Token;
 use RuleType;
@@ -28,16 +28,18 @@ pub struct Tokens<R> {
     /// # Safety:
     ///
     /// All `QueueableToken`s' `input_pos` must be valid character boundary indices into `input`.
-    queue: Rc<Vec<QueueableToken<R>>>,
+    queue: Arc<Vec<QueueableToken<R>>>,
     input: Arc<str>,
+    lines: SharedLineIndex,
     start: usize,
     end: usize,
 }
 
 // TODO(safety): QueueableTokens must be valid indices into input.
 pub fn new<R: RuleType>(
-    queue: Rc<Vec<QueueableToken<R>>>,
+    queue: Arc<Vec<QueueableToken<R>>>,
     input: Arc<str>,
+    lines: SharedLineIndex,
     start: usize,
     end: usize,
 ) -> Tokens<R> {
@@ -57,6 +59,7 @@ pub fn new<R: RuleType>(
     Tokens {
         queue,
         input,
+        lines,
         start,
         end,
     }
@@ -78,7 +81,11 @@ impl<R: RuleType> Tokens<R> {
                     rule,
                     // QueueableTokens are safely created.
                     pos: unsafe {
-                        position::Position::new_unchecked(self.input.clone(), input_pos)
+                        position::Position::new_unchecked(
+                            self.input.clone(),
+                            self.lines.clone(),
+                            input_pos,
+                        )
                     },
                 }
             }
@@ -89,7 +96,11 @@ impl<R: RuleType> Tokens<R> {
                     rule,
                     // QueueableTokens are safely created.
                     pos: unsafe {
-                        position::Position::new_unchecked(self.input.clone(), input_pos)
+                        position::Position::new_unchecked(
+                            self.input.clone(),
+                            self.lines.clone(),
+                            input_pos,
+                        )
                     },
                 }
             }
@@ -101,6 +112,8 @@ impl<R: RuleType> Iterator for Tokens<R> {
     type Item = Token<R>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        self.start = queueable_token::skip_trivia(&self.queue, self.start, self.end);
+
         if self.start >= self.end {
             return None;
         }
@@ -115,6 +128,8 @@ impl<R: RuleType> Iterator for Tokens<R> {
 
 impl<R: RuleType> DoubleEndedIterator for Tokens<R> {
     fn next_back(&mut self) -> Option<Self::Item> {
+        self.end = queueable_token::skip_trivia_back(&self.queue, self.start, self.end);
+
         if self.end <= self.start {
             return None;
         }
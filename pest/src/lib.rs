@@ -67,29 +67,55 @@
 extern crate alloc;
 extern crate ucd_trie;
 
-#[cfg(feature = "pretty-print")]
+#[cfg(any(feature = "pretty-print", feature = "deserialize"))]
 extern crate serde;
 #[cfg(feature = "pretty-print")]
 extern crate serde_json;
 
+pub use bytes::state_bytes;
+pub use incremental::{Edit, IncrementalParse};
+pub use line_index::{LineIndex, Utf16Position, Utf16Range};
 pub use parser::Parser;
-pub use parser_state::{state, Atomicity, Lookahead, MatchDir, ParseResult, ParserState};
+pub use parser_config::{Memoization, ParserConfig, CANCEL_INTERVAL};
+pub use parser_state::{
+    state, state_with_config, state_with_recovery, Atomicity, Lookahead, MatchDir, ParseResult,
+    ParserState,
+};
 pub use position::Position;
+pub use recovery::Recovered;
 pub use span::{Lines, Span};
 use std::fmt::Debug;
 use std::hash::Hash;
 pub use token::Token;
 
+pub mod bytes;
+#[cfg(feature = "deserialize")]
+pub mod de;
 pub mod error;
+pub mod from_pairs;
+mod incremental;
 pub mod iterators;
+mod line_index;
 mod macros;
+mod memo;
 mod parser;
+mod parser_config;
 mod parser_state;
 mod position;
+pub mod pratt_parser;
 pub mod prec_climber;
+#[cfg(feature = "trace")]
+pub mod profile;
+pub mod query;
+mod recovery;
+#[cfg(feature = "sarif")]
+pub mod sarif;
 mod span;
 mod stack;
 mod token;
+#[cfg(feature = "trace")]
+pub mod trace;
+pub mod tree;
 #[doc(hidden)]
 pub mod unicode;
 
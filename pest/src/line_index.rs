@@ -0,0 +1,359 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use std::fmt;
+use std::ops::Range;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// The byte offsets at which the lines of an input start, used to look up lines and columns in
+/// `O(log n)` instead of scanning the input.
+///
+/// Lines end right after a `\n`, so a `\r\n` ending belongs to the line it ends, while a lone `\r`
+/// does not end a line. Lines and columns are counted from `1`, columns in `char`s.
+///
+/// `Position`s and `Span`s share the `LineIndex` of their input with every `Position` and `Span`
+/// derived from them, so it is only built once per parse, the first time a line is looked up.
+///
+/// # Examples
+///
+/// ```
+/// # use pest::LineIndex;
+/// let input = "a\r\nbc\nd";
+/// let index = LineIndex::new(input);
+///
+/// assert_eq!(index.line_count(), 3);
+/// assert_eq!(index.line_col(input, 4), (2, 2));
+/// assert_eq!(index.line_start(3), Some(6));
+/// assert_eq!(index.line_text(input, 1), Some("a\r\n"));
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineIndex {
+    line_starts: Vec<usize>,
+    // The char offset of every line start.
+    char_starts: Vec<usize>,
+    len: usize,
+}
+
+impl LineIndex {
+    /// Creates a new `LineIndex` by scanning `input` once.
+    pub fn new(input: &str) -> LineIndex {
+        let mut line_starts = vec![0];
+        let mut char_starts = vec![0];
+
+        for (chars, (i, c)) in input.char_indices().enumerate() {
+            if c == '\n' {
+                line_starts.push(i + 1);
+                char_starts.push(chars + 1);
+            }
+        }
+
+        LineIndex {
+            line_starts,
+            char_starts,
+            len: input.len(),
+        }
+    }
+
+    /// Returns the number of lines, which is one more than the number of `\n`s.
+    #[inline]
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Returns the line containing the byte position `pos`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos` is past the end of the input.
+    #[inline]
+    pub fn line(&self, pos: usize) -> usize {
+        if pos > self.len {
+            panic!("position out of bounds");
+        }
+
+        match self.line_starts.binary_search(&pos) {
+            Ok(i) => i + 1,
+            Err(i) => i,
+        }
+    }
+
+    /// Returns the byte position at which `line` starts, or `None` if there is no such line.
+    #[inline]
+    pub fn line_start(&self, line: usize) -> Option<usize> {
+        line.checked_sub(1)
+            .and_then(|i| self.line_starts.get(i))
+            .cloned()
+    }
+
+    /// Returns the byte range of `line`, including its line ending, or `None` if there is no such
+    /// line.
+    #[inline]
+    pub fn line_range(&self, line: usize) -> Option<Range<usize>> {
+        let start = self.line_start(line)?;
+        let end = self.line_start(line + 1).unwrap_or(self.len);
+
+        Some(start..end)
+    }
+
+    /// Returns the text of `line` in `input`, including its line ending, or `None` if there is no
+    /// such line.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `input` is not the input this `LineIndex` was built from.
+    #[inline]
+    pub fn line_text<'i>(&self, input: &'i str, line: usize) -> Option<&'i str> {
+        self.line_range(line).map(|range| &input[range])
+    }
+
+    /// Returns the line and column of the byte position `pos` in `input`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos` is past the end of the input or if `input` is not the input this
+    /// `LineIndex` was built from.
+    #[inline]
+    pub fn line_col(&self, input: &str, pos: usize) -> (usize, usize) {
+        let line = self.line(pos);
+        let start = self.line_starts[line - 1];
+
+        (line, input[start..pos].chars().count() + 1)
+    }
+
+    /// Returns the line and UTF-16 column of the byte position `pos` in `input`, with the column
+    /// counted in UTF-16 code units.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos` is past the end of the input or if `input` is not the input this
+    /// `LineIndex` was built from.
+    #[inline]
+    pub fn line_col_utf16(&self, input: &str, pos: usize) -> (usize, usize) {
+        let line = self.line(pos);
+        let start = self.line_starts[line - 1];
+        let col: usize = input[start..pos].chars().map(char::len_utf16).sum();
+
+        (line, col + 1)
+    }
+
+    /// Returns the number of `char`s in `input` before the byte position `pos`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos` is past the end of the input or if `input` is not the input this
+    /// `LineIndex` was built from.
+    #[inline]
+    pub fn char_offset(&self, input: &str, pos: usize) -> usize {
+        let line = self.line(pos);
+        let start = self.line_starts[line - 1];
+
+        self.char_starts[line - 1] + input[start..pos].chars().count()
+    }
+
+    /// Returns the byte position at `line` and `char` column `col` in `input`, or `None` if it is
+    /// not in the input.
+    #[inline]
+    pub fn pos_of_line_col(&self, input: &str, line: usize, col: usize) -> Option<usize> {
+        self.pos_in_line(input, line, col.checked_sub(1)?, |_| 1)
+    }
+
+    /// Returns the byte position at `line` and UTF-16 column `col` in `input`, or `None` if it is
+    /// not in the input or splits a surrogate pair.
+    #[inline]
+    pub fn pos_of_line_col_utf16(&self, input: &str, line: usize, col: usize) -> Option<usize> {
+        self.pos_in_line(input, line, col.checked_sub(1)?, char::len_utf16)
+    }
+
+    /// Returns the byte position after the first `offset` `char`s of `input`, or `None` if
+    /// `input` is shorter.
+    #[inline]
+    pub fn pos_of_char_offset(&self, input: &str, offset: usize) -> Option<usize> {
+        let line = match self.char_starts.binary_search(&offset) {
+            Ok(i) => i + 1,
+            Err(i) => i,
+        };
+
+        self.pos_in_line(input, line, offset - self.char_starts[line - 1], |_| 1)
+    }
+
+    // Returns the byte position `units` into `line`, where every `char` counts as `width` units.
+    // Positions past the line ending belong to the next line and are rejected.
+    fn pos_in_line(
+        &self,
+        input: &str,
+        line: usize,
+        units: usize,
+        width: fn(char) -> usize,
+    ) -> Option<usize> {
+        let range = self.line_range(line)?;
+        let mut pos = range.start;
+        let mut counted = 0;
+
+        for c in input[range.clone()].chars() {
+            if counted >= units {
+                break;
+            }
+
+            counted += width(c);
+            pos += c.len_utf8();
+        }
+
+        if counted != units || pos == range.end && range.end != self.len {
+            return None;
+        }
+
+        Some(pos)
+    }
+}
+
+// The `LineIndex` of an input, built the first time it is needed and shared by all clones. It
+// lives as long as the `Position`s and `Span`s into the input, so it never outlives it.
+#[derive(Clone, Default)]
+pub(crate) struct SharedLineIndex(Arc<Mutex<Option<Arc<LineIndex>>>>);
+
+impl fmt::Debug for SharedLineIndex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SharedLineIndex").finish()
+    }
+}
+
+impl SharedLineIndex {
+    // Returns the `LineIndex` of `input`, which must be the input this is shared for.
+    pub(crate) fn get(&self, input: &str) -> Arc<LineIndex> {
+        let mut index = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+
+        Arc::clone(index.get_or_insert_with(|| Arc::new(LineIndex::new(input))))
+    }
+}
+
+/// A position as used by the [Language Server Protocol][1]: a `0`-based line and a `0`-based
+/// column counted in UTF-16 code units.
+///
+/// [1]: https://microsoft.github.io/language-server-protocol/specification#position
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Utf16Position {
+    /// `0`-based line
+    pub line: u32,
+    /// `0`-based column in UTF-16 code units
+    pub character: u32,
+}
+
+/// A range as used by the [Language Server Protocol][1], going from `start` to the exclusive
+/// `end`.
+///
+/// [1]: https://microsoft.github.io/language-server-protocol/specification#range
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Utf16Range {
+    /// Start of the range
+    pub start: Utf16Position,
+    /// Exclusive end of the range
+    pub end: Utf16Position,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_col() {
+        let input = "a\rb\nc\r\nd嗨";
+        let index = LineIndex::new(input);
+
+        let line_cols: Vec<_> = [0, 1, 2, 3, 4, 5, 6, 7, 8, 11]
+            .iter()
+            .map(|&pos| index.line_col(input, pos))
+            .collect();
+
+        assert_eq!(
+            line_cols,
+            vec![
+                (1, 1),
+                (1, 2),
+                (1, 3),
+                (1, 4),
+                (2, 1),
+                (2, 2),
+                (2, 3),
+                (3, 1),
+                (3, 2),
+                (3, 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn lines() {
+        let input = "a\n\r\nb";
+        let index = LineIndex::new(input);
+
+        assert_eq!(index.line_count(), 3);
+        assert_eq!(index.line_start(0), None);
+        assert_eq!(index.line_start(2), Some(2));
+        assert_eq!(index.line_range(3), Some(4..5));
+        assert_eq!(index.line_text(input, 2), Some("\r\n"));
+        assert_eq!(index.line_text(input, 4), None);
+    }
+
+    #[test]
+    fn trailing_new_line() {
+        let input = "a\n";
+        let index = LineIndex::new(input);
+
+        assert_eq!(index.line_count(), 2);
+        assert_eq!(index.line(1), 1);
+        assert_eq!(index.line(2), 2);
+        assert_eq!(index.line_text(input, 2), Some(""));
+    }
+
+    #[test]
+    fn astral_round_trip() {
+        let input = "😀\n𝄞a\r\n";
+        let index = LineIndex::new(input);
+
+        assert_eq!(index.line_col_utf16(input, 4), (1, 3));
+        assert_eq!(index.line_col_utf16(input, 9), (2, 3));
+        assert_eq!(index.char_offset(input, 9), 3);
+
+        for (pos, _) in input.char_indices().chain(Some((input.len(), ' '))) {
+            let (line, col) = index.line_col(input, pos);
+            let (line16, col16) = index.line_col_utf16(input, pos);
+
+            assert_eq!(index.pos_of_line_col(input, line, col), Some(pos));
+            assert_eq!(index.pos_of_line_col_utf16(input, line16, col16), Some(pos));
+            assert_eq!(
+                index.pos_of_char_offset(input, index.char_offset(input, pos)),
+                Some(pos)
+            );
+        }
+
+        assert_eq!(index.pos_of_line_col_utf16(input, 1, 2), None);
+        assert_eq!(index.pos_of_line_col(input, 1, 3), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "position out of bounds")]
+    fn out_of_bounds() {
+        LineIndex::new("a").line(2);
+    }
+
+    #[test]
+    fn shared() {
+        let input = "a\nb";
+        let shared = SharedLineIndex::default();
+
+        assert!(Arc::ptr_eq(&shared.get(input), &shared.clone().get(input)));
+        assert!(!Arc::ptr_eq(
+            &shared.get(input),
+            &SharedLineIndex::default().get(input)
+        ));
+        assert_eq!(*shared.get(input), LineIndex::new(input));
+    }
+}
@@ -0,0 +1,295 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use std::collections::HashMap;
+use std::ops::Range;
+
+use iterators::QueueableToken;
+use parser_state::{Atomicity, Attempts, Lookahead};
+use RuleType;
+
+/// Everything that influences the outcome of a rule besides the input itself.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct MemoKey<R> {
+    pub rule: R,
+    pub pos: usize,
+    pub atomicity: Atomicity,
+    pub lookahead: Lookahead,
+}
+
+/// The cached outcome of a rule. Everything but the attempts is relative to the position the rule
+/// started at, so that entries can be moved around when the input is edited.
+#[derive(Debug)]
+pub struct MemoEntry<R> {
+    pub success: bool,
+    /// Number of bytes consumed by the rule.
+    pub len: usize,
+    /// Tokens pushed by the rule, with indices relative to the first of them.
+    pub tokens: Vec<QueueableToken<R>>,
+    pub attempts: Attempts<R>,
+    /// Number of bytes the rule looked at, the end of input counting as one byte.
+    pub reach: usize,
+    /// Whether the outcome depends on how far the rule starts from the start of input.
+    pub anchored: bool,
+}
+
+/// The longest match so far of a left-recursive rule being grown by `ParserState::left_recursive`,
+/// relative to the position the rule started at.
+#[derive(Debug)]
+pub struct Seed<R> {
+    /// Number of bytes consumed by the rule.
+    pub len: usize,
+    /// Tokens pushed by the rule, with indices relative to the first of them.
+    pub tokens: Vec<QueueableToken<R>>,
+}
+
+/// Where an entry starts, from the start of input before the gap of a `Memo`, and from the end of
+/// input after it.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum Offset {
+    Start(usize),
+    End(usize),
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+struct Slot<R> {
+    rule: R,
+    offset: Offset,
+    atomicity: Atomicity,
+    lookahead: Lookahead,
+}
+
+/// Packrat memoization table of a `ParserState`.
+///
+/// Entries are keyed by their distance to the start of input if they start before the gap, and
+/// by their distance to the end of input otherwise. Edits are made at the gap, so that only the
+/// entries between the previous gap and the edit need to be moved.
+#[derive(Debug)]
+pub struct Memo<R> {
+    entries: HashMap<Slot<R>, MemoEntry<R>>,
+    gap: usize,
+    len: usize,
+}
+
+impl<R: RuleType> Memo<R> {
+    /// Creates an empty `Memo`.
+    pub fn new() -> Self {
+        Memo {
+            entries: HashMap::new(),
+            gap: usize::MAX,
+            len: 0,
+        }
+    }
+
+    /// Returns the cached outcome for `key`, if any.
+    pub fn get(&self, key: &MemoKey<R>) -> Option<&MemoEntry<R>> {
+        self.entries.get(&self.slot(key))
+    }
+
+    /// Caches the outcome for `key`.
+    pub fn insert(&mut self, key: MemoKey<R>, entry: MemoEntry<R>) {
+        let slot = self.slot(&key);
+        self.entries.insert(slot, entry);
+    }
+
+    /// Adapts the table of an input of `len` bytes to the replacement of the bytes in `range` by
+    /// `text_len` other bytes. Entries which looked at the replaced bytes are dropped, and the
+    /// ones after them are moved.
+    pub fn edit(&mut self, len: usize, range: Range<usize>, text_len: usize) {
+        self.len = len;
+
+        let new_len = len - range.len() + text_len;
+        let mut moved = vec![];
+
+        {
+            let Memo {
+                ref mut entries,
+                gap,
+                ..
+            } = *self;
+
+            entries.retain(|slot, entry| {
+                let pos = match slot.offset {
+                    Offset::Start(pos) => pos,
+                    Offset::End(distance) => len - distance,
+                };
+
+                let new_pos = if pos + entry.reach <= range.start {
+                    pos
+                } else if pos >= range.end && !entry.anchored {
+                    let new_pos = pos - range.end + range.start + text_len;
+                    entry.attempts.pos = entry.attempts.pos - range.end + range.start + text_len;
+                    new_pos
+                } else {
+                    return false;
+                };
+
+                // The new gap is at the start of the edit.
+                if (pos < gap) != (new_pos < range.start) {
+                    moved.push((*slot, new_pos));
+                }
+
+                true
+            });
+        }
+
+        self.gap = range.start;
+        self.len = new_len;
+
+        for (slot, pos) in moved {
+            let entry = self.entries.remove(&slot).unwrap();
+            let key = MemoKey {
+                rule: slot.rule,
+                pos,
+                atomicity: slot.atomicity,
+                lookahead: slot.lookahead,
+            };
+
+            self.insert(key, entry);
+        }
+    }
+
+    fn slot(&self, key: &MemoKey<R>) -> Slot<R> {
+        let offset = if key.pos < self.gap {
+            Offset::Start(key.pos)
+        } else {
+            Offset::End(self.len - key.pos)
+        };
+
+        Slot {
+            rule: key.rule,
+            offset,
+            atomicity: key.atomicity,
+            lookahead: key.lookahead,
+        }
+    }
+}
+
+/// Moves the token indices of `token` by `from` and then `to`, and its positions by `pos_from` and
+/// then `pos_to`, translating a token between a queue and a `MemoEntry`.
+#[inline]
+pub fn relocate<R: RuleType>(
+    token: &QueueableToken<R>,
+    (from, to): (usize, usize),
+    (pos_from, pos_to): (usize, usize),
+) -> QueueableToken<R> {
+    match *token {
+        QueueableToken::Start {
+            end_token_index,
+            input_pos,
+        } => QueueableToken::Start {
+            end_token_index: end_token_index - from + to,
+            input_pos: input_pos - pos_from + pos_to,
+        },
+        QueueableToken::End {
+            start_token_index,
+            rule,
+            input_pos,
+            trivia,
+        } => QueueableToken::End {
+            start_token_index: start_token_index - from + to,
+            rule,
+            input_pos: input_pos - pos_from + pos_to,
+            trivia,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(pos: usize) -> MemoKey<()> {
+        MemoKey {
+            rule: (),
+            pos,
+            atomicity: Atomicity::NonAtomic,
+            lookahead: Lookahead::None,
+        }
+    }
+
+    fn entry(pos: usize, reach: usize, anchored: bool) -> MemoEntry<()> {
+        MemoEntry {
+            success: true,
+            len: reach,
+            tokens: vec![],
+            attempts: Attempts {
+                pos,
+                positives: vec![],
+                negatives: vec![],
+                terminals: vec![],
+                stack: None,
+            },
+            reach,
+            anchored,
+        }
+    }
+
+    #[test]
+    fn edit_keeps_before() {
+        let mut memo = Memo::new();
+        memo.insert(key(0), entry(0, 2, true));
+        memo.insert(key(1), entry(1, 2, false));
+
+        memo.edit(10, 3..5, 4);
+
+        assert!(memo.get(&key(0)).is_some());
+        assert!(memo.get(&key(1)).is_some());
+    }
+
+    #[test]
+    fn edit_moves_after() {
+        let mut memo = Memo::new();
+        memo.insert(key(5), entry(6, 2, false));
+        memo.insert(key(8), entry(8, 3, false));
+
+        memo.edit(11, 3..5, 4);
+
+        assert!(memo.get(&key(5)).is_none());
+        assert_eq!(memo.get(&key(7)).unwrap().attempts.pos, 8);
+        assert_eq!(memo.get(&key(10)).unwrap().attempts.pos, 10);
+    }
+
+    #[test]
+    fn edit_drops_overlapping() {
+        let mut memo = Memo::new();
+        memo.insert(key(0), entry(0, 4, false));
+        memo.insert(key(4), entry(4, 1, false));
+        memo.insert(key(5), entry(5, 1, true));
+
+        memo.edit(10, 3..5, 0);
+
+        assert!(memo.get(&key(0)).is_none());
+        assert!(memo.get(&key(3)).is_none());
+        assert!(memo.get(&key(4)).is_none());
+    }
+
+    #[test]
+    fn edit_moves_gap() {
+        let mut memo = Memo::new();
+        memo.insert(key(2), entry(2, 1, false));
+        memo.insert(key(6), entry(6, 1, false));
+
+        memo.edit(10, 8..8, 2);
+        memo.edit(12, 0..1, 0);
+        memo.edit(11, 4..4, 3);
+
+        assert_eq!(memo.get(&key(1)).unwrap().attempts.pos, 1);
+        assert_eq!(memo.get(&key(8)).unwrap().attempts.pos, 8);
+
+        memo.insert(key(12), entry(12, 1, false));
+        memo.edit(14, 10..10, 1);
+
+        assert!(memo.get(&key(1)).is_some());
+        assert!(memo.get(&key(8)).is_some());
+        assert_eq!(memo.get(&key(13)).unwrap().attempts.pos, 13);
+    }
+}
@@ -7,8 +7,13 @@
 // option. All files in the project carrying such notice may not be copied,
 // modified, or distributed except according to those terms.
 
+use bytes::{self, BytePairs};
 use error::Error;
+use incremental::{Edit, IncrementalParse};
 use iterators::Pairs;
+use memo::Memo;
+use parser_config::ParserConfig;
+use recovery::Recovered;
 use std::sync::Arc;
 use RuleType;
 
@@ -16,4 +21,85 @@ use RuleType;
 pub trait Parser<R: RuleType> {
     /// Parses a `&str` starting from `rule`.
     fn parse(rule: R, input: Arc<str>) -> Result<Pairs<R>, Error<R>>;
+
+    /// Parses a `&str` starting from `rule` with a `ParserState` configured by `config`.
+    ///
+    /// Parsers generated by `pest_derive` honor `config`. The default implementation ignores it
+    /// and calls [`parse`]; manual implementations should use [`pest::state_with_config`].
+    ///
+    /// [`parse`]: #tymethod.parse
+    /// [`pest::state_with_config`]: fn.state_with_config.html
+    fn parse_with_config(
+        rule: R,
+        input: Arc<str>,
+        config: ParserConfig<R>,
+    ) -> Result<Pairs<R>, Error<R>> {
+        let _ = config;
+        Self::parse(rule, input)
+    }
+
+    /// Parses a `&str` starting from `rule` with a `ParserState` configured by `config`, collecting
+    /// every error recovered from instead of stopping at the first one.
+    ///
+    /// Parsers generated by `pest_derive` recover where the grammar uses `RECOVER`. The default
+    /// implementation calls [`parse_with_config`] and never recovers; manual implementations
+    /// should use [`pest::state_with_recovery`].
+    ///
+    /// [`parse_with_config`]: #method.parse_with_config
+    /// [`pest::state_with_recovery`]: fn.state_with_recovery.html
+    fn parse_with_recovery(rule: R, input: Arc<str>, config: ParserConfig<R>) -> Recovered<R> {
+        Recovered::from(Self::parse_with_config(rule, input, config))
+    }
+
+    /// Parses bytes starting from `rule`. See the [`bytes`] module for how the grammar applies to
+    /// them.
+    ///
+    /// [`bytes`]: bytes/index.html
+    fn parse_bytes(rule: R, input: Arc<[u8]>) -> Result<BytePairs<R>, Error<R>> {
+        Self::parse_bytes_with_config(rule, input, ParserConfig::default())
+    }
+
+    /// Parses bytes starting from `rule` with a `ParserState` configured by `config`, like
+    /// [`parse_with_config`].
+    ///
+    /// [`parse_with_config`]: #method.parse_with_config
+    fn parse_bytes_with_config(
+        rule: R,
+        input: Arc<[u8]>,
+        config: ParserConfig<R>,
+    ) -> Result<BytePairs<R>, Error<R>> {
+        bytes::parse(input, config, |input, config| {
+            Self::parse_with_config(rule, input, config)
+        })
+    }
+
+    /// Parses a `&str` starting from `rule` like [`parse_with_config`], keeping what is needed to
+    /// bring the result up to date with [`reparse`] once the input gets edited.
+    ///
+    /// Only the rules memoized by `config` are reused when reparsing. Memoizing every rule reuses
+    /// as much as possible, but slows parsing down and keeps a lot of memory around; memoizing
+    /// the rules of the items that make up a file, like statements or declarations, is usually
+    /// enough for only the items containing an edit to be parsed again.
+    ///
+    /// [`parse_with_config`]: #method.parse_with_config
+    /// [`reparse`]: #method.reparse
+    fn parse_incremental(rule: R, input: Arc<str>, config: ParserConfig<R>) -> IncrementalParse<R> {
+        IncrementalParse::new(rule, input, config, Memo::new(), Self::parse_with_config)
+    }
+
+    /// Applies `edits` to the input of `previous` and parses the result again, reusing the
+    /// outcome of every memoized rule the edits did not affect. The result is the same as the one
+    /// of [`parse_with_config`] on the edited input.
+    ///
+    /// The edits are applied in order, the range of every edit referring to the input as modified
+    /// by the edits before it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range of an edit is out of bounds, or does not lie on `char` boundaries.
+    ///
+    /// [`parse_with_config`]: #method.parse_with_config
+    fn reparse(previous: IncrementalParse<R>, edits: &[Edit]) -> IncrementalParse<R> {
+        previous.reparse(edits, Self::parse_with_config)
+    }
 }
@@ -0,0 +1,505 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use memo::Memo;
+#[cfg(feature = "trace")]
+use trace::{SharedTracer, Tracer};
+use RuleType;
+
+/// The set of rules whose results a [`ParserState`] caches.
+///
+/// [`ParserState`]: struct.ParserState.html
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Memoization<R> {
+    /// No rule is memoized
+    None,
+    /// Every rule is memoized
+    All,
+    /// Only the listed rules are memoized
+    Rules(Vec<R>),
+}
+
+/// Runtime configuration of a [`ParserState`]. It is passed to [`pest::state_with_config`] or
+/// [`Parser::parse_with_config`].
+///
+/// [`ParserState`]: struct.ParserState.html
+/// [`pest::state_with_config`]: fn.state_with_config.html
+/// [`Parser::parse_with_config`]: trait.Parser.html#method.parse_with_config
+#[derive(Clone, Debug)]
+pub struct ParserConfig<R> {
+    memoization: Memoization<R>,
+    // The rules of `Memoization::Rules`, which are looked up on every rule call.
+    memoized: HashSet<R>,
+    max_depth: Option<usize>,
+    max_calls: Option<usize>,
+    max_steps: Option<usize>,
+    cancel: Option<Cancel>,
+    track_terminals: bool,
+    track_rule_stack: bool,
+    track_trivia: bool,
+    fold_unicode_case: bool,
+    // Whether every `char` of the input stands for a byte, as decoded by `ByteInput`.
+    byte_input: bool,
+    // Memoization table to start from, and to hand back once parsing is done.
+    memo: Option<Arc<Mutex<Memo<R>>>>,
+    #[cfg(feature = "trace")]
+    tracer: Option<SharedTracer<R>>,
+}
+
+impl<R: RuleType> ParserConfig<R> {
+    /// Creates a `ParserConfig` with every option disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::ParserConfig;
+    /// let config: ParserConfig<()> = ParserConfig::new();
+    /// ```
+    pub fn new() -> ParserConfig<R> {
+        ParserConfig {
+            memoization: Memoization::None,
+            memoized: HashSet::new(),
+            max_depth: None,
+            max_calls: None,
+            max_steps: None,
+            cancel: None,
+            track_terminals: false,
+            track_rule_stack: false,
+            track_trivia: false,
+            fold_unicode_case: false,
+            byte_input: false,
+            memo: None,
+            #[cfg(feature = "trace")]
+            tracer: None,
+        }
+    }
+
+    /// Enables packrat memoization for every rule. Each `(rule, position)` pair is then evaluated
+    /// at most once, which bounds the parsing time of heavily backtracking grammars by the size
+    /// of the input times the number of rules, at the cost of memory.
+    ///
+    /// Rules which are entered while the stack is not empty, or which leave values on the stack,
+    /// are never cached since their result depends on more than the input.
+    ///
+    /// Caching has a cost of its own, so grammars that rarely backtrack, like JSON, get slower.
+    /// Memoizing only the rules that are re-tried at the same position with [`memoize`] is usually
+    /// the better trade-off.
+    ///
+    /// [`memoize`]: #method.memoize
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::{Memoization, ParserConfig};
+    /// let config: ParserConfig<()> = ParserConfig::new().memoize_all();
+    /// assert_eq!(config.memoization(), &Memoization::All);
+    /// ```
+    pub fn memoize_all(mut self) -> ParserConfig<R> {
+        self.memoization = Memoization::All;
+        self.memoized.clear();
+        self
+    }
+
+    /// Enables packrat memoization for `rule` only. See [`memoize_all`] for details.
+    ///
+    /// [`memoize_all`]: #method.memoize_all
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::{Memoization, ParserConfig};
+    /// # #[allow(non_camel_case_types)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// enum Rule {
+    ///     a,
+    ///     b
+    /// }
+    ///
+    /// let config = ParserConfig::new().memoize(Rule::a).memoize(Rule::b);
+    /// assert_eq!(config.memoization(), &Memoization::Rules(vec![Rule::a, Rule::b]));
+    /// ```
+    pub fn memoize(mut self, rule: R) -> ParserConfig<R> {
+        match self.memoization {
+            Memoization::None => {
+                self.memoization = Memoization::Rules(vec![rule]);
+                self.memoized.insert(rule);
+            }
+            Memoization::All => (),
+            Memoization::Rules(ref mut rules) => {
+                if self.memoized.insert(rule) {
+                    rules.push(rule);
+                }
+            }
+        }
+
+        self
+    }
+
+    /// Limits the number of nested rule calls to `max`. Since every rule call recurses, this
+    /// bounds the native stack used by the parser on deeply nested input. Calls to silent rules
+    /// count towards the limit too.
+    ///
+    /// Exceeding the limit aborts parsing with an [`ErrorVariant::LimitError`].
+    ///
+    /// [`ErrorVariant::LimitError`]: error/enum.ErrorVariant.html#variant.LimitError
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::ParserConfig;
+    /// let config: ParserConfig<()> = ParserConfig::new().max_depth(256);
+    /// assert_eq!(config.depth_limit(), Some(256));
+    /// ```
+    pub fn max_depth(mut self, max: usize) -> ParserConfig<R> {
+        self.max_depth = Some(max);
+        self
+    }
+
+    /// Limits the total number of rule calls to `max`, bounding the time spent on a single
+    /// parse. Calls to silent rules are not counted.
+    ///
+    /// Exceeding the limit aborts parsing with an [`ErrorVariant::LimitError`].
+    ///
+    /// [`ErrorVariant::LimitError`]: error/enum.ErrorVariant.html#variant.LimitError
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::ParserConfig;
+    /// let config: ParserConfig<()> = ParserConfig::new().max_calls(1_000_000);
+    /// assert_eq!(config.call_limit(), Some(1_000_000));
+    /// ```
+    pub fn max_calls(mut self, max: usize) -> ParserConfig<R> {
+        self.max_calls = Some(max);
+        self
+    }
+
+    /// Limits the total number of parsing steps to `max`, a step being either a rule call or an
+    /// iteration of a repetition. Unlike [`max_calls`], this also bounds the time spent in
+    /// repetitions that call no rule, like `(!"\n" ~ ANY)*`.
+    ///
+    /// Exceeding the limit aborts parsing with an [`ErrorVariant::LimitError`].
+    ///
+    /// [`max_calls`]: #method.max_calls
+    /// [`ErrorVariant::LimitError`]: error/enum.ErrorVariant.html#variant.LimitError
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::ParserConfig;
+    /// let config: ParserConfig<()> = ParserConfig::new().max_steps(10_000_000);
+    /// assert_eq!(config.step_limit(), Some(10_000_000));
+    /// ```
+    pub fn max_steps(mut self, max: usize) -> ParserConfig<R> {
+        self.max_steps = Some(max);
+        self
+    }
+
+    /// Cancels parsing once `flag` is set, which can be done from another thread. The flag is
+    /// polled every [`CANCEL_INTERVAL`] steps, as counted by [`max_steps`], and on the first one.
+    ///
+    /// Cancellation aborts parsing with an [`ErrorVariant::Cancelled`].
+    ///
+    /// [`CANCEL_INTERVAL`]: constant.CANCEL_INTERVAL.html
+    /// [`max_steps`]: #method.max_steps
+    /// [`ErrorVariant::Cancelled`]: error/enum.ErrorVariant.html#variant.Cancelled
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::sync::atomic::AtomicBool;
+    /// # use std::sync::Arc;
+    /// # use pest::ParserConfig;
+    /// let flag = Arc::new(AtomicBool::new(false));
+    /// let config: ParserConfig<()> = ParserConfig::new().cancel_on(flag.clone());
+    /// ```
+    pub fn cancel_on(self, flag: Arc<AtomicBool>) -> ParserConfig<R> {
+        self.cancel_if(move || flag.load(Ordering::Relaxed))
+    }
+
+    /// Cancels parsing once `cancelled` returns `true`, which allows for deadlines. It is called
+    /// every [`CANCEL_INTERVAL`] steps, as counted by [`max_steps`], and on the first one.
+    ///
+    /// Cancellation aborts parsing with an [`ErrorVariant::Cancelled`].
+    ///
+    /// [`CANCEL_INTERVAL`]: constant.CANCEL_INTERVAL.html
+    /// [`max_steps`]: #method.max_steps
+    /// [`ErrorVariant::Cancelled`]: error/enum.ErrorVariant.html#variant.Cancelled
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::{Duration, Instant};
+    /// # use pest::ParserConfig;
+    /// let deadline = Instant::now() + Duration::from_millis(100);
+    /// let config: ParserConfig<()> =
+    ///     ParserConfig::new().cancel_if(move || Instant::now() >= deadline);
+    /// ```
+    pub fn cancel_if<F>(mut self, cancelled: F) -> ParserConfig<R>
+    where
+        F: Fn() -> bool + Send + Sync + 'static,
+    {
+        self.cancel = Some(Cancel(Arc::new(cancelled)));
+        self
+    }
+
+    /// Records the literals, insensitive literals, character ranges and character predicates that
+    /// fail to match at the furthest position reached. Parsing errors are then reported as an
+    /// [`ErrorVariant::DetailedParsingError`] listing them, which also places them at that
+    /// position even when no rule was attempted there.
+    ///
+    /// Terminals are only recorded outside of negative lookaheads.
+    ///
+    /// [`ErrorVariant::DetailedParsingError`]: error/enum.ErrorVariant.html#variant.DetailedParsingError
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::ParserConfig;
+    /// let config: ParserConfig<()> = ParserConfig::new().track_terminals();
+    /// assert!(config.tracks_terminals());
+    /// ```
+    pub fn track_terminals(mut self) -> ParserConfig<R> {
+        self.track_terminals = true;
+        self
+    }
+
+    /// Records the stack of rules being parsed where the first failure at the furthest position
+    /// reached happens. Parsing errors then carry it as their [`Error::rule_stack`], from the
+    /// outermost rule to the one that failed, and render it as a "while parsing" note.
+    ///
+    /// [`Error::rule_stack`]: error/struct.Error.html#method.rule_stack
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::ParserConfig;
+    /// let config: ParserConfig<()> = ParserConfig::new().track_rule_stack();
+    /// assert!(config.tracks_rule_stack());
+    /// ```
+    pub fn track_rule_stack(mut self) -> ParserConfig<R> {
+        self.track_rule_stack = true;
+        self
+    }
+
+    /// Records the implicit whitespace and comments skipped between the elements of non-atomic
+    /// rules as trivia pairs of the `WHITESPACE` and `COMMENT` rules. They are not yielded by the
+    /// iterators over pairs, but are attached to the neighboring pairs, so that the input can be
+    /// rebuilt byte for byte from [`Pair::leaves`]. Pairs matched inside of trivia, like those of
+    /// a non-silent `COMMENT`, are folded into them.
+    ///
+    /// [`Pair::leaves`]: iterators/struct.Pair.html#method.leaves
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::ParserConfig;
+    /// let config: ParserConfig<()> = ParserConfig::new().track_trivia();
+    /// assert!(config.tracks_trivia());
+    /// ```
+    pub fn track_trivia(mut self) -> ParserConfig<R> {
+        self.track_trivia = true;
+        self
+    }
+
+    /// Matches case-insensitive literals, like `^"straße"`, under full Unicode case folding
+    /// instead of ASCII case-insensitively. A literal then matches any input folding to the same
+    /// characters, even of a different length, so that `^"STRASSE"` matches `straße` and `^"Σ"`
+    /// matches the final `ς`. The Turkic mappings of dotted and dotless `i` are not applied.
+    ///
+    /// This only affects text input, byte inputs being always matched ASCII case-insensitively.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::ParserConfig;
+    /// let config: ParserConfig<()> = ParserConfig::new().fold_unicode_case();
+    /// assert!(config.folds_unicode_case());
+    /// ```
+    pub fn fold_unicode_case(mut self) -> ParserConfig<R> {
+        self.fold_unicode_case = true;
+        self
+    }
+
+    /// Installs `tracer`, which is then called on every rule call. The `Arc` can be kept to
+    /// inspect the tracer once parsing is done. Requires the `trace` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::sync::{Arc, Mutex};
+    /// # use pest::ParserConfig;
+    /// # use pest::trace::EventLog;
+    /// let log = Arc::new(Mutex::new(EventLog::new()));
+    /// let config: ParserConfig<()> = ParserConfig::new().tracer(log.clone());
+    /// ```
+    #[cfg(feature = "trace")]
+    pub fn tracer<T>(mut self, tracer: Arc<Mutex<T>>) -> ParserConfig<R>
+    where
+        T: Tracer<R> + Send + 'static,
+    {
+        self.tracer = Some(SharedTracer(tracer));
+        self
+    }
+
+    /// Returns the rules memoized by this `ParserConfig`.
+    pub fn memoization(&self) -> &Memoization<R> {
+        &self.memoization
+    }
+
+    /// Returns the maximum number of nested rule calls, if limited.
+    pub fn depth_limit(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    /// Returns the maximum total number of rule calls, if limited.
+    pub fn call_limit(&self) -> Option<usize> {
+        self.max_calls
+    }
+
+    /// Returns the maximum total number of parsing steps, if limited.
+    pub fn step_limit(&self) -> Option<usize> {
+        self.max_steps
+    }
+
+    /// Returns whether terminals that fail to match are recorded.
+    pub fn tracks_terminals(&self) -> bool {
+        self.track_terminals
+    }
+
+    /// Returns whether the stack of rules is recorded for parsing errors.
+    pub fn tracks_rule_stack(&self) -> bool {
+        self.track_rule_stack
+    }
+
+    /// Returns whether implicit whitespace and comments are recorded as trivia.
+    pub fn tracks_trivia(&self) -> bool {
+        self.track_trivia
+    }
+
+    /// Returns whether case-insensitive literals are matched under Unicode case folding.
+    pub fn folds_unicode_case(&self) -> bool {
+        self.fold_unicode_case
+    }
+
+    pub(crate) fn with_byte_input(mut self) -> ParserConfig<R> {
+        self.byte_input = true;
+        self
+    }
+
+    #[inline]
+    pub(crate) fn byte_input(&self) -> bool {
+        self.byte_input
+    }
+
+    pub(crate) fn with_memo(mut self, memo: Arc<Mutex<Memo<R>>>) -> ParserConfig<R> {
+        self.memo = Some(memo);
+        self
+    }
+
+    pub(crate) fn memo(&self) -> Option<&Arc<Mutex<Memo<R>>>> {
+        self.memo.as_ref()
+    }
+
+    #[cfg(feature = "trace")]
+    #[inline]
+    pub(crate) fn traced<F>(&self, f: F)
+    where
+        F: FnOnce(&mut dyn Tracer<R>),
+    {
+        if let Some(ref tracer) = self.tracer {
+            f(&mut *tracer.0.lock().unwrap_or_else(PoisonError::into_inner));
+        }
+    }
+
+    #[inline]
+    pub(crate) fn cancelled(&self) -> bool {
+        match self.cancel {
+            Some(ref cancel) => (cancel.0)(),
+            None => false,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn memoizes(&self, rule: R) -> bool {
+        match self.memoization {
+            Memoization::None => false,
+            Memoization::All => true,
+            Memoization::Rules(_) => self.memoized.contains(&rule),
+        }
+    }
+}
+
+/// Number of parsing steps between two checks for cancellation.
+pub const CANCEL_INTERVAL: usize = 1024;
+
+/// Cancellation callback of a `ParserConfig`.
+#[derive(Clone)]
+struct Cancel(Arc<dyn Fn() -> bool + Send + Sync>);
+
+impl fmt::Debug for Cancel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Cancel")
+    }
+}
+
+impl<R: RuleType> Default for ParserConfig<R> {
+    fn default() -> Self {
+        ParserConfig::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn config_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<ParserConfig<()>>();
+    }
+
+    #[test]
+    fn memoizes_listed_rules() {
+        let config = ParserConfig::new().memoize(1).memoize(2).memoize(1);
+
+        assert_eq!(config.memoization(), &Memoization::Rules(vec![1, 2]));
+        assert!(config.memoizes(1));
+        assert!(config.memoizes(2));
+        assert!(!config.memoizes(3));
+        assert!(config.memoize_all().memoizes(3));
+    }
+
+    #[test]
+    fn config_across_threads() {
+        let deadline = Instant::now() + Duration::from_secs(60);
+        let config = ParserConfig::new()
+            .memoize_all()
+            .cancel_if(move || Instant::now() >= deadline);
+        let input: Arc<str> = Arc::from("a");
+
+        let matched = thread::spawn(move || {
+            ::state_with_config::<(), _>(input, config, |state| state.match_string("a")).is_ok()
+        })
+        .join()
+        .unwrap();
+
+        assert!(matched);
+    }
+}
@@ -8,15 +8,19 @@
 // modified, or distributed except according to those terms.
 
 use alloc::boxed::Box;
-use alloc::rc::Rc;
 use alloc::vec;
 use alloc::vec::Vec;
+use std::collections::{HashMap, HashSet};
+use std::mem;
 use std::ops::Range;
-use std::sync::Arc;
+use std::sync::{Arc, PoisonError};
 
-use error::{Error, ErrorVariant};
+use error::{DetailedAttempts, Error, ErrorVariant, Limit, Terminal};
 use iterators::{pairs, QueueableToken};
-use position::{self, Position};
+use memo::{self, Memo, MemoEntry, MemoKey, Seed};
+use parser_config::{ParserConfig, CANCEL_INTERVAL};
+use position::Position;
+use recovery::{Recovered, RecoveredError};
 use span::Span;
 use stack::Stack;
 use RuleType;
@@ -24,7 +28,7 @@ use RuleType;
 /// The current lookahead status of a [`ParserState`].
 ///
 /// [`ParserState`]: struct.ParserState.html
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum Lookahead {
     Positive,
     Negative,
@@ -34,7 +38,7 @@ pub enum Lookahead {
 /// The current atomicity of a [`ParserState`].
 ///
 /// [`ParserState`]: struct.ParserState.html
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum Atomicity {
     Atomic,
     CompoundAtomic,
@@ -44,6 +48,19 @@ pub enum Atomicity {
 /// Type alias to simplify specifying the return value of chained closures.
 pub type ParseResult<S> = Result<S, S>;
 
+/// Error-reporting attempts produced by an expression, as if it had been run with no prior
+/// attempts.
+#[derive(Debug)]
+pub(crate) struct Attempts<R> {
+    pub pos: usize,
+    pub positives: Vec<R>,
+    pub negatives: Vec<R>,
+    pub terminals: Vec<Terminal>,
+    /// Rules being parsed at the first attempt, if recorded, without the ones that were already
+    /// being parsed when the attempts started to be collected.
+    pub stack: Option<Vec<R>>,
+}
+
 /// Match direction for the stack. Used in `PEEK[a..b]`/`stack_match_peek_slice`.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum MatchDir {
@@ -61,9 +78,25 @@ pub struct ParserState<R: RuleType> {
     lookahead: Lookahead,
     pos_attempts: Vec<R>,
     neg_attempts: Vec<R>,
+    terminal_attempts: Vec<Terminal>,
     attempt_pos: usize,
+    attempt_stack: Option<Vec<R>>,
+    rule_stack: Vec<R>,
     atomicity: Atomicity,
     stack: Stack<Span>,
+    config: ParserConfig<R>,
+    memo: Memo<R>,
+    // Seeds of the left-recursive rules being grown by position, `None` until they first match.
+    seeds: HashMap<(R, usize), Option<Seed<R>>>,
+    depth: usize,
+    // Nesting of silent rule calls, which counts towards the depth limit but is not traced.
+    silent_depth: usize,
+    calls: usize,
+    steps: usize,
+    aborted: Option<(ErrorVariant<R>, usize)>,
+    recovered: Vec<RecoveredError<R>>,
+    reach: usize,
+    anchored: bool,
 }
 
 /// Creates a `ParserState` from a `&str`, supplying it to a closure `f`.
@@ -80,31 +113,194 @@ pub fn state<'i, R: RuleType, F>(input: Arc<str>, f: F) -> Result<pairs::Pairs<R
 where
     F: FnOnce(Box<ParserState<R>>) -> ParseResult<Box<ParserState<R>>>,
 {
-    let state = ParserState::new(input.clone());
+    state_with_config(input, ParserConfig::default(), f)
+}
+
+/// Creates a `ParserState` from a `&str` configured with `config`, supplying it to a closure `f`.
+///
+/// # Examples
+///
+/// ```
+/// # use pest;
+/// # use pest::ParserConfig;
+/// # use std::sync::Arc;
+/// let input: Arc<str> = Arc::from("");
+/// pest::state_with_config::<(), _>(input, ParserConfig::new().memoize_all(), |s| Ok(s)).unwrap();
+/// ```
+pub fn state_with_config<R: RuleType, F>(
+    input: Arc<str>,
+    config: ParserConfig<R>,
+    f: F,
+) -> Result<pairs::Pairs<R>, Error<R>>
+where
+    F: FnOnce(Box<ParserState<R>>) -> ParseResult<Box<ParserState<R>>>,
+{
+    state_with_recovery(input, config, f).into_result()
+}
 
-    match f(state) {
+/// Creates a `ParserState` from a `&str` configured with `config`, supplying it to a closure `f`,
+/// and collects every error recovered from with [`ParserState::recover`].
+///
+/// [`ParserState::recover`]: struct.ParserState.html#method.recover
+///
+/// # Examples
+///
+/// ```
+/// # use pest;
+/// # use pest::ParserConfig;
+/// # use std::sync::Arc;
+/// # #[allow(non_camel_case_types)]
+/// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+/// enum Rule {
+///     error
+/// }
+///
+/// let input: Arc<str> = Arc::from("a?b");
+/// let recovered = pest::state_with_recovery(input, ParserConfig::new(), |state| {
+///     state.repeat(|s| {
+///         s.recover(Rule::error, |s| s.match_range('a'..'z'), |s| s.match_range('a'..'z'))
+///     })
+/// });
+///
+/// assert_eq!(recovered.errors().len(), 1);
+/// assert_eq!(recovered.pairs().unwrap().as_str(), "?");
+/// ```
+pub fn state_with_recovery<R: RuleType, F>(
+    input: Arc<str>,
+    config: ParserConfig<R>,
+    f: F,
+) -> Recovered<R>
+where
+    F: FnOnce(Box<ParserState<R>>) -> ParseResult<Box<ParserState<R>>>,
+{
+    let state = ParserState::with_config(input.clone(), config);
+    let result = f(state)
+        .map(ParserState::release_memo)
+        .map_err(ParserState::release_memo);
+
+    match result {
+        Ok(state) | Err(state) if state.aborted.is_some() => {
+            let (variant, pos) = state.aborted.unwrap();
+            let error = Error::new_from_pos(variant, state.position.at(pos).unwrap());
+
+            Recovered::new(None, vec![error])
+        }
         Ok(state) => {
+            // Only the errors whose node made it into the final queue were not backtracked over.
+            // Errors without a node, recovered from in atomic rules, were forgotten when they were.
+            let errors = if state.recovered.is_empty() {
+                vec![]
+            } else {
+                let nodes: HashSet<_> = state
+                    .queue
+                    .iter()
+                    .filter_map(|token| match *token {
+                        QueueableToken::End {
+                            start_token_index,
+                            rule,
+                            input_pos,
+                            ..
+                        } => match state.queue[start_token_index] {
+                            QueueableToken::Start {
+                                input_pos: start_pos,
+                                ..
+                            } => Some((rule, start_pos, input_pos)),
+                            _ => unreachable!(),
+                        },
+                        _ => None,
+                    })
+                    .collect();
+
+                recovered_errors(state.recovered, |error| {
+                    error.atomic || nodes.contains(&(error.rule, error.start, error.end))
+                })
+            };
             let len = state.queue.len();
-            Ok(pairs::new(Rc::new(state.queue), input, 0, len))
+            let lines = state.position.lines().clone();
+
+            Recovered::new(
+                Some(pairs::new(Arc::new(state.queue), input, lines, 0, len)),
+                errors,
+            )
         }
-        Err(mut state) => {
-            state.pos_attempts.sort();
-            state.pos_attempts.dedup();
-            state.neg_attempts.sort();
-            state.neg_attempts.dedup();
+        Err(state) => {
+            let attempts = Attempts {
+                pos: state.attempt_pos,
+                positives: state.pos_attempts,
+                negatives: state.neg_attempts,
+                terminals: state.terminal_attempts,
+                stack: state.attempt_stack,
+            };
+            let error = parsing_error(&state.position, attempts, state.config.tracks_terminals());
+            let mut errors = recovered_errors(state.recovered, |_| true);
+            errors.push(error);
 
-            Err(Error::new_from_pos(
-                ErrorVariant::ParsingError {
-                    positives: state.pos_attempts.clone(),
-                    negatives: state.neg_attempts.clone(),
-                },
-                // TODO(performance): Guarantee state.attempt_pos is a valid position
-                position::Position::new(input, state.attempt_pos).unwrap(),
-            ))
+            Recovered::new(None, errors)
         }
     }
 }
 
+// Keeps the last error recovered from for every error node accepted by `f`, ordered by position.
+fn recovered_errors<R: RuleType, F>(recovered: Vec<RecoveredError<R>>, mut f: F) -> Vec<Error<R>>
+where
+    F: FnMut(&RecoveredError<R>) -> bool,
+{
+    let mut nodes = HashSet::new();
+    let mut errors: Vec<_> = recovered
+        .into_iter()
+        .rev()
+        .filter(|error| f(error) && nodes.insert((error.rule, error.start, error.end)))
+        .collect();
+    errors.reverse();
+    errors.sort_by_key(|error| error.start);
+
+    errors.into_iter().map(|error| error.error).collect()
+}
+
+// Builds the error reported for `attempts`, listing the terminals attempted if `detailed`. The
+// stack of `attempts` must be complete.
+fn parsing_error<R: RuleType>(
+    position: &Position,
+    attempts: Attempts<R>,
+    detailed: bool,
+) -> Error<R> {
+    let Attempts {
+        pos,
+        mut positives,
+        mut negatives,
+        mut terminals,
+        stack,
+    } = attempts;
+
+    positives.sort();
+    positives.dedup();
+    negatives.sort();
+    negatives.dedup();
+
+    let variant = if detailed {
+        terminals.sort();
+        terminals.dedup();
+
+        ErrorVariant::DetailedParsingError(Box::new(DetailedAttempts {
+            positives,
+            negatives,
+            terminals,
+        }))
+    } else {
+        ErrorVariant::ParsingError {
+            positives,
+            negatives,
+        }
+    };
+
+    Error::new_from_pos(
+        variant,
+        // TODO(performance): Guarantee state.attempt_pos is a valid position
+        position.at(pos).unwrap(),
+    )
+    .with_rule_stack(stack.unwrap_or_default())
+}
+
 impl<R: RuleType> ParserState<R> {
     /// Allocates a fresh `ParserState` object to the heap and returns the owned `Box`. This `Box`
     /// will be passed from closure to closure based on the needs of the specified `Parser`.
@@ -119,15 +315,51 @@ impl<R: RuleType> ParserState<R> {
     /// ```
     #[allow(clippy::new_ret_no_self)]
     pub fn new(input: Arc<str>) -> Box<Self> {
+        ParserState::with_config(input, ParserConfig::default())
+    }
+
+    /// Allocates a fresh `ParserState` configured with `config`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest;
+    /// # use pest::ParserConfig;
+    /// # use std::sync::Arc;
+    /// let input: Arc<str> = Arc::from("");
+    /// let config = ParserConfig::new().memoize_all();
+    /// let state: Box<pest::ParserState<&str>> = pest::ParserState::with_config(input, config);
+    /// ```
+    pub fn with_config(input: Arc<str>, config: ParserConfig<R>) -> Box<Self> {
         Box::new(ParserState {
             position: Position::from_start(input),
             queue: vec![],
             lookahead: Lookahead::None,
             pos_attempts: vec![],
             neg_attempts: vec![],
+            terminal_attempts: vec![],
             attempt_pos: 0,
+            attempt_stack: None,
+            rule_stack: vec![],
             atomicity: Atomicity::NonAtomic,
             stack: Stack::new(),
+            memo: match config.memo() {
+                Some(memo) => mem::replace(
+                    &mut *memo.lock().unwrap_or_else(PoisonError::into_inner),
+                    Memo::new(),
+                ),
+                None => Memo::new(),
+            },
+            seeds: HashMap::new(),
+            config,
+            depth: 0,
+            silent_depth: 0,
+            calls: 0,
+            steps: 0,
+            aborted: None,
+            recovered: vec![],
+            reach: 0,
+            anchored: false,
         })
     }
 
@@ -199,11 +431,485 @@ impl<R: RuleType> ParserState<R> {
     /// ```
     #[inline]
     pub fn rule<F>(mut self: Box<Self>, rule: R, f: F) -> ParseResult<Box<Self>>
+    where
+        F: FnOnce(Box<Self>) -> ParseResult<Box<Self>>,
+    {
+        if !self.enter() {
+            return Err(self);
+        }
+
+        #[cfg(feature = "trace")]
+        let pos = self.position.pos();
+        #[cfg(feature = "trace")]
+        {
+            let depth = self.depth - 1;
+            self.config.traced(|tracer| tracer.enter(rule, pos, depth));
+        }
+
+        let tracks_rule_stack = self.config.tracks_rule_stack();
+        if tracks_rule_stack {
+            self.rule_stack.push(rule);
+        }
+
+        let result = if self.config.memoizes(rule) {
+            self.memoized_rule(rule, f)
+        } else {
+            self.tracked_rule(rule, f)
+        };
+
+        match result {
+            Ok(mut new_state) => {
+                new_state.depth -= 1;
+                if tracks_rule_stack {
+                    new_state.rule_stack.pop();
+                }
+                #[cfg(feature = "trace")]
+                {
+                    let (end, depth) = (new_state.position.pos(), new_state.depth);
+                    new_state
+                        .config
+                        .traced(|tracer| tracer.success(rule, pos, end, depth));
+                }
+                Ok(new_state)
+            }
+            Err(mut new_state) => {
+                new_state.depth -= 1;
+                if tracks_rule_stack {
+                    new_state.rule_stack.pop();
+                }
+                #[cfg(feature = "trace")]
+                {
+                    let depth = new_state.depth;
+                    new_state
+                        .config
+                        .traced(|tracer| tracer.failure(rule, pos, depth));
+                }
+                Err(new_state)
+            }
+        }
+    }
+
+    /// Wrapper around the body of a silent rule, which does not produce any token. It only
+    /// accounts for the call against [`ParserConfig::max_depth`], so that recursion through silent
+    /// rules is bounded like recursion through any other rule.
+    ///
+    /// [`ParserConfig::max_depth`]: struct.ParserConfig.html#method.max_depth
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest;
+    /// # use std::sync::Arc;
+    /// # #[allow(non_camel_case_types)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// enum Rule {
+    ///     a
+    /// }
+    ///
+    /// let input: Arc<str> = Arc::from("aa");
+    /// let pairs: Vec<_> = pest::state(input, |state| {
+    ///     state.silent(|s| s.match_string("a").and_then(|s| s.rule(Rule::a, |s| s.match_string("a"))))
+    /// }).unwrap().collect();
+    ///
+    /// assert_eq!(pairs.len(), 1);
+    /// ```
+    #[inline]
+    pub fn silent<F>(mut self: Box<Self>, f: F) -> ParseResult<Box<Self>>
+    where
+        F: FnOnce(Box<Self>) -> ParseResult<Box<Self>>,
+    {
+        if !self.enter_silent() {
+            return Err(self);
+        }
+
+        match f(self) {
+            Ok(mut new_state) => {
+                new_state.silent_depth -= 1;
+                Ok(new_state)
+            }
+            Err(mut new_state) => {
+                new_state.silent_depth -= 1;
+                Err(new_state)
+            }
+        }
+    }
+
+    /// Parses the left-recursive `rule` by growing a seed. `f`, which parses the rule, is first
+    /// applied with its recursive calls at the same position failing, and then over and over with
+    /// them matching what the previous application did, for as long as it matches more of the
+    /// input. Generated parsers wrap left-recursive rules with it, so that
+    /// `expr = { expr ~ "-" ~ num | num }` parses `1-2-3` as `(1-2)-3`.
+    ///
+    /// Rules parsed while a seed grows are not memoized, and values pushed on the stack by `f` are
+    /// dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest;
+    /// # use std::sync::Arc;
+    /// # #[allow(non_camel_case_types)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// enum Rule {
+    ///     expr
+    /// }
+    ///
+    /// fn expr(state: Box<pest::ParserState<Rule>>) -> pest::ParseResult<Box<pest::ParserState<Rule>>> {
+    ///     state.left_recursive(Rule::expr, |state| {
+    ///         state.rule(Rule::expr, |s| {
+    ///             s.sequence(|s| expr(s).and_then(|s| s.match_string("-1")))
+    ///                 .or_else(|s| s.match_string("1"))
+    ///         })
+    ///     })
+    /// }
+    ///
+    /// let input: Arc<str> = Arc::from("1-1-1");
+    /// let pair = pest::state(input, expr).unwrap().next().unwrap();
+    ///
+    /// assert_eq!(pair.as_str(), "1-1-1");
+    /// assert_eq!(pair.into_inner().next().unwrap().as_str(), "1-1");
+    /// ```
+    pub fn left_recursive<F>(mut self: Box<Self>, rule: R, f: F) -> ParseResult<Box<Self>>
+    where
+        F: Fn(Box<Self>) -> ParseResult<Box<Self>>,
+    {
+        // Recursive calls happen inside of `f`, which may change the atomicity.
+        let key = (rule, self.position.pos());
+
+        if self.seeds.contains_key(&key) {
+            return if self.replay_seed(&key) {
+                Ok(self)
+            } else {
+                Err(self)
+            };
+        }
+
+        self.seeds.insert(key, None);
+
+        let initial_pos = self.position.clone();
+        let index = self.queue.len();
+        let mut state = self;
+
+        loop {
+            state.stack.snapshot();
+
+            let (len, mut new_state) = match f(state) {
+                Ok(new_state) => (Some(new_state.position.pos() - key.1), new_state),
+                Err(new_state) => (None, new_state),
+            };
+
+            new_state.stack.restore();
+            new_state.position = initial_pos.clone();
+
+            let grown = match (len, new_state.seeds[&key].as_ref()) {
+                (Some(len), Some(seed)) => len > seed.len,
+                (Some(_), None) => true,
+                (None, _) => false,
+            };
+
+            if !grown || new_state.aborted.is_some() {
+                new_state.queue.truncate(index);
+                state = new_state;
+                break;
+            }
+
+            let tokens = new_state
+                .queue
+                .drain(index..)
+                .map(|token| memo::relocate(&token, (index, 0), (key.1, 0)))
+                .collect();
+            new_state.seeds.insert(
+                key,
+                Some(Seed {
+                    len: len.unwrap(),
+                    tokens,
+                }),
+            );
+            state = new_state;
+        }
+
+        let success = state.aborted.is_none() && state.replay_seed(&key);
+        state.seeds.remove(&key);
+
+        if success {
+            Ok(state)
+        } else {
+            Err(state)
+        }
+    }
+
+    // Applies the seed grown for `key` so far, returning whether it matched.
+    fn replay_seed(&mut self, key: &(R, usize)) -> bool {
+        let tokens = self.lookahead == Lookahead::None && self.atomicity != Atomicity::Atomic;
+        let ParserState {
+            ref mut position,
+            ref mut queue,
+            ref seeds,
+            ..
+        } = *self;
+
+        match seeds[key] {
+            Some(ref seed) => {
+                if tokens {
+                    let index = queue.len();
+                    queue.extend(
+                        seed.tokens
+                            .iter()
+                            .map(|token| memo::relocate(token, (0, index), (0, key.1))),
+                    );
+                }
+                position.advance(seed.len);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Accounts for a rule call, returning `false` if it exceeds one of the configured limits.
+    // Once a limit has been exceeded, every following rule call fails so that parsing unwinds.
+    #[inline]
+    fn enter(&mut self) -> bool {
+        if !self.step() {
+            return false;
+        }
+
+        self.calls += 1;
+
+        let limit = match (self.config.depth_limit(), self.config.call_limit()) {
+            (Some(max), _) if self.depth + self.silent_depth >= max => Some(Limit::Depth(max)),
+            (_, Some(max)) if self.calls > max => Some(Limit::Calls(max)),
+            _ => None,
+        };
+
+        if let Some(limit) = limit {
+            self.abort(ErrorVariant::LimitError { limit });
+            return false;
+        }
+
+        self.depth += 1;
+        true
+    }
+
+    // Accounts for a silent rule call, returning `false` if it exceeds the depth limit. Unlike
+    // `enter`, it neither counts as a call nor as a step.
+    #[inline]
+    fn enter_silent(&mut self) -> bool {
+        if self.aborted.is_some() {
+            return false;
+        }
+
+        match self.config.depth_limit() {
+            Some(max) if self.depth + self.silent_depth >= max => {
+                self.abort(ErrorVariant::LimitError {
+                    limit: Limit::Depth(max),
+                });
+                false
+            }
+            _ => {
+                self.silent_depth += 1;
+                true
+            }
+        }
+    }
+
+    // Accounts for a step, a rule call, an iteration of `repeat` or of a recovery scan, returning `false` if
+    // parsing exceeds the step budget or is cancelled, or already has been aborted.
+    #[inline]
+    fn step(&mut self) -> bool {
+        if self.aborted.is_some() {
+            return false;
+        }
+
+        let variant = match self.config.step_limit() {
+            Some(max) if self.steps >= max => Some(ErrorVariant::LimitError {
+                limit: Limit::Steps(max),
+            }),
+            // `CANCEL_INTERVAL` is a power of two.
+            _ if self.steps & (CANCEL_INTERVAL - 1) == 0 && self.config.cancelled() => {
+                Some(ErrorVariant::Cancelled)
+            }
+            _ => None,
+        };
+
+        if let Some(variant) = variant {
+            self.abort(variant);
+            return false;
+        }
+
+        self.steps += 1;
+        true
+    }
+
+    // Aborts parsing, after which every rule call and repetition fails so that parsing unwinds.
+    fn abort(&mut self, variant: ErrorVariant<R>) {
+        self.aborted = Some((variant, self.position.pos()));
+    }
+
+    // Runs `rule` through the memoization table: the outcome of the first evaluation at a given
+    // position is cached along with the tokens and the attempts it produced, and later evaluations
+    // replay them instead of running `f` again.
+    fn memoized_rule<F>(mut self: Box<Self>, rule: R, f: F) -> ParseResult<Box<Self>>
+    where
+        F: FnOnce(Box<Self>) -> ParseResult<Box<Self>>,
+    {
+        let key = MemoKey {
+            rule,
+            pos: self.position.pos(),
+            atomicity: self.atomicity,
+            lookahead: self.lookahead,
+        };
+
+        // The outcome of a rule depends on the stack as soon as it isn't empty.
+        if !self.stack.is_empty() {
+            return self.tracked_rule(rule, f);
+        }
+
+        if let Some(success) = self.replay(&key) {
+            return if success { Ok(self) } else { Err(self) };
+        }
+
+        // Run the rule in isolation, so that the attempts it produces can be replayed on top of
+        // any other ones, and so that how far it looks at the input is known.
+        let index = self.queue.len();
+        let recovered = self.recovered.len();
+        let reach = mem::replace(&mut self.reach, key.pos);
+        let anchored = mem::replace(&mut self.anchored, false);
+        let (result, attempts) = self.isolate_attempts(|state| state.tracked_rule(rule, f));
+        let (success, mut state) = match result {
+            Ok(state) => (true, state),
+            Err(state) => (false, state),
+        };
+        state.merge_attempts(&attempts);
+
+        let entry_reach = state.reach - key.pos;
+        let entry_anchored = state.anchored;
+        state.examine(reach);
+        state.anchored |= anchored;
+
+        // Errors recovered from are not replayed, aborted rules are incomplete, and outcomes may
+        // depend on the seeds of left-recursive rules being grown.
+        if state.stack.is_empty()
+            && state.recovered.len() == recovered
+            && state.aborted.is_none()
+            && state.seeds.is_empty()
+        {
+            let tokens = state.queue[index..]
+                .iter()
+                .map(|token| memo::relocate(token, (index, 0), (key.pos, 0)))
+                .collect();
+            let entry = MemoEntry {
+                success,
+                len: state.position.pos() - key.pos,
+                tokens,
+                attempts,
+                reach: entry_reach,
+                anchored: entry_anchored,
+            };
+            state.memo.insert(key, entry);
+        }
+
+        if success {
+            Ok(state)
+        } else {
+            Err(state)
+        }
+    }
+
+    // Applies the cached outcome for `key`, returning whether the rule succeeded, or `None` if
+    // there is nothing cached.
+    fn replay(&mut self, key: &MemoKey<R>) -> Option<bool> {
+        let ParserState {
+            ref mut position,
+            ref mut queue,
+            ref mut pos_attempts,
+            ref mut neg_attempts,
+            ref mut terminal_attempts,
+            ref mut attempt_pos,
+            ref mut attempt_stack,
+            ref rule_stack,
+            ref mut reach,
+            ref mut anchored,
+            ref memo,
+            ..
+        } = *self;
+        let entry = memo.get(key)?;
+
+        let index = queue.len();
+        queue.extend(
+            entry
+                .tokens
+                .iter()
+                .map(|token| memo::relocate(token, (0, index), (0, key.pos))),
+        );
+        position.advance(entry.len);
+        merge_attempts(
+            (attempt_pos, attempt_stack),
+            pos_attempts,
+            neg_attempts,
+            terminal_attempts,
+            rule_stack,
+            &entry.attempts,
+        );
+        *reach = (*reach).max(key.pos + entry.reach);
+        *anchored |= entry.anchored;
+
+        Some(entry.success)
+    }
+
+    // Runs `f` as if no attempts had been made yet, returning its result along with the attempts
+    // it made. The attempts made before are restored afterwards.
+    fn isolate_attempts<F>(mut self: Box<Self>, f: F) -> (ParseResult<Box<Self>>, Attempts<R>)
+    where
+        F: FnOnce(Box<Self>) -> ParseResult<Box<Self>>,
+    {
+        let pos = self.position.pos();
+        let attempt_pos = mem::replace(&mut self.attempt_pos, pos);
+        let pos_attempts = mem::take(&mut self.pos_attempts);
+        let neg_attempts = mem::take(&mut self.neg_attempts);
+        let terminal_attempts = mem::take(&mut self.terminal_attempts);
+        let attempt_stack = self.attempt_stack.take();
+        let depth = self.rule_stack.len();
+
+        let restore = |state: &mut Box<Self>| Attempts {
+            pos: mem::replace(&mut state.attempt_pos, attempt_pos),
+            positives: mem::replace(&mut state.pos_attempts, pos_attempts),
+            negatives: mem::replace(&mut state.neg_attempts, neg_attempts),
+            terminals: mem::replace(&mut state.terminal_attempts, terminal_attempts),
+            stack: mem::replace(&mut state.attempt_stack, attempt_stack)
+                .map(|stack| stack[depth..].to_vec()),
+        };
+
+        match f(self) {
+            Ok(mut state) => {
+                let attempts = restore(&mut state);
+                (Ok(state), attempts)
+            }
+            Err(mut state) => {
+                let attempts = restore(&mut state);
+                (Err(state), attempts)
+            }
+        }
+    }
+
+    fn merge_attempts(&mut self, attempts: &Attempts<R>) {
+        merge_attempts(
+            (&mut self.attempt_pos, &mut self.attempt_stack),
+            &mut self.pos_attempts,
+            &mut self.neg_attempts,
+            &mut self.terminal_attempts,
+            &self.rule_stack,
+            attempts,
+        );
+    }
+
+    #[inline]
+    fn tracked_rule<F>(mut self: Box<Self>, rule: R, f: F) -> ParseResult<Box<Self>>
     where
         F: FnOnce(Box<Self>) -> ParseResult<Box<Self>>,
     {
         let actual_pos = self.position.pos();
         let index = self.queue.len();
+        let recovered = self.recovered.len();
 
         let (pos_attempts_index, neg_attempts_index) = if actual_pos == self.attempt_pos {
             (self.pos_attempts.len(), self.neg_attempts.len())
@@ -256,6 +962,7 @@ impl<R: RuleType> ParserState<R> {
                         start_token_index: index,
                         rule,
                         input_pos: new_pos,
+                        trivia: false,
                     });
                 }
 
@@ -277,12 +984,46 @@ impl<R: RuleType> ParserState<R> {
                 {
                     new_state.queue.truncate(index);
                 }
+                new_state.forget_atomic_recoveries(recovered);
 
                 Err(new_state)
             }
         }
     }
 
+    // Forgets the errors recovered from inside atomic rules since the first `len` were recorded.
+    // Those have no error node, so they are not dropped along with the token queue when their rule
+    // or sequence fails.
+    #[inline]
+    fn forget_atomic_recoveries(&mut self, len: usize) {
+        if self.recovered[len..].iter().any(|error| error.atomic) {
+            let forgotten = self.recovered.split_off(len);
+            self.recovered
+                .extend(forgotten.into_iter().filter(|error| !error.atomic));
+        }
+    }
+
+    // Records that the input was looked at up to `pos`, exclusive, the end of input counting as one
+    // byte.
+    #[inline]
+    fn examine(&mut self, pos: usize) {
+        if pos > self.reach {
+            self.reach = pos;
+        }
+    }
+
+    // Hands the memoization table back to the configuration it was taken from, if any.
+    fn release_memo(mut self: Box<Self>) -> Box<Self> {
+        if let Some(memo) = self.config.memo() {
+            mem::swap(
+                &mut *memo.lock().unwrap_or_else(PoisonError::into_inner),
+                &mut self.memo,
+            );
+        }
+
+        self
+    }
+
     fn attempts_at(&self, pos: usize) -> usize {
         if self.attempt_pos == pos {
             self.pos_attempts.len() + self.neg_attempts.len()
@@ -319,17 +1060,132 @@ impl<R: RuleType> ParserState<R> {
         if pos > self.attempt_pos {
             self.pos_attempts.clear();
             self.neg_attempts.clear();
+            self.terminal_attempts.clear();
             self.attempt_pos = pos;
+            self.attempt_stack = None;
         }
 
-        let attempts = if self.lookahead != Lookahead::Negative {
-            &mut self.pos_attempts
-        } else {
-            &mut self.neg_attempts
-        };
+        if pos == self.attempt_pos {
+            if self.lookahead != Lookahead::Negative {
+                self.pos_attempts.push(rule);
+            } else {
+                self.neg_attempts.push(rule);
+            }
+
+            self.track_stack();
+        }
+    }
+
+    // Records that the terminal returned by `f` failed to match at the current position, if
+    // terminals are tracked.
+    #[inline]
+    fn track_terminal<F>(&mut self, f: F)
+    where
+        F: FnOnce() -> Terminal,
+    {
+        if !self.config.tracks_terminals() || self.lookahead == Lookahead::Negative {
+            return;
+        }
+
+        let pos = self.position.pos();
+
+        if pos > self.attempt_pos {
+            self.pos_attempts.clear();
+            self.neg_attempts.clear();
+            self.terminal_attempts.clear();
+            self.attempt_pos = pos;
+            self.attempt_stack = None;
+        }
 
         if pos == self.attempt_pos {
-            attempts.push(rule);
+            self.terminal_attempts.push(f());
+            self.track_stack();
+        }
+    }
+
+    // Records the rules being parsed if this is the first attempt at the attempt position and the
+    // stack of rules is tracked.
+    #[inline]
+    fn track_stack(&mut self) {
+        if self.config.tracks_rule_stack() && self.attempt_stack.is_none() {
+            self.attempt_stack = Some(self.rule_stack.clone());
+        }
+    }
+
+    /// Wraps the trivia matched by `f`, like implicit whitespace and comments, as a trivia pair of
+    /// `rule` when [`ParserConfig::track_trivia`] is enabled, and simply applies `f` otherwise.
+    /// The pairs generated by `f` are dropped in favor of the trivia pair.
+    ///
+    /// Trivia pairs are not yielded by the iterators, but are attached to their neighboring pairs
+    /// as returned by [`Pair::leading_trivia`] and [`Pair::trailing_trivia`], and are part of
+    /// [`Pair::leaves`].
+    ///
+    /// [`ParserConfig::track_trivia`]: struct.ParserConfig.html#method.track_trivia
+    /// [`Pair::leading_trivia`]: iterators/struct.Pair.html#method.leading_trivia
+    /// [`Pair::trailing_trivia`]: iterators/struct.Pair.html#method.trailing_trivia
+    /// [`Pair::leaves`]: iterators/struct.Pair.html#method.leaves
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest;
+    /// # use pest::ParserConfig;
+    /// # use std::sync::Arc;
+    /// # #[allow(non_camel_case_types)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// enum Rule {
+    ///     a,
+    ///     WHITESPACE
+    /// }
+    ///
+    /// let input: Arc<str> = Arc::from(" a");
+    /// let pair = pest::state_with_config(input, ParserConfig::new().track_trivia(), |state| {
+    ///     state
+    ///         .trivia(Rule::WHITESPACE, |s| s.match_string(" "))
+    ///         .and_then(|s| s.rule(Rule::a, |s| s.match_string("a")))
+    /// }).unwrap().next().unwrap();
+    ///
+    /// assert_eq!(pair.leading_trivia()[0].as_str(), " ");
+    /// ```
+    #[inline]
+    pub fn trivia<F>(self: Box<Self>, rule: R, f: F) -> ParseResult<Box<Self>>
+    where
+        F: FnOnce(Box<Self>) -> ParseResult<Box<Self>>,
+    {
+        if !self.config.tracks_trivia()
+            || self.lookahead != Lookahead::None
+            || self.atomicity == Atomicity::Atomic
+        {
+            return f(self);
+        }
+
+        let start = self.position.pos();
+        let index = self.queue.len();
+
+        match f(self) {
+            Ok(mut state) => {
+                let end = state.position.pos();
+                state.queue.truncate(index);
+
+                if end > start {
+                    state.queue.push(QueueableToken::Start {
+                        end_token_index: index + 1,
+                        input_pos: start,
+                    });
+                    state.queue.push(QueueableToken::End {
+                        start_token_index: index,
+                        rule,
+                        input_pos: end,
+                        trivia: true,
+                    });
+                }
+
+                Ok(state)
+            }
+            Err(mut state) => {
+                state.queue.truncate(index);
+                Err(state)
+            }
         }
     }
 
@@ -372,6 +1228,7 @@ impl<R: RuleType> ParserState<R> {
         F: FnOnce(Box<Self>) -> ParseResult<Box<Self>>,
     {
         let token_index = self.queue.len();
+        let recovered = self.recovered.len();
         let initial_pos = self.position.clone();
 
         let result = f(self);
@@ -382,6 +1239,7 @@ impl<R: RuleType> ParserState<R> {
                 // Restore the initial position and truncate the token queue.
                 new_state.position = initial_pos;
                 new_state.queue.truncate(token_index);
+                new_state.forget_atomic_recoveries(recovered);
                 Err(new_state)
             }
         }
@@ -417,15 +1275,17 @@ impl<R: RuleType> ParserState<R> {
     /// assert_eq!(result.unwrap().position().pos(), 0);
     /// ```
     #[inline]
-    pub fn repeat<F>(self: Box<Self>, mut f: F) -> ParseResult<Box<Self>>
+    pub fn repeat<F>(mut self: Box<Self>, mut f: F) -> ParseResult<Box<Self>>
     where
         F: FnMut(Box<Self>) -> ParseResult<Box<Self>>,
     {
-        let mut result = f(self);
-
         loop {
-            match result {
-                Ok(state) => result = f(state),
+            if !self.step() {
+                return Err(self);
+            }
+
+            self = match f(self) {
+                Ok(state) => state,
                 Err(state) => return Ok(state),
             };
         }
@@ -498,9 +1358,15 @@ impl<R: RuleType> ParserState<R> {
     where
         F: FnOnce(char) -> bool,
     {
+        let pos = self.position.pos();
+
         if self.position.match_char_by(f) {
+            let pos = self.position.pos();
+            self.examine(pos);
             Ok(self)
         } else {
+            self.examine(pos + 1);
+            self.track_terminal(|| Terminal::Predicate);
             Err(self)
         }
     }
@@ -530,9 +1396,19 @@ impl<R: RuleType> ParserState<R> {
     /// ```
     #[inline]
     pub fn match_string(mut self: Box<Self>, string: &str) -> ParseResult<Box<Self>> {
-        if self.position.match_string(string) {
+        let pos = self.position.pos();
+        let matched = if self.config.byte_input() {
+            self.examine(pos + 2 * string.len());
+            self.position.match_latin1(string.as_bytes(), false)
+        } else {
+            self.examine(pos + string.len());
+            self.position.match_string(string)
+        };
+
+        if matched {
             Ok(self)
         } else {
+            self.track_terminal(|| Terminal::Literal(string.to_owned()));
             Err(self)
         }
     }
@@ -540,6 +1416,12 @@ impl<R: RuleType> ParserState<R> {
     /// Attempts to case-insensitively match the given string. Returns `Ok` with the updated
     /// `Box<ParserState>` if successful, or `Err` with the updated `Box<ParserState>` otherwise.
     ///
+    /// Only ASCII letters are matched case-insensitively, unless the [`ParserConfig`] folds
+    /// Unicode case, in which case this is [`match_insensitive_unicode`].
+    ///
+    /// [`ParserConfig`]: struct.ParserConfig.html#method.fold_unicode_case
+    /// [`match_insensitive_unicode`]: #method.match_insensitive_unicode
+    ///
     /// # Examples
     ///
     /// ```
@@ -562,9 +1444,62 @@ impl<R: RuleType> ParserState<R> {
     /// ```
     #[inline]
     pub fn match_insensitive(mut self: Box<Self>, string: &str) -> ParseResult<Box<Self>> {
-        if self.position.match_insensitive(string) {
+        if self.config.folds_unicode_case() && !self.config.byte_input() {
+            return self.match_insensitive_unicode(string);
+        }
+
+        let pos = self.position.pos();
+        let matched = if self.config.byte_input() {
+            self.examine(pos + 2 * string.len());
+            self.position.match_latin1(string.as_bytes(), true)
+        } else {
+            self.examine(pos + string.len());
+            self.position.match_insensitive(string)
+        };
+
+        if matched {
+            Ok(self)
+        } else {
+            self.track_terminal(|| Terminal::Insensitive(string.to_owned()));
+            Err(self)
+        }
+    }
+
+    /// Attempts to match the given string under full Unicode case folding, whatever the
+    /// [`ParserConfig`], so that `"STRASSE"` matches `straße`. Returns `Ok` with the updated
+    /// `Box<ParserState>` if successful, or `Err` with the updated `Box<ParserState>` otherwise.
+    ///
+    /// [`ParserConfig`]: struct.ParserConfig.html#method.fold_unicode_case
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest;
+    /// # use std::sync::Arc;
+    /// # #[allow(non_camel_case_types)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// enum Rule {}
+    ///
+    /// let input: Arc<str> = Arc::from("straße");
+    /// let mut state: Box<pest::ParserState<Rule>> = pest::ParserState::new(input.clone());
+    /// let mut result = state.match_insensitive_unicode("STRASSE");
+    /// assert!(result.is_ok());
+    /// assert_eq!(result.unwrap().position().pos(), 7);
+    ///
+    /// state = pest::ParserState::new(input.clone());
+    /// result = state.match_insensitive_unicode("STRAS");
+    /// assert!(result.is_err());
+    /// assert_eq!(result.unwrap_err().position().pos(), 0);
+    /// ```
+    #[inline]
+    pub fn match_insensitive_unicode(mut self: Box<Self>, string: &str) -> ParseResult<Box<Self>> {
+        let (matched, examined) = self.position.match_insensitive_unicode(string);
+        self.examine(examined);
+
+        if matched {
             Ok(self)
         } else {
+            self.track_terminal(|| Terminal::Insensitive(string.to_owned()));
             Err(self)
         }
     }
@@ -572,6 +1507,11 @@ impl<R: RuleType> ParserState<R> {
     /// Attempts to match a single character from the given range. Returns `Ok` with the updated
     /// `Box<ParserState>` if successful, or `Err` with the updated `Box<ParserState>` otherwise.
     ///
+    /// When parsing bytes with [`Parser::parse_bytes`], the character is matched against the
+    /// UTF-8 it is encoded in, like literals are.
+    ///
+    /// [`Parser::parse_bytes`]: trait.Parser.html#method.parse_bytes
+    ///
     /// # Examples
     ///
     /// ```
@@ -594,9 +1534,119 @@ impl<R: RuleType> ParserState<R> {
     /// ```
     #[inline]
     pub fn match_range(mut self: Box<Self>, range: Range<char>) -> ParseResult<Box<Self>> {
-        if self.position.match_range(range) {
+        let pos = self.position.pos();
+        let (start, end) = (range.start, range.end);
+
+        let matched = if self.config.byte_input() {
+            self.position.match_latin1_range(range)
+        } else {
+            self.position.match_range(range)
+        };
+
+        if matched {
+            let pos = self.position.pos();
+            self.examine(pos);
             Ok(self)
         } else {
+            self.examine(pos + 1);
+            self.track_terminal(|| Terminal::Range(start, end));
+            Err(self)
+        }
+    }
+
+    /// Attempts to match the given bytes. Returns `Ok` with the updated `Box<ParserState>` if
+    /// successful, or `Err` with the updated `Box<ParserState>` otherwise.
+    ///
+    /// When parsing bytes with [`Parser::parse_bytes`], every byte of the input is matched. When
+    /// parsing a `&str`, the bytes are matched against its UTF-8 encoding, and only succeed if
+    /// they end on a `char` boundary.
+    ///
+    /// [`Parser::parse_bytes`]: trait.Parser.html#method.parse_bytes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest;
+    /// # use std::sync::Arc;
+    /// # #[allow(non_camel_case_types)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// enum Rule {}
+    ///
+    /// let input: Arc<str> = Arc::from("❤a");
+    /// let mut state: Box<pest::ParserState<Rule>> = pest::ParserState::new(input.clone());
+    /// let mut result = state.match_bytes(b"\xE2\x9D\xA4");
+    /// assert!(result.is_ok());
+    /// assert_eq!(result.unwrap().position().pos(), 3);
+    ///
+    /// state = pest::ParserState::new(input.clone());
+    /// result = state.match_bytes(b"\xE2");
+    /// assert!(result.is_err());
+    /// assert_eq!(result.unwrap_err().position().pos(), 0);
+    /// ```
+    #[inline]
+    pub fn match_bytes(mut self: Box<Self>, bytes: &[u8]) -> ParseResult<Box<Self>> {
+        let pos = self.position.pos();
+        let matched = if self.config.byte_input() {
+            self.examine(pos + 2 * bytes.len());
+            self.position.match_latin1(bytes, false)
+        } else {
+            self.examine(pos + bytes.len());
+            self.position.match_bytes(bytes)
+        };
+
+        if matched {
+            Ok(self)
+        } else {
+            self.track_terminal(|| Terminal::Bytes(bytes.to_vec()));
+            Err(self)
+        }
+    }
+
+    /// Attempts to match a single byte from the given range, inclusive. Returns `Ok` with the
+    /// updated `Box<ParserState>` if successful, or `Err` with the updated `Box<ParserState>`
+    /// otherwise.
+    ///
+    /// When parsing a `&str`, only bytes which are a whole `char`, i.e. ASCII, can be matched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest;
+    /// # use std::sync::Arc;
+    /// # #[allow(non_camel_case_types)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// enum Rule {}
+    ///
+    /// let input: Arc<str> = Arc::from("ab");
+    /// let mut state: Box<pest::ParserState<Rule>> = pest::ParserState::new(input.clone());
+    /// let mut result = state.match_byte_range(0x61..0x7A);
+    /// assert!(result.is_ok());
+    /// assert_eq!(result.unwrap().position().pos(), 1);
+    ///
+    /// state = pest::ParserState::new(input.clone());
+    /// result = state.match_byte_range(0x41..0x5A);
+    /// assert!(result.is_err());
+    /// assert_eq!(result.unwrap_err().position().pos(), 0);
+    /// ```
+    #[inline]
+    pub fn match_byte_range(mut self: Box<Self>, range: Range<u8>) -> ParseResult<Box<Self>> {
+        let pos = self.position.pos();
+        let (start, end) = (range.start, range.end);
+
+        let matched = if self.config.byte_input() {
+            self.position
+                .match_range(char::from(start)..char::from(end))
+        } else {
+            self.position.match_byte_range(range)
+        };
+
+        if matched {
+            let pos = self.position.pos();
+            self.examine(pos);
+            Ok(self)
+        } else {
+            self.examine(pos + 1);
+            self.track_terminal(|| Terminal::ByteRange(start, end));
             Err(self)
         }
     }
@@ -627,8 +1677,12 @@ impl<R: RuleType> ParserState<R> {
     #[inline]
     pub fn skip(mut self: Box<Self>, n: usize) -> ParseResult<Box<Self>> {
         if self.position.skip(n) {
+            let pos = self.position.pos();
+            self.examine(pos);
             Ok(self)
         } else {
+            let len = self.position.input().len();
+            self.examine(len + 1);
             Err(self)
         }
     }
@@ -652,8 +1706,30 @@ impl<R: RuleType> ParserState<R> {
     /// assert_eq!(result.unwrap().position().pos(), 2);
     /// ```
     #[inline]
-    pub fn skip_until(mut self: Box<Self>, strings: &[&str]) -> ParseResult<Box<Self>> {
-        self.position.skip_until(strings);
+    pub fn skip_until(self: Box<Self>, strings: &[&str]) -> ParseResult<Box<Self>> {
+        if self.config.byte_input() && strings.iter().any(|string| !string.is_ascii()) {
+            // Every byte of the strings stands for a `char` of the input.
+            let latin1: Vec<String> = strings
+                .iter()
+                .map(|string| string.bytes().map(char::from).collect())
+                .collect();
+            let strings: Vec<&str> = latin1.iter().map(|string| string.as_str()).collect();
+
+            return self.skip_until_strings(&strings);
+        }
+
+        self.skip_until_strings(strings)
+    }
+
+    fn skip_until_strings(mut self: Box<Self>, strings: &[&str]) -> ParseResult<Box<Self>> {
+        let pos = if self.position.skip_until(strings) {
+            let longest = strings.iter().map(|string| string.len()).max().unwrap_or(0);
+            self.position.pos() + longest
+        } else {
+            self.position.pos() + 1
+        };
+        self.examine(pos);
+
         Ok(self)
     }
 
@@ -680,7 +1756,9 @@ impl<R: RuleType> ParserState<R> {
     /// assert!(result.is_err());
     /// ```
     #[inline]
-    pub fn start_of_input(self: Box<Self>) -> ParseResult<Box<Self>> {
+    pub fn start_of_input(mut self: Box<Self>) -> ParseResult<Box<Self>> {
+        self.anchored = true;
+
         if self.position.at_start() {
             Ok(self)
         } else {
@@ -711,7 +1789,10 @@ impl<R: RuleType> ParserState<R> {
     /// assert!(result.is_ok());
     /// ```
     #[inline]
-    pub fn end_of_input(self: Box<Self>) -> ParseResult<Box<Self>> {
+    pub fn end_of_input(mut self: Box<Self>) -> ParseResult<Box<Self>> {
+        let pos = self.position.pos();
+        self.examine(pos + 1);
+
         if self.position.at_end() {
             Ok(self)
         } else {
@@ -984,14 +2065,19 @@ impl<R: RuleType> ParserState<R> {
         }
 
         let mut position = self.position.clone();
+        let mut reach = self.reach;
         let result = {
             let mut iter_b2t = self.stack[range].iter();
-            let matcher = |span: &Span| position.match_string(span.as_str());
+            let matcher = |span: &Span| {
+                reach = reach.max(position.pos() + span.as_str().len());
+                position.match_string(span.as_str())
+            };
             match match_dir {
                 MatchDir::BottomToTop => iter_b2t.all(matcher),
                 MatchDir::TopToBottom => iter_b2t.rev().all(matcher),
             }
         };
+        self.reach = reach;
         if result {
             self.position = position;
             Ok(self)
@@ -1049,6 +2135,8 @@ impl<R: RuleType> ParserState<R> {
         let mut position = self.position.clone();
         let mut result = true;
         while let Some(span) = self.stack.pop() {
+            let pos = position.pos() + span.as_str().len();
+            self.examine(pos);
             result = position.match_string(span.as_str());
             if !result {
                 break;
@@ -1091,6 +2179,118 @@ impl<R: RuleType> ParserState<R> {
         }
     }
 
+    /// Attempts `f` and, if it fails, recovers from the failure instead of backtracking: the
+    /// error is recorded, and input is skipped up to the next match of `sync` or the end of input,
+    /// skipping at least one character. The skipped input is covered by an error node with the
+    /// `rule` `Rule`, unless the state is atomic. Returns `Err` only if `f` fails at the end of
+    /// input, if it fails inside a lookahead, where no recovery happens, or if parsing is aborted
+    /// while skipping.
+    ///
+    /// Recovered errors are collected by [`pest::state_with_recovery`], while [`pest::state`]
+    /// reports the first one.
+    ///
+    /// [`pest::state_with_recovery`]: fn.state_with_recovery.html
+    /// [`pest::state`]: fn.state.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest;
+    /// # use std::sync::Arc;
+    /// # #[allow(non_camel_case_types)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// enum Rule {
+    ///     error
+    /// }
+    ///
+    /// let input: Arc<str> = Arc::from("a?;b");
+    /// let mut state: Box<pest::ParserState<Rule>> = pest::ParserState::new(input);
+    /// let result = state.recover(Rule::error, |s| s.match_string("ab"), |s| s.match_string(";"));
+    /// assert!(result.is_ok());
+    /// assert_eq!(result.unwrap().position().pos(), 2);
+    /// ```
+    pub fn recover<F, G>(self: Box<Self>, rule: R, f: F, mut sync: G) -> ParseResult<Box<Self>>
+    where
+        F: FnOnce(Box<Self>) -> ParseResult<Box<Self>>,
+        G: FnMut(Box<Self>) -> ParseResult<Box<Self>>,
+    {
+        if self.lookahead != Lookahead::None {
+            return f(self);
+        }
+
+        let start = self.position.clone();
+        let index = self.queue.len();
+
+        let (result, attempts) = self.checkpoint().isolate_attempts(f);
+        let mut state = match result {
+            Ok(state) => {
+                let mut state = state.checkpoint_ok();
+                state.merge_attempts(&attempts);
+                return Ok(state);
+            }
+            Err(state) => state.restore(),
+        };
+
+        state.position = start;
+        state.queue.truncate(index);
+
+        if state.position.at_end() || state.aborted.is_some() {
+            state.merge_attempts(&attempts);
+            return Err(state);
+        }
+
+        let start = state.position.pos();
+        let mut attempts = attempts;
+        attempts.stack = attempts
+            .stack
+            .map(|stack| state.rule_stack.iter().cloned().chain(stack).collect());
+        let error = parsing_error(&state.position, attempts, state.config.tracks_terminals());
+
+        state.position.skip(1);
+        let (result, _) = state.isolate_attempts(|mut state| {
+            while !state.position.at_end() {
+                if !state.step() {
+                    return Err(state);
+                }
+
+                match state.lookahead(true, &mut sync) {
+                    Ok(state) => return Ok(state),
+                    Err(mut new_state) => {
+                        new_state.position.skip(1);
+                        state = new_state;
+                    }
+                }
+            }
+
+            Ok(state)
+        });
+        let mut state = result?;
+
+        let end = state.position.pos();
+        let atomic = state.atomicity == Atomicity::Atomic;
+        if !atomic {
+            state.queue.push(QueueableToken::Start {
+                end_token_index: index + 1,
+                input_pos: start,
+            });
+            state.queue.push(QueueableToken::End {
+                start_token_index: index,
+                rule,
+                input_pos: end,
+                trivia: false,
+            });
+        }
+        state.recovered.push(RecoveredError {
+            rule,
+            start,
+            end,
+            error,
+            atomic,
+        });
+
+        Ok(state)
+    }
+
     /// Restores the original state of the `ParserState` when `f` returns an `Err`. Currently,
     /// this method only restores the stack.
     ///
@@ -1149,6 +2349,42 @@ impl<R: RuleType> ParserState<R> {
     }
 }
 
+// Merges attempts produced by a rule in isolation into the current ones, the same way `track` would
+// have if the rule had been run on top of them.
+fn merge_attempts<R: RuleType>(
+    (attempt_pos, attempt_stack): (&mut usize, &mut Option<Vec<R>>),
+    pos_attempts: &mut Vec<R>,
+    neg_attempts: &mut Vec<R>,
+    terminal_attempts: &mut Vec<Terminal>,
+    rule_stack: &[R],
+    attempts: &Attempts<R>,
+) {
+    if attempts.positives.is_empty()
+        && attempts.negatives.is_empty()
+        && attempts.terminals.is_empty()
+    {
+        return;
+    }
+
+    if attempts.pos > *attempt_pos {
+        pos_attempts.clear();
+        neg_attempts.clear();
+        terminal_attempts.clear();
+        *attempt_pos = attempts.pos;
+        *attempt_stack = None;
+    }
+
+    if attempts.pos == *attempt_pos {
+        pos_attempts.extend_from_slice(&attempts.positives);
+        neg_attempts.extend_from_slice(&attempts.negatives);
+        terminal_attempts.extend_from_slice(&attempts.terminals);
+
+        if let (None, Some(stack)) = (attempt_stack.as_ref(), attempts.stack.as_ref()) {
+            *attempt_stack = Some(rule_stack.iter().chain(stack).cloned().collect());
+        }
+    }
+}
+
 fn constrain_idxs(start: i32, end: Option<i32>, len: usize) -> Option<Range<usize>> {
     let start_norm = normalize_index(start, len)?;
     let end_norm = end.map_or(Some(len), |e| normalize_index(e, len))?;
@@ -1189,4 +2425,51 @@ mod test {
         assert_eq!(normalize_index(-5, 5), Some(0));
         assert_eq!(normalize_index(-6, 3), None);
     }
+
+    // expr = { expr ~ "-" ~ "1" | "1" }
+    fn expr(state: Box<ParserState<&'static str>>) -> ParseResult<Box<ParserState<&'static str>>> {
+        state.left_recursive("expr", |state| {
+            state.rule("expr", |s| {
+                s.sequence(|s| {
+                    expr(s)
+                        .and_then(|s| s.match_string("-"))
+                        .and_then(|s| s.stack_push(|s| s.match_string("1")))
+                })
+                .or_else(|s| s.match_string("1"))
+            })
+        })
+    }
+
+    #[test]
+    fn left_recursive() {
+        for config in vec![ParserConfig::new(), ParserConfig::new().memoize_all()] {
+            let pairs = state_with_config(Arc::from("1-1-1+"), config, |state| {
+                expr(state).and_then(|s| {
+                    assert!(s.stack.is_empty());
+                    s.match_string("+")
+                })
+            })
+            .unwrap();
+
+            assert_eq!(
+                format!("{}", pairs),
+                r#"["expr"(0, 5, ["expr"(0, 3, ["expr"(0, 1)])])]"#
+            );
+        }
+    }
+
+    #[test]
+    fn left_recursive_atomic() {
+        let pairs = state(Arc::from("1-1"), |state| {
+            state.rule("outer", |s| s.atomic(Atomicity::Atomic, expr))
+        })
+        .unwrap();
+
+        assert_eq!(format!("{}", pairs), r#"["outer"(0, 3)]"#);
+    }
+
+    #[test]
+    fn left_recursive_failure() {
+        assert!(state(Arc::from("-1"), expr).is_err());
+    }
 }
@@ -14,12 +14,14 @@ use std::ops::Range;
 use std::str;
 use std::sync::Arc;
 
+use line_index::{LineIndex, SharedLineIndex, Utf16Position};
 use span;
 
 /// A cursor position in a `&str` which provides useful methods to manually parse that string.
 #[derive(Clone)]
 pub struct Position {
     input: Arc<str>,
+    lines: SharedLineIndex,
     /// # Safety:
     ///
     /// `input[pos..]` must be a valid codepoint boundary (should not panic when indexing thus).
@@ -32,9 +34,13 @@ impl Position {
     /// # Safety:
     ///
     /// `input[pos..]` must be a valid codepoint boundary (should not panic when indexing thus).
-    pub(crate) unsafe fn new_unchecked(input: Arc<str>, pos: usize) -> Position {
+    pub(crate) unsafe fn new_unchecked(
+        input: Arc<str>,
+        lines: SharedLineIndex,
+        pos: usize,
+    ) -> Position {
         debug_assert!(input.get(pos..).is_some());
-        Position { input, pos }
+        Position { input, lines, pos }
     }
 
     /// Attempts to create a new `Position` at the given position. If the specified position is
@@ -53,7 +59,11 @@ impl Position {
     #[allow(clippy::new_ret_no_self)]
     pub fn new(input: Arc<str>, pos: usize) -> Option<Position> {
         match input.get(pos..) {
-            Some(..) => Some(Position { input, pos }),
+            Some(..) => Some(Position {
+                input,
+                lines: SharedLineIndex::default(),
+                pos,
+            }),
             None => None,
         }
     }
@@ -71,7 +81,11 @@ impl Position {
     #[inline]
     pub fn from_start(input: Arc<str>) -> Position {
         // Position 0 is always safe because it's always a valid UTF-8 border.
-        Position { input, pos: 0 }
+        Position {
+            input,
+            lines: SharedLineIndex::default(),
+            pos: 0,
+        }
     }
 
     /// Returns the byte position of this `Position` as a `usize`.
@@ -91,6 +105,34 @@ impl Position {
         self.pos
     }
 
+    #[inline]
+    pub(crate) fn input(&self) -> &Arc<str> {
+        &self.input
+    }
+
+    // Returns the `Position` at `pos` in the same input, sharing its `LineIndex`, or `None` if
+    // `pos` is not a valid position.
+    #[inline]
+    pub(crate) fn at(&self, pos: usize) -> Option<Position> {
+        self.input.get(pos..).map(|_| Position {
+            input: self.input.clone(),
+            lines: self.lines.clone(),
+            pos,
+        })
+    }
+
+    #[inline]
+    pub(crate) fn lines(&self) -> &SharedLineIndex {
+        &self.lines
+    }
+
+    // Returns the `LineIndex` of the input, shared with every `Position` and `Span` derived from
+    // this one.
+    #[inline]
+    pub(crate) fn line_index(&self) -> Arc<LineIndex> {
+        self.lines.get(&self.input)
+    }
+
     /// Creates a `Span` from two `Position`s.
     ///
     /// # Panics
@@ -115,7 +157,14 @@ impl Position {
         /* && self.input.get(self.pos..other.pos).is_some() */
         {
             // This is safe because the pos field of a Position should always be a valid str index.
-            unsafe { span::Span::new_unchecked(self.input.clone(), self.pos, other.pos) }
+            unsafe {
+                span::Span::new_shared_unchecked(
+                    self.input.clone(),
+                    self.lines.clone(),
+                    self.pos,
+                    other.pos,
+                )
+            }
         } else {
             // TODO: maybe a panic if self.pos < other.pos
             panic!("span created from positions from different inputs")
@@ -141,48 +190,7 @@ impl Position {
     /// ```
     #[inline]
     pub fn line_col(&self) -> (usize, usize) {
-        if self.pos > self.input.len() {
-            panic!("position out of bounds");
-        }
-
-        let mut pos = self.pos;
-        // Position's pos is always a UTF-8 border.
-        let slice = &self.input[..pos];
-        let mut chars = slice.chars().peekable();
-
-        let mut line_col = (1, 1);
-
-        while pos != 0 {
-            match chars.next() {
-                Some('\r') => {
-                    if let Some(&'\n') = chars.peek() {
-                        chars.next();
-
-                        if pos == 1 {
-                            pos -= 1;
-                        } else {
-                            pos -= 2;
-                        }
-
-                        line_col = (line_col.0 + 1, 1);
-                    } else {
-                        pos -= 1;
-                        line_col = (line_col.0, line_col.1 + 1);
-                    }
-                }
-                Some('\n') => {
-                    pos -= 1;
-                    line_col = (line_col.0 + 1, 1);
-                }
-                Some(c) => {
-                    pos -= c.len_utf8();
-                    line_col = (line_col.0, line_col.1 + 1);
-                }
-                None => unreachable!(),
-            }
-        }
-
-        line_col
+        self.line_index().line_col(&self.input, self.pos)
     }
 
     /// Returns the entire line of the input that contains this `Position`.
@@ -204,55 +212,137 @@ impl Position {
     /// ```
     #[inline]
     pub fn line_of(&self) -> &str {
-        if self.pos > self.input.len() {
-            panic!("position out of bounds");
-        };
-        // Safe since start and end can only be valid UTF-8 borders.
-        &self.input[self.find_line_start()..self.find_line_end()]
+        let index = self.line_index();
+        let range = index.line_range(index.line(self.pos)).unwrap();
+
+        // Safe since line starts are always right after a `\n`.
+        &self.input[range]
     }
 
-    pub(crate) fn find_line_start(&self) -> usize {
-        if self.input.is_empty() {
-            return 0;
-        };
-        // Position's pos is always a UTF-8 border.
-        let start = self
-            .input
-            .char_indices()
-            .rev()
-            .skip_while(|&(i, _)| i >= self.pos)
-            .find(|&(_, c)| c == '\n');
-        match start {
-            Some((i, _)) => i + 1,
-            None => 0,
-        }
+    /// Returns the line and column of this `Position`, with the column counted in UTF-16 code
+    /// units.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::Position;
+    /// # use std::sync::Arc;
+    /// let input: Arc<str> = Arc::from("\n💖a");
+    /// let pos = Position::new(input, 5).unwrap();
+    ///
+    /// assert_eq!(pos.line_col(), (2, 2));
+    /// assert_eq!(pos.line_col_utf16(), (2, 3));
+    /// ```
+    #[inline]
+    pub fn line_col_utf16(&self) -> (usize, usize) {
+        self.line_index().line_col_utf16(&self.input, self.pos)
     }
 
-    pub(crate) fn find_line_end(&self) -> usize {
-        if self.input.is_empty() {
-            0
-        } else if self.pos == self.input.len() - 1 {
-            self.input.len()
-        } else {
-            // Position's pos is always a UTF-8 border.
-            let end = self
-                .input
-                .char_indices()
-                .skip_while(|&(i, _)| i < self.pos)
-                .find(|&(_, c)| c == '\n');
-            match end {
-                Some((i, _)) => i + 1,
-                None => self.input.len(),
-            }
+    /// Returns the number of `char`s before this `Position`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::Position;
+    /// # use std::sync::Arc;
+    /// let input: Arc<str> = Arc::from("💖a");
+    /// let pos = Position::new(input, 5).unwrap();
+    ///
+    /// assert_eq!(pos.char_offset(), 2);
+    /// ```
+    #[inline]
+    pub fn char_offset(&self) -> usize {
+        self.line_index().char_offset(&self.input, self.pos)
+    }
+
+    /// Returns the Language Server Protocol position of this `Position`.
+    #[inline]
+    pub fn utf16_position(&self) -> Utf16Position {
+        let (line, col) = self.line_col_utf16();
+
+        Utf16Position {
+            line: (line - 1) as u32,
+            character: (col - 1) as u32,
         }
     }
 
+    /// Attempts to create a new `Position` at the given line and column, counted in `char`s as in
+    /// [`line_col`]. Returns `None` if the input has no such line or column.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::Position;
+    /// # use std::sync::Arc;
+    /// let input: Arc<str> = Arc::from("\n💖a");
+    ///
+    /// assert_eq!(Position::from_line_col(input.clone(), 2, 2).unwrap().pos(), 5);
+    /// assert_eq!(Position::from_line_col(input, 1, 2), None);
+    /// ```
+    ///
+    /// [`line_col`]: #method.line_col
+    pub fn from_line_col(input: Arc<str>, line: usize, col: usize) -> Option<Position> {
+        let lines = SharedLineIndex::default();
+        let pos = lines.get(&input).pos_of_line_col(&input, line, col)?;
+
+        Some(Position { input, lines, pos })
+    }
+
+    /// Attempts to create a new `Position` at the given line and column, counted in UTF-16 code
+    /// units as in [`line_col_utf16`]. Returns `None` if the input has no such line or column, or
+    /// if the column splits a surrogate pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::Position;
+    /// # use std::sync::Arc;
+    /// let input: Arc<str> = Arc::from("\n💖a");
+    ///
+    /// assert_eq!(Position::from_line_col_utf16(input.clone(), 2, 3).unwrap().pos(), 5);
+    /// assert_eq!(Position::from_line_col_utf16(input, 2, 2), None);
+    /// ```
+    ///
+    /// [`line_col_utf16`]: #method.line_col_utf16
+    pub fn from_line_col_utf16(input: Arc<str>, line: usize, col: usize) -> Option<Position> {
+        let lines = SharedLineIndex::default();
+        let pos = lines.get(&input).pos_of_line_col_utf16(&input, line, col)?;
+
+        Some(Position { input, lines, pos })
+    }
+
+    /// Attempts to create a new `Position` after the first `offset` `char`s of the input. Returns
+    /// `None` if the input is shorter.
+    pub fn from_char_offset(input: Arc<str>, offset: usize) -> Option<Position> {
+        let lines = SharedLineIndex::default();
+        let pos = lines.get(&input).pos_of_char_offset(&input, offset)?;
+
+        Some(Position { input, lines, pos })
+    }
+
+    /// Attempts to create a new `Position` from a Language Server Protocol position. Returns
+    /// `None` if the input has no such line or character, or if the character splits a
+    /// surrogate pair.
+    pub fn from_utf16_position(input: Arc<str>, position: Utf16Position) -> Option<Position> {
+        Position::from_line_col_utf16(
+            input,
+            position.line as usize + 1,
+            position.character as usize + 1,
+        )
+    }
+
     /// Returns `true` when the `Position` points to the start of the input `&str`.
     #[inline]
     pub(crate) fn at_start(&self) -> bool {
         self.pos == 0
     }
 
+    /// Moves the `Position` `n` bytes forward, to a position known to be valid.
+    #[inline]
+    pub(crate) fn advance(&mut self, n: usize) {
+        self.pos += n;
+    }
+
     /// Returns `true` when the `Position` points to the end of the input `&str`.
     #[inline]
     pub(crate) fn at_end(&self) -> bool {
@@ -360,6 +450,107 @@ impl Position {
         }
     }
 
+    /// Matches the raw `bytes` from the `Position` and returns `true` if a match was made that ends
+    /// on a `char` boundary or `false` otherwise. If no match was made, `pos` will not be updated.
+    #[inline]
+    pub(crate) fn match_bytes(&mut self, bytes: &[u8]) -> bool {
+        let to = self.pos + bytes.len();
+
+        if Some(bytes) == self.input.as_bytes().get(self.pos..to) && self.input.is_char_boundary(to)
+        {
+            self.pos = to;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Matches the raw byte `range` from the `Position` and returns `true` if a match was made that
+    /// ends on a `char` boundary or `false` otherwise. If no match was made, `pos` will not be
+    /// updated.
+    #[inline]
+    pub(crate) fn match_byte_range(&mut self, range: Range<u8>) -> bool {
+        if let Some(&byte) = self.input.as_bytes().get(self.pos) {
+            if range.start <= byte && byte <= range.end && self.input.is_char_boundary(self.pos + 1)
+            {
+                self.pos += 1;
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Matches `bytes` from the `Position` of an input where every `char` stands for the byte of
+    /// the same value, ASCII case-insensitively if `insensitive`, and returns `true` if a match was
+    /// made or `false` otherwise. If no match was made, `pos` will not be updated.
+    #[inline]
+    pub(crate) fn match_latin1(&mut self, bytes: &[u8], insensitive: bool) -> bool {
+        let mut chars = self.input[self.pos..].chars();
+        let mut len = 0;
+
+        for &byte in bytes {
+            match chars.next() {
+                Some(c) if (c as u32) < 256 => {
+                    let matched = if insensitive {
+                        (c as u8).eq_ignore_ascii_case(&byte)
+                    } else {
+                        c as u8 == byte
+                    };
+
+                    if !matched {
+                        return false;
+                    }
+
+                    len += c.len_utf8();
+                }
+                _ => return false,
+            }
+        }
+
+        self.pos += len;
+        true
+    }
+
+    /// Matches a `char` from `range`, encoded in UTF-8, from the `Position` of an input where every
+    /// `char` stands for the byte of the same value, and returns `true` if a match was made or
+    /// `false` otherwise. If no match was made, `pos` will not be updated.
+    #[inline]
+    pub(crate) fn match_latin1_range(&mut self, range: Range<char>) -> bool {
+        let mut chars = self.input[self.pos..].chars();
+        let width = match chars.clone().next().map(|c| c as u32) {
+            Some(0x00..=0x7F) => 1,
+            Some(0xC0..=0xDF) => 2,
+            Some(0xE0..=0xEF) => 3,
+            Some(0xF0..=0xF7) => 4,
+            _ => return false,
+        };
+
+        let mut bytes = [0; 4];
+        let mut len = 0;
+
+        for byte in bytes.iter_mut().take(width) {
+            match chars.next() {
+                Some(c) if (c as u32) < 256 => {
+                    *byte = c as u8;
+                    len += c.len_utf8();
+                }
+                _ => return false,
+            }
+        }
+
+        match str::from_utf8(&bytes[..width])
+            .ok()
+            .and_then(|s| s.chars().next())
+        {
+            Some(c) if range.start <= c && c <= range.end => {
+                self.pos += len;
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// Case-insensitively matches `string` from the `Position` and returns `true` if a match was
     /// made or `false` otherwise. If no match was made, `pos` will not be updated.
     #[inline]
@@ -381,6 +572,31 @@ impl Position {
         }
     }
 
+    /// Matches `string` from the `Position` under full Unicode case folding and returns `true` if
+    /// a match was made or `false` otherwise, along with the end of the input looked at, the end
+    /// of input counting as one byte. If no match was made, `pos` will not be updated.
+    #[inline]
+    pub(crate) fn match_insensitive_unicode(&mut self, string: &str) -> (bool, usize) {
+        let mut expected = string.chars().flat_map(fold_case).peekable();
+        let mut pos = self.pos;
+
+        // Input `char`s are matched whole, so that `"s"` does not match the first half of `ß`.
+        while expected.peek().is_some() {
+            let c = match self.input[pos..].chars().next() {
+                Some(c) => c,
+                None => return (false, pos + 1),
+            };
+            pos += c.len_utf8();
+
+            if !fold_case(c).all(|folded| expected.next() == Some(folded)) {
+                return (false, pos);
+            }
+        }
+
+        self.pos = pos;
+        (true, pos)
+    }
+
     /// Matches `char` `range` from the `Position` and returns `true` if a match was made or `false`
     /// otherwise. If no match was made, `pos` will not be updated.
     #[inline]
@@ -434,6 +650,17 @@ impl Hash for Position {
     }
 }
 
+// Returns the full case folding of `c`, which lowercasing alone misses for `ß`, the final `σ`
+// and some compatibility characters, which only fold once uppercased.
+#[inline]
+fn fold_case(c: char) -> impl Iterator<Item = char> {
+    c.to_lowercase()
+        .flat_map(char::to_uppercase)
+        .flat_map(char::to_lowercase)
+        // The dotless `ı` is the only letter which uppercases to a letter of a different fold.
+        .map(move |folded| if c == '\u{131}' { c } else { folded })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -520,6 +747,70 @@ mod tests {
         assert_eq!(Position::new(input, 1).unwrap().line_of(), "\n");
     }
 
+    #[test]
+    fn line_col_utf16() {
+        let input: Arc<str> = Arc::from("𝄞a\r\n💖\n");
+
+        assert_eq!(
+            Position::new(input.clone(), 4).unwrap().line_col_utf16(),
+            (1, 3)
+        );
+        assert_eq!(
+            Position::new(input.clone(), 6).unwrap().line_col_utf16(),
+            (1, 5)
+        );
+        assert_eq!(
+            Position::new(input.clone(), 11).unwrap().line_col_utf16(),
+            (2, 3)
+        );
+        assert_eq!(Position::new(input, 12).unwrap().line_col_utf16(), (3, 1));
+    }
+
+    #[test]
+    fn utf16_round_trip() {
+        let input: Arc<str> = Arc::from("𝄞a\r\n💖\n嗨😀\r\nb");
+
+        for (pos, _) in input.char_indices().chain(Some((input.len(), ' '))) {
+            let position = Position::new(input.clone(), pos).unwrap();
+            let (line, col) = position.line_col();
+            let (line16, col16) = position.line_col_utf16();
+
+            assert_eq!(
+                Position::from_line_col(input.clone(), line, col),
+                Some(position.clone())
+            );
+            assert_eq!(
+                Position::from_line_col_utf16(input.clone(), line16, col16),
+                Some(position.clone())
+            );
+            assert_eq!(
+                Position::from_char_offset(input.clone(), position.char_offset()),
+                Some(position.clone())
+            );
+            assert_eq!(
+                Position::from_utf16_position(input.clone(), position.utf16_position()),
+                Some(position)
+            );
+        }
+    }
+
+    #[test]
+    fn from_line_col_out_of_input() {
+        let input: Arc<str> = Arc::from("💖\na");
+
+        assert_eq!(Position::from_line_col(input.clone(), 0, 1), None);
+        assert_eq!(Position::from_line_col(input.clone(), 1, 0), None);
+        assert_eq!(Position::from_line_col(input.clone(), 1, 3), None);
+        assert_eq!(Position::from_line_col(input.clone(), 2, 3), None);
+        assert_eq!(Position::from_line_col(input.clone(), 3, 1), None);
+        assert_eq!(Position::from_line_col_utf16(input.clone(), 1, 2), None);
+        assert_eq!(
+            Position::from_char_offset(input.clone(), 3).unwrap().pos(),
+            6
+        );
+        assert_eq!(Position::from_char_offset(input, 4), None);
+    }
+
     fn measure_skip(input: &Arc<str>, pos: usize, n: usize) -> Option<usize> {
         let mut p = Position::new(input.clone(), pos).unwrap();
         if p.skip(n) {
@@ -626,6 +917,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn match_insensitive_unicode() {
+        let input: Arc<str> = Arc::from("Straße ΣΊΣΥΦΟΣ ı");
+        let matches = |pos: usize, string: &str| {
+            let mut position = Position::new(input.clone(), pos).unwrap();
+            let (matched, examined) = position.match_insensitive_unicode(string);
+            (matched, position.pos(), examined)
+        };
+
+        assert_eq!(matches(0, "STRASSE"), (true, 7, 7));
+        assert_eq!(matches(0, "strasse "), (true, 8, 8));
+        assert_eq!(matches(0, "STRAẞE"), (true, 7, 7));
+        assert_eq!(matches(0, "strAS"), (false, 0, 6));
+        assert_eq!(matches(0, "STRASSX"), (false, 0, 7));
+        assert_eq!(matches(8, "σίσυφος"), (true, 22, 22));
+        assert_eq!(matches(23, "ı"), (true, 25, 25));
+        assert_eq!(matches(23, "I"), (false, 23, 25));
+        assert_eq!(matches(23, "ıı"), (false, 23, 26));
+    }
+
     #[test]
     fn cmp() {
         let input: Arc<str> = Arc::from("a");
@@ -660,4 +971,19 @@ mod tests {
 
         positions.insert(start);
     }
+
+    #[test]
+    fn shared_line_index() {
+        let input: Arc<str> = Arc::from("a\nb");
+        let start = Position::from_start(input.clone());
+        let span = start.span(&start.at(3).unwrap());
+        let index = start.line_index();
+
+        assert!(Arc::ptr_eq(&index, &span.line_index()));
+        assert!(Arc::ptr_eq(&index, &span.end_pos().line_index()));
+        assert!(!Arc::ptr_eq(
+            &index,
+            &Position::from_start(input).line_index()
+        ));
+    }
 }
@@ -0,0 +1,594 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Constructs useful in prefix, postfix, and infix operator parsing with the Pratt parsing method.
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::vec::Vec;
+use std::iter::Peekable;
+use std::marker::PhantomData;
+use std::ops::BitOr;
+
+use error::{Error, ErrorVariant};
+use iterators::Pair;
+use RuleType;
+
+/// Associativity of an infix [`Op`].
+///
+/// [`Op`]: struct.Op.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Assoc {
+    /// Left operator associativity, `a - b - c` is `(a - b) - c`
+    Left,
+    /// Right operator associativity, `a ^ b ^ c` is `a ^ (b ^ c)`
+    Right,
+    /// No operator associativity, `a < b < c` is an error
+    Neither,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Affix {
+    Prefix,
+    Postfix,
+    Infix(Assoc),
+}
+
+/// Prefix, postfix, or infix operator used in [`PrattParser`].
+///
+/// [`PrattParser`]: struct.PrattParser.html
+#[derive(Debug)]
+pub struct Op<R: RuleType> {
+    rule: R,
+    affix: Affix,
+    next: Option<Box<Op<R>>>,
+}
+
+impl<R: RuleType> Op<R> {
+    /// Creates a new prefix `Op` from a `Rule`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::pratt_parser::Op;
+    /// # #[allow(non_camel_case_types)]
+    /// # #[allow(dead_code)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// # enum Rule {
+    /// #     neg,
+    /// #     not
+    /// # }
+    /// Op::prefix(Rule::neg) | Op::prefix(Rule::not);
+    /// ```
+    pub fn prefix(rule: R) -> Op<R> {
+        Op::new(rule, Affix::Prefix)
+    }
+
+    /// Creates a new postfix `Op` from a `Rule`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::pratt_parser::Op;
+    /// # #[allow(non_camel_case_types)]
+    /// # #[allow(dead_code)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// # enum Rule {
+    /// #     call,
+    /// #     index
+    /// # }
+    /// Op::postfix(Rule::call) | Op::postfix(Rule::index);
+    /// ```
+    pub fn postfix(rule: R) -> Op<R> {
+        Op::new(rule, Affix::Postfix)
+    }
+
+    /// Creates a new infix `Op` from a `Rule` and `Assoc`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::pratt_parser::{Assoc, Op};
+    /// # #[allow(non_camel_case_types)]
+    /// # #[allow(dead_code)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// # enum Rule {
+    /// #     plus,
+    /// #     lt
+    /// # }
+    /// Op::infix(Rule::plus, Assoc::Left) | Op::infix(Rule::lt, Assoc::Neither);
+    /// ```
+    pub fn infix(rule: R, assoc: Assoc) -> Op<R> {
+        Op::new(rule, Affix::Infix(assoc))
+    }
+
+    fn new(rule: R, affix: Affix) -> Op<R> {
+        Op {
+            rule,
+            affix,
+            next: None,
+        }
+    }
+}
+
+impl<R: RuleType> BitOr for Op<R> {
+    type Output = Self;
+
+    fn bitor(mut self, rhs: Self) -> Self {
+        fn assign_next<R: RuleType>(op: &mut Op<R>, next: Op<R>) {
+            if let Some(ref mut child) = op.next {
+                assign_next(child, next);
+            } else {
+                op.next = Some(Box::new(next));
+            }
+        }
+
+        assign_next(&mut self, rhs);
+        self
+    }
+}
+
+/// List of operators and precedences, which can perform [Pratt parsing][1] on expressions
+/// contained in a [`Pairs`]. Unlike [`PrecClimber`], it handles prefix and postfix operators, and
+/// reports chained non-associative operators as an [`Error`].
+///
+/// The token pairs contained in the `Pairs` should be made of *primary* pairs, each with any
+/// number of prefix operators before it and postfix operators after it, separated by infix
+/// operators.
+///
+/// [1]: https://en.wikipedia.org/wiki/Operator-precedence_parser#Pratt_parsing
+/// [`Pairs`]: ../iterators/struct.Pairs.html
+/// [`PrecClimber`]: ../prec_climber/struct.PrecClimber.html
+/// [`Error`]: ../error/struct.Error.html
+#[derive(Debug)]
+pub struct PrattParser<R: RuleType> {
+    ops: Vec<(R, u32, Affix)>,
+    prec: u32,
+}
+
+impl<R: RuleType> Default for PrattParser<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R: RuleType> PrattParser<R> {
+    /// Creates a new `PrattParser` without any operators.
+    pub fn new() -> PrattParser<R> {
+        PrattParser {
+            ops: Vec::new(),
+            prec: 0,
+        }
+    }
+
+    /// Adds the `Op`s in `op` with a higher precedence than all the ones added before. In order to
+    /// have operators with same precedence, they need to be chained with `|` between them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::pratt_parser::{Assoc, Op, PrattParser};
+    /// # #[allow(non_camel_case_types)]
+    /// # #[allow(dead_code)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// # enum Rule {
+    /// #     lt,
+    /// #     plus,
+    /// #     minus,
+    /// #     times,
+    /// #     power,
+    /// #     neg,
+    /// #     question
+    /// # }
+    /// PrattParser::new()
+    ///     .op(Op::infix(Rule::lt, Assoc::Neither))
+    ///     .op(Op::infix(Rule::plus, Assoc::Left) | Op::infix(Rule::minus, Assoc::Left))
+    ///     .op(Op::infix(Rule::times, Assoc::Left))
+    ///     .op(Op::infix(Rule::power, Assoc::Right))
+    ///     .op(Op::prefix(Rule::neg))
+    ///     .op(Op::postfix(Rule::question));
+    /// ```
+    pub fn op(mut self, op: Op<R>) -> PrattParser<R> {
+        self.prec += 1;
+        let mut next = Some(op);
+
+        while let Some(op) = next.take() {
+            let Op {
+                rule,
+                affix,
+                next: op_next,
+            } = op;
+
+            self.ops.push((rule, self.prec, affix));
+            next = op_next.map(|op| *op);
+        }
+
+        self
+    }
+
+    /// Maps *primary* pairs with `primary`, returning a [`PrattParserMap`] which can be given
+    /// the operator mappings and then run on some `Pairs`.
+    ///
+    /// [`PrattParserMap`]: struct.PrattParserMap.html
+    pub fn map_primary<'pratt, F, T>(&'pratt self, primary: F) -> PrattParserMap<'pratt, R, F, T>
+    where
+        F: FnMut(Pair<R>) -> T,
+    {
+        PrattParserMap {
+            pratt: self,
+            primary,
+            prefix: None,
+            postfix: None,
+            infix: None,
+            phantom: PhantomData,
+        }
+    }
+
+    // find matching operator by `rule`
+    fn get(&self, rule: &R) -> Option<(u32, Affix)> {
+        self.ops
+            .iter()
+            .find(|(r, _, _)| r == rule)
+            .map(|(_, prec, affix)| (*prec, *affix))
+    }
+}
+
+type PrefixFn<'pratt, R, T> = Box<dyn FnMut(Pair<R>, T) -> T + 'pratt>;
+type PostfixFn<'pratt, R, T> = Box<dyn FnMut(T, Pair<R>) -> T + 'pratt>;
+type InfixFn<'pratt, R, T> = Box<dyn FnMut(T, Pair<R>, T) -> T + 'pratt>;
+
+/// A [`PrattParser`] together with the functions mapping *primary* pairs and operators, created
+/// with [`PrattParser::map_primary`].
+///
+/// [`PrattParser`]: struct.PrattParser.html
+/// [`PrattParser::map_primary`]: struct.PrattParser.html#method.map_primary
+pub struct PrattParserMap<'pratt, R, F, T>
+where
+    R: RuleType,
+    F: FnMut(Pair<R>) -> T,
+{
+    pratt: &'pratt PrattParser<R>,
+    primary: F,
+    prefix: Option<PrefixFn<'pratt, R, T>>,
+    postfix: Option<PostfixFn<'pratt, R, T>>,
+    infix: Option<InfixFn<'pratt, R, T>>,
+    phantom: PhantomData<T>,
+}
+
+impl<'pratt, R, F, T> PrattParserMap<'pratt, R, F, T>
+where
+    R: RuleType,
+    F: FnMut(Pair<R>) -> T,
+{
+    /// Maps prefix operators and their operand with `prefix`.
+    pub fn map_prefix<X>(mut self, prefix: X) -> Self
+    where
+        X: FnMut(Pair<R>, T) -> T + 'pratt,
+    {
+        self.prefix = Some(Box::new(prefix));
+        self
+    }
+
+    /// Maps postfix operators and their operand with `postfix`.
+    pub fn map_postfix<X>(mut self, postfix: X) -> Self
+    where
+        X: FnMut(T, Pair<R>) -> T + 'pratt,
+    {
+        self.postfix = Some(Box::new(postfix));
+        self
+    }
+
+    /// Maps infix operators and their operands with `infix`.
+    pub fn map_infix<X>(mut self, infix: X) -> Self
+    where
+        X: FnMut(T, Pair<R>, T) -> T + 'pratt,
+    {
+        self.infix = Some(Box::new(infix));
+        self
+    }
+
+    /// Performs the Pratt parsing algorithm on the `pairs` in a similar manner to map-reduce.
+    /// *Primary* pairs are mapped with the `primary` function and then reduced to one single
+    /// result with the operator functions.
+    ///
+    /// Returns an `Error` spanning both operators when a non-associative operator is chained with
+    /// another operator of the same precedence, as in `a < b < c`.
+    ///
+    /// # Panics
+    ///
+    /// Panics will occur when `pairs` is empty, when an operator is not in the position its
+    /// affix allows, or when an operator is met whose kind has no mapping function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest;
+    /// # use pest::pratt_parser::{Assoc, Op, PrattParser};
+    /// # use std::sync::Arc;
+    /// # #[allow(non_camel_case_types)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// enum Rule {
+    ///     int,
+    ///     neg,
+    ///     plus,
+    ///     times
+    /// }
+    ///
+    /// let input: Arc<str> = Arc::from("-1+2*3");
+    /// let pairs = pest::state(input, |state| {
+    ///     state.repeat(|s| {
+    ///         s.rule(Rule::int, |s| s.match_range('0'..'9'))
+    ///             .or_else(|s| s.rule(Rule::neg, |s| s.match_string("-")))
+    ///             .or_else(|s| s.rule(Rule::plus, |s| s.match_string("+")))
+    ///             .or_else(|s| s.rule(Rule::times, |s| s.match_string("*")))
+    ///     })
+    /// })
+    /// .unwrap();
+    ///
+    /// let pratt = PrattParser::new()
+    ///     .op(Op::infix(Rule::plus, Assoc::Left))
+    ///     .op(Op::infix(Rule::times, Assoc::Left))
+    ///     .op(Op::prefix(Rule::neg));
+    ///
+    /// let result = pratt
+    ///     .map_primary(|primary| primary.as_str().parse::<i32>().unwrap())
+    ///     .map_prefix(|_, rhs| -rhs)
+    ///     .map_infix(|lhs, op, rhs| match op.as_rule() {
+    ///         Rule::plus => lhs + rhs,
+    ///         Rule::times => lhs * rhs,
+    ///         _ => unreachable!(),
+    ///     })
+    ///     .parse(pairs);
+    ///
+    /// assert_eq!(result.unwrap(), 5);
+    /// ```
+    pub fn parse<P>(&mut self, pairs: P) -> Result<T, Error<R>>
+    where
+        P: Iterator<Item = Pair<R>>,
+    {
+        self.expr(&mut pairs.peekable(), 0)
+    }
+
+    fn expr<P>(&mut self, pairs: &mut Peekable<P>, rbp: u32) -> Result<T, Error<R>>
+    where
+        P: Iterator<Item = Pair<R>>,
+    {
+        let mut lhs = self.nud(pairs)?;
+        // The last non-associative operator applied at this level, with its precedence.
+        let mut neither: Option<(Pair<R>, u32)> = None;
+
+        while let Some((prec, affix)) = self.lbp(pairs) {
+            if prec <= rbp {
+                break;
+            }
+
+            let op = pairs.next().unwrap();
+
+            if let Some((ref prev, prev_prec)) = neither {
+                if prev_prec == prec {
+                    let message = format!(
+                        "non-associative operator {:?} cannot be chained with {:?}",
+                        prev.as_rule(),
+                        op.as_rule()
+                    );
+                    let span = prev.as_span().start_pos().span(&op.as_span().end_pos());
+
+                    return Err(Error::new_from_span(
+                        ErrorVariant::CustomError { message },
+                        span,
+                    ));
+                }
+            }
+
+            neither = match affix {
+                Affix::Infix(Assoc::Neither) => Some((op.clone(), prec)),
+                _ => None,
+            };
+            lhs = self.led(pairs, lhs, op, prec, affix)?;
+        }
+
+        Ok(lhs)
+    }
+
+    // Maps a primary pair or a prefix operator and its operand.
+    fn nud<P>(&mut self, pairs: &mut Peekable<P>) -> Result<T, Error<R>>
+    where
+        P: Iterator<Item = Pair<R>>,
+    {
+        let pair = pairs
+            .next()
+            .expect("Pratt parsing expects a non-empty Pairs");
+
+        match self.pratt.get(&pair.as_rule()) {
+            None => Ok((self.primary)(pair)),
+            Some((prec, Affix::Prefix)) => {
+                let rhs = self.expr(pairs, prec)?;
+                let prefix = self
+                    .prefix
+                    .as_mut()
+                    .expect("Could not map prefix operator, no map_prefix was given");
+
+                Ok(prefix(pair, rhs))
+            }
+            Some(_) => panic!(
+                "expected a prefix operator or a primary expression, found {:?}",
+                pair.as_rule()
+            ),
+        }
+    }
+
+    // Maps a postfix operator or an infix operator and its right operand.
+    fn led<P>(
+        &mut self,
+        pairs: &mut Peekable<P>,
+        lhs: T,
+        op: Pair<R>,
+        prec: u32,
+        affix: Affix,
+    ) -> Result<T, Error<R>>
+    where
+        P: Iterator<Item = Pair<R>>,
+    {
+        match affix {
+            Affix::Postfix => {
+                let postfix = self
+                    .postfix
+                    .as_mut()
+                    .expect("Could not map postfix operator, no map_postfix was given");
+
+                Ok(postfix(lhs, op))
+            }
+            Affix::Infix(assoc) => {
+                let rbp = match assoc {
+                    Assoc::Right => prec - 1,
+                    Assoc::Left | Assoc::Neither => prec,
+                };
+                let rhs = self.expr(pairs, rbp)?;
+                let infix = self
+                    .infix
+                    .as_mut()
+                    .expect("Could not map infix operator, no map_infix was given");
+
+                Ok(infix(lhs, op, rhs))
+            }
+            Affix::Prefix => unreachable!(),
+        }
+    }
+
+    // The binding power of the next operator, if any.
+    fn lbp<P>(&self, pairs: &mut Peekable<P>) -> Option<(u32, Affix)>
+    where
+        P: Iterator<Item = Pair<R>>,
+    {
+        let pair = pairs.peek()?;
+
+        match self.pratt.get(&pair.as_rule()) {
+            Some((_, Affix::Prefix)) | None => panic!(
+                "expected a postfix or infix operator, found {:?}",
+                pair.as_rule()
+            ),
+            op => op,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{state, ParseResult, ParserState};
+    use super::*;
+    use alloc::string::{String, ToString};
+    use iterators::Pairs;
+    use std::sync::Arc;
+
+    #[allow(non_camel_case_types)]
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    enum Rule {
+        num,
+        neg,
+        not,
+        question,
+        call,
+        lt,
+        eq,
+        plus,
+        minus,
+        times,
+        power,
+    }
+
+    // Every character is a pair: digits are primaries, the rest are operators.
+    fn pairs(input: &str) -> Pairs<Rule> {
+        fn token(state: Box<ParserState<Rule>>) -> ParseResult<Box<ParserState<Rule>>> {
+            state
+                .rule(Rule::num, |s| s.match_char_by(|c| c.is_ascii_digit()))
+                .or_else(|s| s.rule(Rule::neg, |s| s.match_string("-")))
+                .or_else(|s| s.rule(Rule::not, |s| s.match_string("!")))
+                .or_else(|s| s.rule(Rule::question, |s| s.match_string("?")))
+                .or_else(|s| s.rule(Rule::call, |s| s.match_string("()")))
+                .or_else(|s| s.rule(Rule::lt, |s| s.match_string("<")))
+                .or_else(|s| s.rule(Rule::eq, |s| s.match_string("=")))
+                .or_else(|s| s.rule(Rule::plus, |s| s.match_string("+")))
+                .or_else(|s| s.rule(Rule::minus, |s| s.match_string("~")))
+                .or_else(|s| s.rule(Rule::times, |s| s.match_string("*")))
+                .or_else(|s| s.rule(Rule::power, |s| s.match_string("^")))
+        }
+
+        let input: Arc<str> = Arc::from(input);
+        state(input, |state| {
+            state.repeat(token).and_then(|s| s.end_of_input())
+        })
+        .unwrap()
+    }
+
+    fn pratt() -> PrattParser<Rule> {
+        PrattParser::new()
+            .op(Op::infix(Rule::eq, Assoc::Neither))
+            .op(Op::infix(Rule::lt, Assoc::Neither))
+            .op(Op::infix(Rule::plus, Assoc::Left) | Op::infix(Rule::minus, Assoc::Left))
+            .op(Op::infix(Rule::times, Assoc::Left))
+            .op(Op::prefix(Rule::neg) | Op::prefix(Rule::not))
+            .op(Op::infix(Rule::power, Assoc::Right))
+            .op(Op::postfix(Rule::question) | Op::postfix(Rule::call))
+    }
+
+    // Prints the expression fully parenthesized.
+    fn parse(input: &str) -> Result<String, Error<Rule>> {
+        pratt()
+            .map_primary(|primary| primary.as_str().to_string())
+            .map_prefix(|op, rhs| format!("({}{})", op.as_str(), rhs))
+            .map_postfix(|lhs, op| format!("({}{})", lhs, op.as_str()))
+            .map_infix(|lhs, op, rhs| format!("({}{}{})", lhs, op.as_str(), rhs))
+            .parse(pairs(input))
+    }
+
+    #[test]
+    fn infix() {
+        assert_eq!(parse("1+2*3~4").unwrap(), "((1+(2*3))~4)");
+        assert_eq!(parse("1^2^3").unwrap(), "(1^(2^3))");
+        assert_eq!(parse("1*2^3").unwrap(), "(1*(2^3))");
+    }
+
+    #[test]
+    fn prefix() {
+        assert_eq!(parse("-1*2").unwrap(), "((-1)*2)");
+        assert_eq!(parse("!-1").unwrap(), "(!(-1))");
+        assert_eq!(parse("-1^2").unwrap(), "(-(1^2))");
+        assert_eq!(parse("1*-2+3").unwrap(), "((1*(-2))+3)");
+    }
+
+    #[test]
+    fn postfix() {
+        assert_eq!(parse("1?()").unwrap(), "((1?)())");
+        assert_eq!(parse("-1?").unwrap(), "(-(1?))");
+        assert_eq!(parse("1^2?").unwrap(), "(1^(2?))");
+    }
+
+    #[test]
+    fn non_associative() {
+        assert_eq!(parse("1<2").unwrap(), "(1<2)");
+        assert_eq!(parse("1<2=3<4").unwrap(), "((1<2)=(3<4))");
+        assert_eq!(parse("1+2<3*4").unwrap(), "((1+2)<(3*4))");
+    }
+
+    #[test]
+    fn non_associative_chained() {
+        let error = parse("1<2+3<4").unwrap_err();
+
+        assert_eq!(
+            error.variant,
+            ErrorVariant::CustomError {
+                message: "non-associative operator lt cannot be chained with lt".to_string()
+            }
+        );
+        assert_eq!(error.location, ::error::InputLocation::Span((1, 6)));
+
+        assert!(parse("1=2=3").is_err());
+        assert!(parse("1<2=3<4=5").is_err());
+    }
+}
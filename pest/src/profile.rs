@@ -0,0 +1,395 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Per-rule profiling, enabled by the `trace` feature.
+//!
+//! A [`Profiler`] is a [`Tracer`] which measures how often every rule is called and how long its
+//! calls take. Rules with many repeated attempts are backtracked over at the same position,
+//! which memoizing them with [`ParserConfig::memoize`] avoids.
+//!
+//! [`Profiler`]: struct.Profiler.html
+//! [`Tracer`]: ../trace/trait.Tracer.html
+//! [`ParserConfig::memoize`]: ../struct.ParserConfig.html#method.memoize
+
+use alloc::format;
+use alloc::vec::Vec;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "pretty-print")]
+use alloc::string::String;
+#[cfg(feature = "pretty-print")]
+use serde::ser::{SerializeSeq, SerializeStruct};
+
+use trace::Tracer;
+use RuleType;
+
+/// The statistics of the calls to a rule, collected by a [`Profiler`].
+///
+/// [`Profiler`]: struct.Profiler.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RuleProfile<R> {
+    /// The profiled rule
+    pub rule: R,
+    /// Number of calls
+    pub attempts: usize,
+    /// Number of calls which succeeded
+    pub successes: usize,
+    /// Number of calls which failed
+    pub failures: usize,
+    /// Number of calls at a position the rule had already been called at
+    pub repeats: usize,
+    /// Number of bytes consumed by the calls which succeeded
+    pub bytes: usize,
+    /// Time spent in the calls, including the rules they called. Recursive calls are only counted
+    /// once.
+    pub inclusive: Duration,
+    /// Time spent in the calls, excluding the rules they called
+    pub exclusive: Duration,
+}
+
+impl<R> RuleProfile<R> {
+    fn new(rule: R) -> RuleProfile<R> {
+        RuleProfile {
+            rule,
+            attempts: 0,
+            successes: 0,
+            failures: 0,
+            repeats: 0,
+            bytes: 0,
+            inclusive: Duration::new(0, 0),
+            exclusive: Duration::new(0, 0),
+        }
+    }
+}
+
+// A rule call in progress.
+#[derive(Debug)]
+struct Frame {
+    start: Instant,
+    children: Duration,
+}
+
+/// A `Tracer` which collects a [`RuleProfile`] for every rule called.
+///
+/// Its `Display` implementation prints a table of the profiles, slowest rules first.
+///
+/// [`RuleProfile`]: struct.RuleProfile.html
+///
+/// # Examples
+///
+/// ```
+/// # use std::sync::{Arc, Mutex};
+/// # use pest::ParserConfig;
+/// # use pest::profile::Profiler;
+/// # #[allow(non_camel_case_types)]
+/// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+/// enum Rule {
+///     a
+/// }
+///
+/// let profiler = Arc::new(Mutex::new(Profiler::new()));
+/// let config = ParserConfig::new().tracer(profiler.clone());
+/// let input: Arc<str> = Arc::from("aa");
+///
+/// pest::state_with_config(input, config, |state| {
+///     state.repeat(|s| s.rule(Rule::a, |s| s.match_string("a")))
+/// }).unwrap();
+///
+/// let profiles = profiler.lock().unwrap().profiles();
+/// assert_eq!(profiles[0].attempts, 3);
+/// assert_eq!(profiles[0].successes, 2);
+/// assert_eq!(profiles[0].bytes, 2);
+///
+/// println!("{}", profiler.lock().unwrap());
+/// ```
+#[derive(Debug)]
+pub struct Profiler<R> {
+    profiles: HashMap<R, RuleProfile<R>>,
+    calls: HashSet<(R, usize)>,
+    active: HashMap<R, usize>,
+    frames: Vec<Frame>,
+}
+
+impl<R: RuleType> Profiler<R> {
+    /// Creates an empty `Profiler`.
+    pub fn new() -> Profiler<R> {
+        Profiler {
+            profiles: HashMap::new(),
+            calls: HashSet::new(),
+            active: HashMap::new(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Returns the profile of every rule called, by decreasing exclusive time.
+    pub fn profiles(&self) -> Vec<RuleProfile<R>> {
+        let mut profiles: Vec<_> = self.profiles.values().cloned().collect();
+        profiles.sort_by(|a, b| b.exclusive.cmp(&a.exclusive).then(a.rule.cmp(&b.rule)));
+        profiles
+    }
+
+    /// Generates a string that stores the profiles in a pretty-printed JSON format. Times are in
+    /// nanoseconds.
+    #[cfg(feature = "pretty-print")]
+    pub fn to_json(&self) -> String {
+        ::serde_json::to_string_pretty(self).expect("Failed to pretty-print Profiler to json.")
+    }
+
+    fn exit(&mut self, rule: R) -> Option<&mut RuleProfile<R>> {
+        let frame = self.frames.pop()?;
+        let elapsed = frame.start.elapsed();
+
+        if let Some(parent) = self.frames.last_mut() {
+            parent.children += elapsed;
+        }
+
+        let outermost = match self.active.get_mut(&rule) {
+            Some(active) => {
+                *active -= 1;
+                *active == 0
+            }
+            None => true,
+        };
+        let profile = self.profiles.get_mut(&rule)?;
+
+        if outermost {
+            profile.inclusive += elapsed;
+        }
+        profile.exclusive += elapsed.checked_sub(frame.children).unwrap_or_default();
+
+        Some(profile)
+    }
+}
+
+impl<R: RuleType> Default for Profiler<R> {
+    fn default() -> Profiler<R> {
+        Profiler::new()
+    }
+}
+
+impl<R: RuleType> Tracer<R> for Profiler<R> {
+    fn enter(&mut self, rule: R, pos: usize, _depth: usize) {
+        let repeated = !self.calls.insert((rule, pos));
+        let profile = self
+            .profiles
+            .entry(rule)
+            .or_insert_with(|| RuleProfile::new(rule));
+
+        profile.attempts += 1;
+        if repeated {
+            profile.repeats += 1;
+        }
+
+        *self.active.entry(rule).or_insert(0) += 1;
+        self.frames.push(Frame {
+            start: Instant::now(),
+            children: Duration::new(0, 0),
+        });
+    }
+
+    fn success(&mut self, rule: R, start: usize, end: usize, _depth: usize) {
+        if let Some(profile) = self.exit(rule) {
+            profile.successes += 1;
+            profile.bytes += end - start;
+        }
+    }
+
+    fn failure(&mut self, rule: R, _pos: usize, _depth: usize) {
+        if let Some(profile) = self.exit(rule) {
+            profile.failures += 1;
+        }
+    }
+}
+
+impl<R: RuleType> fmt::Display for Profiler<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let profiles = self.profiles();
+        let names: Vec<_> = profiles
+            .iter()
+            .map(|profile| format!("{:?}", profile.rule))
+            .collect();
+        let width = names
+            .iter()
+            .map(|name| name.len())
+            .max()
+            .unwrap_or(0)
+            .max(4);
+
+        writeln!(
+            f,
+            "{:width$} {:>10} {:>10} {:>10} {:>10} {:>10} {:>14} {:>14}",
+            "rule",
+            "attempts",
+            "successes",
+            "failures",
+            "repeats",
+            "bytes",
+            "inclusive (ms)",
+            "exclusive (ms)",
+            width = width
+        )?;
+
+        for (name, profile) in names.iter().zip(&profiles) {
+            writeln!(
+                f,
+                "{:width$} {:>10} {:>10} {:>10} {:>10} {:>10} {:>14.3} {:>14.3}",
+                name,
+                profile.attempts,
+                profile.successes,
+                profile.failures,
+                profile.repeats,
+                profile.bytes,
+                profile.inclusive.as_secs_f64() * 1000.0,
+                profile.exclusive.as_secs_f64() * 1000.0,
+                width = width
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "pretty-print")]
+impl<R: RuleType> ::serde::Serialize for Profiler<R> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        let profiles = self.profiles();
+        let mut seq = serializer.serialize_seq(Some(profiles.len()))?;
+
+        for profile in &profiles {
+            seq.serialize_element(profile)?;
+        }
+
+        seq.end()
+    }
+}
+
+#[cfg(feature = "pretty-print")]
+impl<R: RuleType> ::serde::Serialize for RuleProfile<R> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        let rule = format!("{:?}", self.rule);
+
+        let mut ser = serializer.serialize_struct("RuleProfile", 8)?;
+        ser.serialize_field("rule", &rule)?;
+        ser.serialize_field("attempts", &self.attempts)?;
+        ser.serialize_field("successes", &self.successes)?;
+        ser.serialize_field("failures", &self.failures)?;
+        ser.serialize_field("repeats", &self.repeats)?;
+        ser.serialize_field("bytes", &self.bytes)?;
+        ser.serialize_field("inclusive", &(self.inclusive.as_nanos() as u64))?;
+        ser.serialize_field("exclusive", &(self.exclusive.as_nanos() as u64))?;
+        ser.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    use parser_config::ParserConfig;
+
+    #[allow(non_camel_case_types)]
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    enum Rule {
+        list,
+        item,
+    }
+
+    // list = { item ~ "," ~ list | item }
+    fn parse(input: &str) -> Profiler<Rule> {
+        let profiler = Arc::new(Mutex::new(Profiler::new()));
+        let config = ParserConfig::new().tracer(profiler.clone());
+
+        fn list(state: Box<::ParserState<Rule>>) -> ::ParseResult<Box<::ParserState<Rule>>> {
+            state.rule(Rule::list, |s| {
+                s.sequence(|s| {
+                    s.rule(Rule::item, |s| s.match_string("a"))
+                        .and_then(|s| s.match_string(","))
+                        .and_then(list)
+                })
+                .or_else(|s| s.rule(Rule::item, |s| s.match_string("a")))
+            })
+        }
+
+        ::state_with_config(Arc::from(input), config, list).unwrap();
+
+        Arc::try_unwrap(profiler).unwrap().into_inner().unwrap()
+    }
+
+    fn profile(profiler: &Profiler<Rule>, rule: Rule) -> RuleProfile<Rule> {
+        profiler.profiles[&rule]
+    }
+
+    #[test]
+    fn counts() {
+        let profiler = parse("a,a,a");
+
+        let list = profile(&profiler, Rule::list);
+        assert_eq!(list.attempts, 3);
+        assert_eq!(list.successes, 3);
+        assert_eq!(list.failures, 0);
+        assert_eq!(list.repeats, 0);
+        assert_eq!(list.bytes, 5 + 3 + 1);
+
+        let item = profile(&profiler, Rule::item);
+        assert_eq!(item.attempts, 4);
+        assert_eq!(item.successes, 4);
+        assert_eq!(item.repeats, 1);
+        assert_eq!(item.bytes, 4);
+    }
+
+    #[test]
+    fn times() {
+        let profiler = parse("a,a,a,a,a,a");
+
+        let list = profile(&profiler, Rule::list);
+        let item = profile(&profiler, Rule::item);
+        assert!(list.exclusive <= list.inclusive);
+        assert!(item.exclusive == item.inclusive);
+        assert!(list.inclusive >= list.exclusive + item.inclusive);
+    }
+
+    #[test]
+    fn display() {
+        let profiler = parse("a");
+        let table = format!("{}", profiler);
+        let lines: Vec<_> = table.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("rule   attempts  successes   failures    repeats"));
+        assert!(lines[1..].iter().any(|line| line.starts_with("list")));
+        assert!(lines[1..].iter().any(|line| line.starts_with("item")));
+    }
+
+    #[test]
+    #[cfg(feature = "pretty-print")]
+    fn to_json() {
+        let profiler = parse("a");
+        let json: ::serde_json::Value = ::serde_json::from_str(&profiler.to_json()).unwrap();
+        let profiles = json.as_array().unwrap();
+
+        assert_eq!(profiles.len(), 2);
+        for profile in profiles {
+            match profile["rule"].as_str().unwrap() {
+                "list" => assert_eq!(profile["attempts"], 1),
+                "item" => assert_eq!(profile["repeats"], 1),
+                _ => unreachable!(),
+            }
+            assert!(profile["inclusive"].is_u64());
+        }
+    }
+}
@@ -0,0 +1,619 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! A selector language, similar to CSS selectors, for extracting pairs out of `Pairs`.
+//!
+//! A selector is a list of *compound* selectors separated by *combinators*:
+//!
+//! * `a > b` selects `b` pairs directly inside an `a` pair
+//! * `a b` selects `b` pairs anywhere inside an `a` pair
+//!
+//! Compound selectors are a rule name or `*` for any rule, followed by any number of filters:
+//!
+//! * `:nth-child(n)` keeps the `n`th of its siblings, counting from `1`
+//! * `:first-child` and `:last-child` keep the first and last of its siblings
+//! * `[str="text"]` keeps pairs whose `as_str()` is `text`, while `^=`, `$=`, and `*=` keep pairs
+//!   whose `as_str()` starts with, ends with, or contains `text`; `\"` and `\\` escape quotes
+//!   and backslashes
+//!
+//! A name is compared with the `Debug` representation of the rules, which is the rule's name in
+//! the grammar for generated parsers. Several selectors can be separated by `,` to select the
+//! pairs matched by any of them.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! let query = Query::new(r#"table > pair:first-child > key[str="name"]"#).unwrap();
+//!
+//! for key in query.select(pairs) {
+//!     println!("{}", key.as_str());
+//! }
+//! ```
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use error::Error;
+use iterators::{Pair, Pairs};
+use parser_state::{state, ParseResult, ParserState};
+use RuleType;
+
+/// Rules of the selector grammar, used in the `Error`s returned by [`Query::new`].
+///
+/// [`Query::new`]: struct.Query.html#method.new
+#[allow(missing_docs, non_camel_case_types)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Rule {
+    query,
+    selector,
+    compound,
+    name,
+    any,
+    child,
+    descendant,
+    nth_child,
+    first_child,
+    last_child,
+    text,
+    operator,
+    string,
+    number,
+}
+
+/// A compiled selector, which can be run on any number of `Pairs`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Query {
+    selectors: Vec<Vec<Step>>,
+}
+
+// A compound selector, along with how it relates to the previous one.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Step {
+    combinator: Combinator,
+    name: Option<String>,
+    filters: Vec<Filter>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Combinator {
+    Child,
+    Descendant,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Filter {
+    NthChild(usize),
+    FirstChild,
+    LastChild,
+    Equals(String),
+    StartsWith(String),
+    EndsWith(String),
+    Contains(String),
+}
+
+// A pair on the path from the top-level `Pairs` to the one being matched.
+struct Node<R> {
+    pair: Pair<R>,
+    name: String,
+    index: usize,
+    siblings: usize,
+}
+
+impl Query {
+    /// Compiles `selector` into a `Query`, returning an `Error` pointing into `selector` if it is
+    /// invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::query::Query;
+    /// assert!(Query::new("object > pair > string").is_ok());
+    /// assert!(Query::new("object >").is_err());
+    /// ```
+    pub fn new(selector: &str) -> Result<Query, Error<Rule>> {
+        let query = state(Arc::from(selector), query)?.next().unwrap();
+        let selectors = query
+            .into_inner()
+            .map(|selector| {
+                let mut combinator = Combinator::Descendant;
+                let mut steps = Vec::new();
+
+                for pair in selector.into_inner() {
+                    match pair.as_rule() {
+                        Rule::child => combinator = Combinator::Child,
+                        Rule::descendant => combinator = Combinator::Descendant,
+                        Rule::compound => steps.push(step(combinator, pair)),
+                        _ => unreachable!(),
+                    }
+                }
+
+                steps
+            })
+            .collect();
+
+        Ok(Query { selectors })
+    }
+
+    /// Returns all the pairs in `pairs` and their inner pairs that match the `Query`, in input
+    /// order.
+    pub fn select<R: RuleType>(&self, pairs: Pairs<R>) -> Vec<Pair<R>> {
+        let mut selected = Vec::new();
+        self.visit(pairs, &mut Vec::new(), &mut |pair| {
+            selected.push(pair);
+            true
+        });
+
+        selected
+    }
+
+    /// Returns the first pair in `pairs` and their inner pairs that matches the `Query`, in input
+    /// order.
+    pub fn select_first<R: RuleType>(&self, pairs: Pairs<R>) -> Option<Pair<R>> {
+        let mut selected = None;
+        self.visit(pairs, &mut Vec::new(), &mut |pair| {
+            selected = Some(pair);
+            false
+        });
+
+        selected
+    }
+
+    // Walks `pairs` depth-first, passing matching pairs to `f` until it returns `false`.
+    fn visit<R: RuleType, F>(&self, pairs: Pairs<R>, path: &mut Vec<Node<R>>, f: &mut F) -> bool
+    where
+        F: FnMut(Pair<R>) -> bool,
+    {
+        let siblings = pairs.clone().count();
+
+        for (index, pair) in pairs.enumerate() {
+            path.push(Node {
+                name: format!("{:?}", pair.as_rule()),
+                pair: pair.clone(),
+                index,
+                siblings,
+            });
+
+            let matched = self
+                .selectors
+                .iter()
+                .any(|selector| matches(selector, path));
+
+            if matched && !f(pair.clone()) || !self.visit(pair.into_inner(), path, f) {
+                return false;
+            }
+
+            path.pop();
+        }
+
+        true
+    }
+}
+
+impl FromStr for Query {
+    type Err = Error<Rule>;
+
+    fn from_str(selector: &str) -> Result<Query, Error<Rule>> {
+        Query::new(selector)
+    }
+}
+
+fn step(combinator: Combinator, compound: Pair<Rule>) -> Step {
+    let mut name = None;
+    let mut filters = Vec::new();
+
+    for pair in compound.into_inner() {
+        match pair.as_rule() {
+            Rule::name => name = Some(pair.as_str().into()),
+            Rule::any => {}
+            Rule::nth_child => {
+                let number = pair.into_inner().next().unwrap();
+                // Numbers too large to be an index can never match.
+                filters.push(Filter::NthChild(number.as_str().parse().unwrap_or(0)));
+            }
+            Rule::first_child => filters.push(Filter::FirstChild),
+            Rule::last_child => filters.push(Filter::LastChild),
+            Rule::text => {
+                let mut inner = pair.into_inner();
+                let operator = inner.next().unwrap();
+                let string = unescape(inner.next().unwrap().as_str());
+
+                filters.push(match operator.as_str() {
+                    "=" => Filter::Equals(string),
+                    "^=" => Filter::StartsWith(string),
+                    "$=" => Filter::EndsWith(string),
+                    "*=" => Filter::Contains(string),
+                    _ => unreachable!(),
+                });
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    Step {
+        combinator,
+        name,
+        filters,
+    }
+}
+
+fn unescape(string: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = string.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            c => unescaped.push(c),
+        }
+    }
+
+    unescaped
+}
+
+// Matches `steps` right to left against `path`, whose last node is the one being selected.
+fn matches<R: RuleType>(steps: &[Step], path: &[Node<R>]) -> bool {
+    let (step, node) = match (steps.last(), path.last()) {
+        (Some(step), Some(node)) => (step, node),
+        (None, _) => return true,
+        (_, None) => return false,
+    };
+
+    if !step_matches(step, node) {
+        return false;
+    }
+
+    let steps = &steps[..steps.len() - 1];
+    let path = &path[..path.len() - 1];
+
+    if steps.is_empty() {
+        return true;
+    }
+
+    match step.combinator {
+        Combinator::Child => matches(steps, path),
+        Combinator::Descendant => (1..=path.len())
+            .rev()
+            .any(|end| matches(steps, &path[..end])),
+    }
+}
+
+fn step_matches<R: RuleType>(step: &Step, node: &Node<R>) -> bool {
+    if let Some(ref name) = step.name {
+        if *name != node.name {
+            return false;
+        }
+    }
+
+    step.filters.iter().all(|filter| match *filter {
+        Filter::NthChild(n) => node.index + 1 == n,
+        Filter::FirstChild => node.index == 0,
+        Filter::LastChild => node.index + 1 == node.siblings,
+        Filter::Equals(ref string) => node.pair.as_str() == string,
+        Filter::StartsWith(ref string) => node.pair.as_str().starts_with(string.as_str()),
+        Filter::EndsWith(ref string) => node.pair.as_str().ends_with(string.as_str()),
+        Filter::Contains(ref string) => node.pair.as_str().contains(string.as_str()),
+    })
+}
+
+// query = { SOI ~ ws* ~ selector ~ (ws* ~ "," ~ ws* ~ selector)* ~ ws* ~ EOI }
+fn query(state: Box<ParserState<Rule>>) -> ParseResult<Box<ParserState<Rule>>> {
+    state.rule(Rule::query, |s| {
+        s.sequence(|s| {
+            s.start_of_input()
+                .and_then(|s| s.repeat(ws))
+                .and_then(selector)
+                .and_then(|s| {
+                    s.repeat(|s| {
+                        s.sequence(|s| {
+                            s.repeat(ws)
+                                .and_then(|s| s.match_string(","))
+                                .and_then(|s| s.repeat(ws))
+                                .and_then(selector)
+                        })
+                    })
+                })
+                .and_then(|s| s.repeat(ws))
+                .and_then(|s| s.end_of_input())
+        })
+    })
+}
+
+// selector = { compound ~ (ws* ~ child ~ ws* ~ compound | descendant ~ compound)* }
+fn selector(state: Box<ParserState<Rule>>) -> ParseResult<Box<ParserState<Rule>>> {
+    state.rule(Rule::selector, |s| {
+        s.sequence(|s| {
+            compound(s).and_then(|s| {
+                s.repeat(|s| {
+                    s.sequence(|s| {
+                        s.repeat(ws)
+                            .and_then(|s| s.rule(Rule::child, |s| s.match_string(">")))
+                            .and_then(|s| s.repeat(ws))
+                            .and_then(compound)
+                    })
+                    .or_else(|s| {
+                        s.sequence(|s| {
+                            s.rule(Rule::descendant, |s| {
+                                s.sequence(|s| ws(s).and_then(|s| s.repeat(ws)))
+                            })
+                            .and_then(compound)
+                        })
+                    })
+                })
+            })
+        })
+    })
+}
+
+// compound = { (name | any) ~ filter* | filter+ }
+fn compound(state: Box<ParserState<Rule>>) -> ParseResult<Box<ParserState<Rule>>> {
+    state.rule(Rule::compound, |s| {
+        s.sequence(|s| {
+            name(s)
+                .or_else(|s| s.rule(Rule::any, |s| s.match_string("*")))
+                .and_then(|s| s.repeat(filter))
+        })
+        .or_else(|s| s.sequence(|s| filter(s).and_then(|s| s.repeat(filter))))
+    })
+}
+
+// name = { (ASCII_ALPHA | "_") ~ (ASCII_ALPHANUMERIC | "_")* }
+fn name(state: Box<ParserState<Rule>>) -> ParseResult<Box<ParserState<Rule>>> {
+    state.rule(Rule::name, |s| {
+        s.sequence(|s| {
+            s.match_char_by(|c| c.is_ascii_alphabetic() || c == '_')
+                .and_then(|s| {
+                    s.repeat(|s| s.match_char_by(|c| c.is_ascii_alphanumeric() || c == '_'))
+                })
+        })
+    })
+}
+
+// filter = _{ nth_child | first_child | last_child | text }
+fn filter(state: Box<ParserState<Rule>>) -> ParseResult<Box<ParserState<Rule>>> {
+    state
+        .rule(Rule::nth_child, |s| {
+            s.sequence(|s| {
+                s.match_string(":nth-child(")
+                    .and_then(|s| s.repeat(ws))
+                    .and_then(|s| {
+                        s.rule(Rule::number, |s| {
+                            s.sequence(|s| digit(s).and_then(|s| s.repeat(digit)))
+                        })
+                    })
+                    .and_then(|s| s.repeat(ws))
+                    .and_then(|s| s.match_string(")"))
+            })
+        })
+        .or_else(|s| s.rule(Rule::first_child, |s| s.match_string(":first-child")))
+        .or_else(|s| s.rule(Rule::last_child, |s| s.match_string(":last-child")))
+        .or_else(|s| s.rule(Rule::text, text))
+}
+
+fn digit(state: Box<ParserState<Rule>>) -> ParseResult<Box<ParserState<Rule>>> {
+    state.match_char_by(|c| c.is_ascii_digit())
+}
+
+// text = { "[" ~ ws* ~ "str" ~ ws* ~ operator ~ ws* ~ "\"" ~ string ~ "\"" ~ ws* ~ "]" }
+fn text(state: Box<ParserState<Rule>>) -> ParseResult<Box<ParserState<Rule>>> {
+    state.sequence(|s| {
+        s.match_string("[")
+            .and_then(|s| s.repeat(ws))
+            .and_then(|s| s.match_string("str"))
+            .and_then(|s| s.repeat(ws))
+            .and_then(|s| {
+                s.rule(Rule::operator, |s| {
+                    s.match_string("=")
+                        .or_else(|s| s.match_string("^="))
+                        .or_else(|s| s.match_string("$="))
+                        .or_else(|s| s.match_string("*="))
+                })
+            })
+            .and_then(|s| s.repeat(ws))
+            .and_then(|s| s.match_string("\""))
+            .and_then(|s| s.rule(Rule::string, |s| s.repeat(string_char)))
+            .and_then(|s| s.match_string("\""))
+            .and_then(|s| s.repeat(ws))
+            .and_then(|s| s.match_string("]"))
+    })
+}
+
+// string_char = _{ "\\" ~ ("\"" | "\\") | !("\"" | "\\") ~ ANY }
+fn string_char(state: Box<ParserState<Rule>>) -> ParseResult<Box<ParserState<Rule>>> {
+    state
+        .sequence(|s| {
+            s.match_string("\\")
+                .and_then(|s| s.match_string("\"").or_else(|s| s.match_string("\\")))
+        })
+        .or_else(|s| s.match_char_by(|c| c != '"' && c != '\\'))
+}
+
+fn ws(state: Box<ParserState<Rule>>) -> ParseResult<Box<ParserState<Rule>>> {
+    state.match_char_by(|c| c == ' ' || c == '\t' || c == '\r' || c == '\n')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::state;
+    use super::*;
+    use alloc::vec;
+
+    #[allow(non_camel_case_types)]
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    enum Json {
+        object,
+        pair,
+        string,
+        number,
+        array,
+    }
+
+    // A JSON-like tree made of single characters: `{`...`}` is an object of pairs made of a
+    // string and a value, `[`...`]` is an array, lowercase letters are strings, and digits are
+    // numbers.
+    fn parse(input: &str) -> Pairs<Json> {
+        fn value(state: Box<ParserState<Json>>) -> ParseResult<Box<ParserState<Json>>> {
+            state
+                .rule(Json::object, |s| {
+                    s.sequence(|s| {
+                        s.match_string("{")
+                            .and_then(|s| {
+                                s.repeat(|s| {
+                                    s.rule(Json::pair, |s| {
+                                        s.sequence(|s| {
+                                            s.rule(Json::string, |s| {
+                                                s.match_char_by(|c| c.is_ascii_lowercase())
+                                            })
+                                            .and_then(value)
+                                        })
+                                    })
+                                })
+                            })
+                            .and_then(|s| s.match_string("}"))
+                    })
+                })
+                .or_else(|s| {
+                    s.rule(Json::array, |s| {
+                        s.sequence(|s| {
+                            s.match_string("[")
+                                .and_then(|s| s.repeat(value))
+                                .and_then(|s| s.match_string("]"))
+                        })
+                    })
+                })
+                .or_else(|s| {
+                    s.rule(Json::string, |s| {
+                        s.match_char_by(|c| c.is_ascii_lowercase())
+                    })
+                })
+                .or_else(|s| s.rule(Json::number, |s| s.match_char_by(|c| c.is_ascii_digit())))
+        }
+
+        state(Arc::from(input), |s| {
+            value(s).and_then(|s| s.end_of_input())
+        })
+        .unwrap()
+    }
+
+    fn select(selector: &str, input: &str) -> Vec<(usize, String)> {
+        Query::new(selector)
+            .unwrap()
+            .select(parse(input))
+            .into_iter()
+            .map(|pair| (pair.as_span().start(), pair.as_str().into()))
+            .collect()
+    }
+
+    fn strs(selected: Vec<(usize, String)>) -> Vec<String> {
+        selected.into_iter().map(|(_, string)| string).collect()
+    }
+
+    #[test]
+    fn child() {
+        assert_eq!(
+            strs(select("object > pair > string", "{a1b{c2}}")),
+            vec!["a", "b", "c"]
+        );
+        assert_eq!(
+            strs(select("pair > object > pair", "{a1b{c2}}")),
+            vec!["c2"]
+        );
+        assert_eq!(
+            strs(select("object > pair > number", "{a1b{c2}}")),
+            vec!["1", "2"]
+        );
+    }
+
+    #[test]
+    fn descendant() {
+        assert_eq!(
+            strs(select("array number", "[1{a[2]}3]")),
+            vec!["1", "2", "3"]
+        );
+        assert_eq!(
+            strs(select("array  object  number", "[1{a[2]}3]")),
+            vec!["2"]
+        );
+        assert_eq!(
+            strs(select("array > number", "[1{a[2]}3]")),
+            vec!["1", "2", "3"]
+        );
+        assert_eq!(
+            strs(select("object array > number", "[1{a[2]}3]")),
+            vec!["2"]
+        );
+    }
+
+    #[test]
+    fn descendant_backtracks() {
+        // The `object` matching the innermost `pair` is not the one directly inside the array.
+        assert_eq!(
+            select("array > object pair > string", "[{a{b1}}]"),
+            vec![(2, "a".into()), (4, "b".into())]
+        );
+    }
+
+    #[test]
+    fn any_and_children() {
+        assert_eq!(strs(select("array > *:first-child", "[1a2]")), vec!["1"]);
+        assert_eq!(strs(select("array > :last-child", "[1a2]")), vec!["2"]);
+        assert_eq!(strs(select("array > :nth-child( 2 )", "[1a2]")), vec!["a"]);
+        assert_eq!(strs(select("*:nth-child(1)", "[1a2]")), vec!["[1a2]", "1"]);
+        assert!(select(":nth-child(99999999999999999999999)", "[1a2]").is_empty());
+    }
+
+    #[test]
+    fn text() {
+        let input = "{a1b2c3}";
+
+        assert_eq!(strs(select(r#"pair[str="b2"]"#, input)), vec!["b2"]);
+        assert_eq!(strs(select(r#"pair[str^="c"]"#, input)), vec!["c3"]);
+        assert_eq!(strs(select(r#"pair[ str $= "1" ] "#, input)), vec!["a1"]);
+        assert_eq!(strs(select(r#"object[str*="b2"]"#, input)), vec![input]);
+        assert!(select(r#"pair[str="\"a1"]"#, input).is_empty());
+    }
+
+    #[test]
+    fn alternatives() {
+        assert_eq!(
+            strs(select("number, pair > string", "[1{a2}]")),
+            vec!["1", "a", "2"]
+        );
+    }
+
+    #[test]
+    fn select_first() {
+        let query: Query = "number".parse().unwrap();
+
+        assert_eq!(query.select_first(parse("[a12]")).unwrap().as_str(), "1");
+        assert_eq!(query.select_first(parse("[ab]")), None);
+    }
+
+    #[test]
+    fn unescape() {
+        assert_eq!(super::unescape(r#"a\"b\\c"#), r#"a"b\c"#);
+    }
+
+    #[test]
+    fn errors() {
+        assert!(Query::new("").is_err());
+        assert!(Query::new("object >").is_err());
+        assert!(Query::new("object,").is_err());
+        assert!(Query::new("pair[str=\"a]").is_err());
+        assert!(Query::new(":nth-child()").is_err());
+        assert!(Query::new("1pair").is_err());
+
+        let error = Query::new("object > > pair").unwrap_err();
+
+        assert_eq!(error.location, ::error::InputLocation::Pos(9));
+    }
+}
@@ -0,0 +1,85 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use error::Error;
+use iterators::Pairs;
+use RuleType;
+
+/// The outcome of a parse which may have recovered from errors with [`ParserState::recover`].
+///
+/// A parse that recovered from every error produces `Pairs` where the skipped input is covered by
+/// error nodes, along with one `Error` per error node. A parse that failed despite recovery
+/// produces no `Pairs`, and its errors end with the one that made it fail.
+///
+/// [`ParserState::recover`]: ../struct.ParserState.html#method.recover
+#[derive(Clone, Debug)]
+pub struct Recovered<R: RuleType> {
+    pairs: Option<Pairs<R>>,
+    errors: Vec<Error<R>>,
+}
+
+impl<R: RuleType> Recovered<R> {
+    pub(crate) fn new(pairs: Option<Pairs<R>>, errors: Vec<Error<R>>) -> Recovered<R> {
+        debug_assert!(pairs.is_some() || !errors.is_empty());
+
+        Recovered { pairs, errors }
+    }
+
+    /// Returns the parsed `Pairs`, or `None` if parsing failed.
+    pub fn pairs(&self) -> Option<&Pairs<R>> {
+        self.pairs.as_ref()
+    }
+
+    /// Returns every error found, ordered by position.
+    pub fn errors(&self) -> &[Error<R>] {
+        &self.errors
+    }
+
+    /// Returns `true` if parsing succeeded without recovering from any error.
+    pub fn is_ok(&self) -> bool {
+        self.pairs.is_some() && self.errors.is_empty()
+    }
+
+    /// Splits the `Recovered` into its `Pairs` and errors.
+    pub fn into_parts(self) -> (Option<Pairs<R>>, Vec<Error<R>>) {
+        (self.pairs, self.errors)
+    }
+
+    /// Converts the `Recovered` into the `Result` of a parse without recovery: the `Pairs` if no
+    /// error was found, or the first error otherwise.
+    pub fn into_result(mut self) -> Result<Pairs<R>, Error<R>> {
+        match self.pairs {
+            Some(pairs) if self.errors.is_empty() => Ok(pairs),
+            _ => Err(self.errors.remove(0)),
+        }
+    }
+}
+
+impl<R: RuleType> From<Result<Pairs<R>, Error<R>>> for Recovered<R> {
+    fn from(result: Result<Pairs<R>, Error<R>>) -> Recovered<R> {
+        match result {
+            Ok(pairs) => Recovered::new(Some(pairs), Vec::new()),
+            Err(error) => Recovered::new(None, vec![error]),
+        }
+    }
+}
+
+/// An error recovered from by `ParserState::recover`, along with the error node covering the
+/// skipped input. Inside atomic rules no node is pushed and `atomic` is set instead.
+#[derive(Debug)]
+pub(crate) struct RecoveredError<R> {
+    pub rule: R,
+    pub start: usize,
+    pub end: usize,
+    pub error: Error<R>,
+    pub atomic: bool,
+}
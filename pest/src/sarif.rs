@@ -0,0 +1,321 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Emitter of [SARIF 2.1.0][1] logs, the format read by code scanning dashboards.
+//!
+//! [1]: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use serde::ser::{Serialize, Serializer};
+use serde_json::{json, Value};
+
+use error::{Error, InputLocation, LineColLocation};
+use RuleType;
+
+/// A SARIF log with a single run of a tool, collecting `Error`s across files.
+///
+/// Every `Error` becomes a result whose rule is its [`ErrorVariant`], located in the file set
+/// with [`Error::with_path()`] and with its labels as related locations. Columns are counted in
+/// `char`s, which the log declares with `unicodeCodePoints`. Errors without a path have no
+/// location.
+///
+/// # Examples
+///
+/// ```
+/// # use pest::error::{Error, ErrorVariant};
+/// # use pest::sarif::SarifLog;
+/// # use pest::Position;
+/// # use std::sync::Arc;
+/// # let input: Arc<str> = Arc::from("a = ");
+/// # let pos = Position::new(input, 4).unwrap();
+/// let error: Error<()> = Error::new_from_pos(
+///     ErrorVariant::CustomError {
+///         message: "expected a value".to_owned()
+///     },
+///     pos
+/// )
+/// .with_path("config.toml");
+///
+/// let mut log = SarifLog::new("config-validator").with_version("1.0.0");
+/// log.push(&error);
+///
+/// println!("{}", log.to_json());
+/// ```
+///
+/// [`ErrorVariant`]: ../error/enum.ErrorVariant.html
+/// [`Error::with_path()`]: ../error/struct.Error.html#method.with_path
+#[derive(Clone, Debug, PartialEq)]
+pub struct SarifLog {
+    tool: String,
+    version: Option<String>,
+    rules: Vec<&'static str>,
+    results: Vec<Value>,
+}
+
+impl SarifLog {
+    /// Creates an empty `SarifLog` for the tool named `tool`.
+    pub fn new(tool: &str) -> SarifLog {
+        SarifLog {
+            tool: tool.to_owned(),
+            version: None,
+            rules: Vec::new(),
+            results: Vec::new(),
+        }
+    }
+
+    /// Returns `SarifLog` with the version of the tool.
+    pub fn with_version(mut self, version: &str) -> SarifLog {
+        self.version = Some(version.to_owned());
+
+        self
+    }
+
+    /// Adds `error` as a result.
+    pub fn push<R: RuleType>(&mut self, error: &Error<R>) {
+        let rule = error.variant_name();
+
+        if !self.rules.contains(&rule) {
+            self.rules.push(rule);
+        }
+
+        let mut result = json!({
+            "ruleId": rule,
+            "level": "error",
+            "message": { "text": error.variant.message() },
+        });
+
+        if let Some(path) = error.path() {
+            result["locations"] = json!([{
+                "physicalLocation": physical_location(path, &error.location, &error.line_col),
+            }]);
+            result["relatedLocations"] = error
+                .labels()
+                .iter()
+                .enumerate()
+                .map(|(id, label)| {
+                    json!({
+                        "id": id,
+                        "message": { "text": label.message },
+                        "physicalLocation":
+                            physical_location(path, &label.location, &label.line_col),
+                    })
+                })
+                .collect();
+        }
+
+        if !error.notes().is_empty() || !error.help().is_empty() {
+            result["properties"] = json!({
+                "notes": error.notes(),
+                "help": error.help(),
+            });
+        }
+
+        self.results.push(result);
+    }
+
+    /// Returns the number of results.
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Returns `true` if no results were added.
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    /// Generates a string that stores the log in a pretty-printed SARIF format.
+    pub fn to_json(&self) -> String {
+        ::serde_json::to_string_pretty(self).expect("Failed to pretty-print SarifLog to json.")
+    }
+}
+
+impl<R: RuleType> Extend<Error<R>> for SarifLog {
+    fn extend<I: IntoIterator<Item = Error<R>>>(&mut self, errors: I) {
+        for error in errors {
+            self.push(&error);
+        }
+    }
+}
+
+impl Serialize for SarifLog {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut driver = json!({
+            "name": self.tool,
+            "rules": self
+                .rules
+                .iter()
+                .map(|rule| json!({ "id": rule }))
+                .collect::<Vec<_>>(),
+        });
+
+        if let Some(ref version) = self.version {
+            driver["version"] = json!(version);
+        }
+
+        json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": { "driver": driver },
+                "columnKind": "unicodeCodePoints",
+                "results": self.results,
+            }],
+        })
+        .serialize(serializer)
+    }
+}
+
+fn physical_location(path: &str, location: &InputLocation, line_col: &LineColLocation) -> Value {
+    let (start, end) = match *location {
+        InputLocation::Pos(pos) => (pos, pos),
+        InputLocation::Span(span) => span,
+    };
+    let (start_line_col, end_line_col) = match *line_col {
+        LineColLocation::Pos(pos) => (pos, pos),
+        LineColLocation::Span(start, end) => (start, end),
+    };
+
+    json!({
+        "artifactLocation": { "uri": path },
+        "region": {
+            "startLine": start_line_col.0,
+            "startColumn": start_line_col.1,
+            "endLine": end_line_col.0,
+            "endColumn": end_line_col.1,
+            "byteOffset": start,
+            "byteLength": end - start,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use error::ErrorVariant;
+    use position::Position;
+    use span::Span;
+    use std::sync::Arc;
+
+    fn errors() -> Vec<Error<u32>> {
+        let input: Arc<str> = Arc::from("{\n  a\n)");
+        let close = Span::new(input.clone(), 6, 7).unwrap();
+        let mismatched = Error::new_from_span(
+            ErrorVariant::CustomError {
+                message: "mismatched closing delimiter".to_owned(),
+            },
+            close,
+        )
+        .with_path("a.conf")
+        .with_label(Span::new(input, 0, 1).unwrap(), "unclosed delimiter")
+        .with_help("replace `)` with `}`");
+
+        let input: Arc<str> = Arc::from("x = ");
+        let pos = Position::new(input, 4).unwrap();
+        let parsing = Error::new_from_pos(
+            ErrorVariant::ParsingError {
+                positives: vec![1, 2],
+                negatives: vec![],
+            },
+            pos,
+        )
+        .with_path("b.conf");
+
+        vec![mismatched, parsing]
+    }
+
+    #[test]
+    fn log() {
+        let mut log = SarifLog::new("validator").with_version("1.0.0");
+        log.extend(errors());
+
+        let json: Value = ::serde_json::from_str(&log.to_json()).unwrap();
+        let run = &json["runs"][0];
+
+        assert_eq!(log.len(), 2);
+        assert_eq!(json["version"], "2.1.0");
+        assert_eq!(run["tool"]["driver"]["name"], "validator");
+        assert_eq!(run["tool"]["driver"]["version"], "1.0.0");
+        assert_eq!(
+            run["tool"]["driver"]["rules"],
+            json!([{ "id": "CustomError" }, { "id": "ParsingError" }])
+        );
+        assert_eq!(
+            run["results"][0],
+            json!({
+                "ruleId": "CustomError",
+                "level": "error",
+                "message": { "text": "mismatched closing delimiter" },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": "a.conf" },
+                        "region": {
+                            "startLine": 3,
+                            "startColumn": 1,
+                            "endLine": 3,
+                            "endColumn": 2,
+                            "byteOffset": 6,
+                            "byteLength": 1,
+                        },
+                    },
+                }],
+                "relatedLocations": [{
+                    "id": 0,
+                    "message": { "text": "unclosed delimiter" },
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": "a.conf" },
+                        "region": {
+                            "startLine": 1,
+                            "startColumn": 1,
+                            "endLine": 1,
+                            "endColumn": 2,
+                            "byteOffset": 0,
+                            "byteLength": 1,
+                        },
+                    },
+                }],
+                "properties": { "notes": [], "help": ["replace `)` with `}`"] },
+            })
+        );
+        assert_eq!(
+            run["results"][1]["locations"][0]["physicalLocation"]["region"],
+            json!({
+                "startLine": 1,
+                "startColumn": 5,
+                "endLine": 1,
+                "endColumn": 5,
+                "byteOffset": 4,
+                "byteLength": 0,
+            })
+        );
+    }
+
+    #[test]
+    fn no_path() {
+        let mut log = SarifLog::new("validator");
+        let input: Arc<str> = Arc::from("");
+        log.push(&Error::<u32>::new_from_pos(
+            ErrorVariant::Cancelled,
+            Position::from_start(input),
+        ));
+
+        let json: Value = ::serde_json::from_str(&log.to_json()).unwrap();
+        let result = &json["runs"][0]["results"][0];
+
+        assert_eq!(result["message"]["text"], "parsing was cancelled");
+        assert!(result.get("locations").is_none());
+        assert!(json["runs"][0]["tool"]["driver"].get("version").is_none());
+    }
+}
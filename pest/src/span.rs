@@ -7,11 +7,14 @@
 // option. All files in the project carrying such notice may not be copied,
 // modified, or distributed except according to those terms.
 
+use std::cmp;
 use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::ops::{Bound, Range, RangeBounds};
 use std::str;
 use std::sync::Arc;
 
+use line_index::{LineIndex, SharedLineIndex, Utf16Range};
 use position;
 
 /// A span over a `&str`. It is created from either [two `Position`s] or from a [`Pair`].
@@ -21,6 +24,7 @@ use position;
 #[derive(Clone)]
 pub struct Span {
     input: Arc<str>,
+    lines: SharedLineIndex,
     /// # Safety
     ///
     /// Must be a valid character boundary index into `input`.
@@ -43,8 +47,23 @@ impl Span {
     ///
     /// `input[start..end]` must be a valid subslice; that is, said indexing should not panic.
     pub unsafe fn new_unchecked(input: Arc<str>, start: usize, end: usize) -> Span {
+        Span::new_shared_unchecked(input, SharedLineIndex::default(), start, end)
+    }
+
+    // Like `new_unchecked`, sharing the `LineIndex` of `lines`.
+    pub(crate) unsafe fn new_shared_unchecked(
+        input: Arc<str>,
+        lines: SharedLineIndex,
+        start: usize,
+        end: usize,
+    ) -> Span {
         debug_assert!(input.get(start..end).is_some());
-        Span { input, start, end }
+        Span {
+            input,
+            lines,
+            start,
+            end,
+        }
     }
 
     /// Attempts to create a new span. Will return `None` if `input[start..end]` is an invalid index
@@ -62,7 +81,12 @@ impl Span {
     #[allow(clippy::new_ret_no_self)]
     pub fn new(input: Arc<str>, start: usize, end: usize) -> Option<Span> {
         if input.get(start..end).is_some() {
-            Some(Span { input, start, end })
+            Some(Span {
+                input,
+                lines: SharedLineIndex::default(),
+                start,
+                end,
+            })
         } else {
             None
         }
@@ -123,7 +147,9 @@ impl Span {
     #[inline]
     pub fn start_pos(&self) -> position::Position {
         // Span's start position is always a UTF-8 border.
-        unsafe { position::Position::new_unchecked(self.input.clone(), self.start) }
+        unsafe {
+            position::Position::new_unchecked(self.input.clone(), self.lines.clone(), self.start)
+        }
     }
 
     /// Returns the `Span`'s end `Position`.
@@ -143,7 +169,9 @@ impl Span {
     #[inline]
     pub fn end_pos(&self) -> position::Position {
         // Span's end position is always a UTF-8 border.
-        unsafe { position::Position::new_unchecked(self.input.clone(), self.end) }
+        unsafe {
+            position::Position::new_unchecked(self.input.clone(), self.lines.clone(), self.end)
+        }
     }
 
     /// Splits the `Span` into a pair of `Position`s.
@@ -163,12 +191,108 @@ impl Span {
     #[inline]
     pub fn split(self) -> (position::Position, position::Position) {
         // Span's start and end positions are always a UTF-8 borders.
-        let pos1 = unsafe { position::Position::new_unchecked(self.input.clone(), self.start) };
-        let pos2 = unsafe { position::Position::new_unchecked(self.input, self.end) };
+        let pos1 = unsafe {
+            position::Position::new_unchecked(self.input.clone(), self.lines.clone(), self.start)
+        };
+        let pos2 = unsafe { position::Position::new_unchecked(self.input, self.lines, self.end) };
 
         (pos1, pos2)
     }
 
+    /// Attempts to create a new span from a range of `char` offsets. Will return `None` if the
+    /// input is shorter than `chars.end` or if `chars.start > chars.end`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::Span;
+    /// # use std::sync::Arc;
+    /// let input: Arc<str> = Arc::from("💖ab");
+    /// assert_eq!(Span::from_char_range(input, 1..2).unwrap().as_str(), "a");
+    /// ```
+    pub fn from_char_range(input: Arc<str>, chars: Range<usize>) -> Option<Span> {
+        let lines = SharedLineIndex::default();
+        let index = lines.get(&input);
+        let start = index.pos_of_char_offset(&input, chars.start)?;
+        let end = index.pos_of_char_offset(&input, chars.end)?;
+
+        if start > end {
+            return None;
+        }
+
+        // Both ends are after whole `char`s.
+        Some(unsafe { Span::new_shared_unchecked(input, lines, start, end) })
+    }
+
+    /// Attempts to create a new span from a Language Server Protocol range. Will return `None` if
+    /// either end is not in the input or splits a surrogate pair, or if `range.start` comes after
+    /// `range.end`.
+    pub fn from_utf16_range(input: Arc<str>, range: Utf16Range) -> Option<Span> {
+        let start = position::Position::from_utf16_position(input, range.start)?;
+        let end = start.line_index().pos_of_line_col_utf16(
+            start.input(),
+            range.end.line as usize + 1,
+            range.end.character as usize + 1,
+        )?;
+
+        if start.pos() > end {
+            return None;
+        }
+
+        // Both ends are after whole `char`s.
+        Some(unsafe {
+            Span::new_shared_unchecked(
+                start.input().clone(),
+                start.lines().clone(),
+                start.pos(),
+                end,
+            )
+        })
+    }
+
+    /// Returns the range of `char` offsets covered by the `Span`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::Span;
+    /// # use std::sync::Arc;
+    /// let input: Arc<str> = Arc::from("💖ab");
+    /// assert_eq!(Span::new(input, 4, 5).unwrap().char_range(), 1..2);
+    /// ```
+    #[inline]
+    pub fn char_range(&self) -> Range<usize> {
+        let index = self.line_index();
+
+        index.char_offset(&self.input, self.start)..index.char_offset(&self.input, self.end)
+    }
+
+    /// Returns the Language Server Protocol range covered by the `Span`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::{Span, Utf16Position, Utf16Range};
+    /// # use std::sync::Arc;
+    /// let input: Arc<str> = Arc::from("💖\nab");
+    /// let span = Span::new(input, 0, 6).unwrap();
+    ///
+    /// assert_eq!(
+    ///     span.utf16_range(),
+    ///     Utf16Range {
+    ///         start: Utf16Position { line: 0, character: 0 },
+    ///         end: Utf16Position { line: 1, character: 1 },
+    ///     }
+    /// );
+    /// ```
+    #[inline]
+    pub fn utf16_range(&self) -> Utf16Range {
+        Utf16Range {
+            start: self.start_pos().utf16_position(),
+            end: self.end_pos().utf16_position(),
+        }
+    }
+
     /// Captures a slice from the `&str` defined by the `Span`.
     ///
     /// # Examples
@@ -215,9 +339,223 @@ impl Span {
     pub fn lines(&self) -> Lines {
         Lines {
             span: self,
+            index: self.line_index(),
             pos: self.start,
         }
     }
+
+    /// Returns the byte range covered by the `Span`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::Span;
+    /// # use std::sync::Arc;
+    /// let input: Arc<str> = Arc::from("abc");
+    /// assert_eq!(Span::new(input, 1, 3).unwrap().range(), 1..3);
+    /// ```
+    #[inline]
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Returns `true` if the `Span` is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Returns a sub-span of the `Span` from byte offsets relative to its start. Will return
+    /// `None` if `range` goes past the end of the `Span` or is not on `char` boundaries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::Span;
+    /// # use std::sync::Arc;
+    /// let input: Arc<str> = Arc::from("abcd");
+    /// let span = Span::new(input, 1, 4).unwrap();
+    ///
+    /// assert_eq!(span.get(1..).unwrap().as_str(), "cd");
+    /// assert_eq!(span.get(..4), None);
+    /// ```
+    pub fn get(&self, range: impl RangeBounds<usize>) -> Option<Span> {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.checked_add(1)?,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.checked_add(1)?,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.end - self.start,
+        };
+
+        self.as_str().get(start..end)?;
+
+        Some(Span {
+            input: self.input.clone(),
+            lines: self.lines.clone(),
+            start: self.start + start,
+            end: self.start + end,
+        })
+    }
+
+    /// Returns `true` if `other` lies within the `Span`. Spans over different inputs never
+    /// contain each other.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::Span;
+    /// # use std::sync::Arc;
+    /// let input: Arc<str> = Arc::from("abcd");
+    /// let outer = Span::new(input.clone(), 0, 3).unwrap();
+    ///
+    /// assert!(outer.contains(&Span::new(input.clone(), 1, 3).unwrap()));
+    /// assert!(!outer.contains(&Span::new(input, 2, 4).unwrap()));
+    /// ```
+    #[inline]
+    pub fn contains(&self, other: &Span) -> bool {
+        self.same_input(other) && self.start <= other.start && other.end <= self.end
+    }
+
+    /// Returns `true` if `pos` lies within the `Span`, including at its end.
+    #[inline]
+    pub fn contains_pos(&self, pos: &position::Position) -> bool {
+        Arc::ptr_eq(&self.input, pos.input()) && self.start <= pos.pos() && pos.pos() <= self.end
+    }
+
+    /// Returns `true` if the `Span` and `other` share at least one byte. Spans over different
+    /// inputs never overlap.
+    #[inline]
+    pub fn overlaps(&self, other: &Span) -> bool {
+        self.same_input(other) && self.start < other.end && other.start < self.end
+    }
+
+    /// Returns the smallest `Span` covering both the `Span` and `other`, including anything in
+    /// between. Will return `None` if the spans are over different inputs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::Span;
+    /// # use std::sync::Arc;
+    /// let input: Arc<str> = Arc::from("a + b");
+    /// let lhs = Span::new(input.clone(), 0, 1).unwrap();
+    /// let rhs = Span::new(input, 4, 5).unwrap();
+    ///
+    /// assert_eq!(lhs.merge(&rhs).unwrap().as_str(), "a + b");
+    /// ```
+    pub fn merge(&self, other: &Span) -> Option<Span> {
+        if !self.same_input(other) {
+            return None;
+        }
+
+        Some(Span {
+            input: self.input.clone(),
+            lines: self.lines.clone(),
+            start: cmp::min(self.start, other.start),
+            end: cmp::max(self.end, other.end),
+        })
+    }
+
+    /// Returns the part of the input covered by both the `Span` and `other`. Spans that only touch
+    /// intersect in an empty `Span`. Will return `None` if the spans are over different inputs
+    /// or are apart.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::Span;
+    /// # use std::sync::Arc;
+    /// let input: Arc<str> = Arc::from("abcd");
+    /// let lhs = Span::new(input.clone(), 0, 3).unwrap();
+    /// let rhs = Span::new(input.clone(), 2, 4).unwrap();
+    ///
+    /// assert_eq!(lhs.intersect(&rhs).unwrap().as_str(), "c");
+    /// assert_eq!(lhs.intersect(&Span::new(input, 4, 4).unwrap()), None);
+    /// ```
+    pub fn intersect(&self, other: &Span) -> Option<Span> {
+        let start = cmp::max(self.start, other.start);
+        let end = cmp::min(self.end, other.end);
+
+        if !self.same_input(other) || start > end {
+            return None;
+        }
+
+        Some(Span {
+            input: self.input.clone(),
+            lines: self.lines.clone(),
+            start,
+            end,
+        })
+    }
+
+    /// Returns the `Span` without leading and trailing whitespace, as defined by
+    /// [`char::is_whitespace`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest::Span;
+    /// # use std::sync::Arc;
+    /// let input: Arc<str> = Arc::from(" a b \n");
+    /// let span = Span::new(input.clone(), 0, input.len()).unwrap();
+    ///
+    /// assert_eq!(span.trim().as_str(), "a b");
+    /// assert_eq!(span.trim().range(), 1..4);
+    /// ```
+    ///
+    /// [`char::is_whitespace`]: https://doc.rust-lang.org/std/primitive.char.html#method.is_whitespace
+    #[inline]
+    pub fn trim(&self) -> Span {
+        self.trim_start().trim_end()
+    }
+
+    /// Returns the `Span` without leading whitespace.
+    #[inline]
+    pub fn trim_start(&self) -> Span {
+        let start = self.end - self.as_str().trim_start().len();
+
+        Span {
+            input: self.input.clone(),
+            lines: self.lines.clone(),
+            start,
+            end: self.end,
+        }
+    }
+
+    /// Returns the `Span` without trailing whitespace.
+    #[inline]
+    pub fn trim_end(&self) -> Span {
+        let end = self.start + self.as_str().trim_end().len();
+
+        Span {
+            input: self.input.clone(),
+            lines: self.lines.clone(),
+            start: self.start,
+            end,
+        }
+    }
+
+    // Returns the `LineIndex` of the input, shared with every `Position` and `Span` derived from
+    // this one.
+    #[inline]
+    pub(crate) fn line_index(&self) -> Arc<LineIndex> {
+        self.lines.get(&self.input)
+    }
+
+    #[inline]
+    fn same_input(&self, other: &Span) -> bool {
+        Arc::ptr_eq(&self.input, &other.input)
+    }
+}
+
+impl From<Span> for Range<usize> {
+    fn from(span: Span) -> Range<usize> {
+        span.range()
+    }
 }
 
 impl fmt::Debug for Span {
@@ -232,7 +570,7 @@ impl fmt::Debug for Span {
 
 impl PartialEq for Span {
     fn eq(&self, other: &Span) -> bool {
-        Arc::ptr_eq(&self.input, &other.input) && self.start == other.start && self.end == other.end
+        self.same_input(other) && self.start == other.start && self.end == other.end
     }
 }
 
@@ -253,24 +591,21 @@ impl Hash for Span {
 /// [`Span::lines()`]: struct.Span.html#method.lines
 pub struct Lines<'i> {
     span: &'i Span,
+    index: Arc<LineIndex>,
     pos: usize,
 }
 
 impl<'i> Iterator for Lines<'i> {
     type Item = &'i str;
     fn next(&mut self) -> Option<&'i str> {
-        if self.pos > self.span.end {
+        if self.pos > self.span.end || self.pos == self.span.input.len() {
             return None;
         }
-        let pos = position::Position::new(self.span.input.clone(), self.pos)?;
-        if pos.at_end() {
-            return None;
-        }
-        let line_start = pos.find_line_start();
-        let line_end = pos.find_line_end();
-        let line = &self.span.input[line_start..line_end];
-        self.pos = line_end;
-        Some(line)
+
+        let range = self.index.line_range(self.index.line(self.pos)).unwrap();
+        self.pos = range.end;
+
+        Some(&self.span.input[range])
     }
 }
 
@@ -279,6 +614,7 @@ mod tests {
     use super::*;
     use alloc::borrow::ToOwned;
     use alloc::vec::Vec;
+    use line_index::Utf16Position;
 
     #[test]
     fn split() {
@@ -293,6 +629,115 @@ mod tests {
         assert_eq!(span.split(), (start, end));
     }
 
+    #[test]
+    fn get() {
+        let input: Arc<str> = Arc::from("a💖bc");
+        let span = Span::new(input, 1, 7).unwrap();
+
+        assert_eq!(span.get(..).unwrap(), span);
+        assert_eq!(span.get(4..=5).unwrap().as_str(), "bc");
+        assert_eq!(span.get(4..4).unwrap().range(), 5..5);
+        assert_eq!(span.get(1..), None);
+        assert_eq!(span.get(..7), None);
+    }
+
+    #[test]
+    fn contains_and_overlaps() {
+        let input: Arc<str> = Arc::from("abcdef");
+        let other: Arc<str> = Arc::from("abcdef");
+        let span = Span::new(input.clone(), 1, 4).unwrap();
+
+        assert!(span.contains(&span));
+        assert!(span.contains(&Span::new(input.clone(), 4, 4).unwrap()));
+        assert!(!span.contains(&Span::new(input.clone(), 0, 2).unwrap()));
+        assert!(!span.contains(&Span::new(other.clone(), 1, 4).unwrap()));
+
+        assert!(span.contains_pos(&span.end_pos()));
+        assert!(!span.contains_pos(&position::Position::from_start(input.clone())));
+
+        assert!(span.overlaps(&Span::new(input.clone(), 3, 6).unwrap()));
+        assert!(!span.overlaps(&Span::new(input, 4, 6).unwrap()));
+        assert!(!span.overlaps(&Span::new(other, 1, 4).unwrap()));
+    }
+
+    #[test]
+    fn merge_and_intersect() {
+        let input: Arc<str> = Arc::from("abcdef");
+        let other: Arc<str> = Arc::from("abcdef");
+        let lhs = Span::new(input.clone(), 0, 2).unwrap();
+        let rhs = Span::new(input.clone(), 4, 5).unwrap();
+
+        assert_eq!(lhs.merge(&rhs).unwrap().range(), 0..5);
+        assert_eq!(rhs.merge(&lhs).unwrap().range(), 0..5);
+        assert_eq!(lhs.merge(&Span::new(other.clone(), 4, 5).unwrap()), None);
+
+        let mid = Span::new(input.clone(), 1, 4).unwrap();
+
+        assert_eq!(lhs.intersect(&mid).unwrap().range(), 1..2);
+        assert_eq!(mid.intersect(&rhs).unwrap().range(), 4..4);
+        assert_eq!(lhs.intersect(&rhs), None);
+        assert_eq!(lhs.intersect(&Span::new(other, 0, 2).unwrap()), None);
+    }
+
+    #[test]
+    fn trim() {
+        let input: Arc<str> = Arc::from("( \t a \n)");
+        let span = Span::new(input.clone(), 1, 7).unwrap();
+
+        assert_eq!(span.trim_start().range(), 4..7);
+        assert_eq!(span.trim_end().range(), 1..5);
+        assert_eq!(span.trim().as_str(), "a");
+
+        let blank = Span::new(input, 1, 4).unwrap();
+
+        assert!(blank.trim().is_empty());
+        assert_eq!(Range::from(span), 1..7);
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)] // reversed on purpose
+    fn char_range() {
+        let input: Arc<str> = Arc::from("𝄞ab\n💖");
+        let span = Span::new(input.clone(), 4, 11).unwrap();
+
+        assert_eq!(span.char_range(), 1..5);
+        assert_eq!(Span::from_char_range(input.clone(), 1..5), Some(span));
+        assert_eq!(Span::from_char_range(input.clone(), 2..1), None);
+        assert_eq!(Span::from_char_range(input, 0..6), None);
+    }
+
+    #[test]
+    fn utf16_range() {
+        let input: Arc<str> = Arc::from("𝄞ab\n💖");
+        let span = Span::new(input.clone(), 4, 11).unwrap();
+        let range = span.utf16_range();
+
+        assert_eq!(
+            range,
+            Utf16Range {
+                start: Utf16Position {
+                    line: 0,
+                    character: 2
+                },
+                end: Utf16Position {
+                    line: 1,
+                    character: 2
+                },
+            }
+        );
+        assert_eq!(Span::from_utf16_range(input.clone(), range), Some(span));
+
+        let split = Utf16Range {
+            start: Utf16Position {
+                line: 0,
+                character: 1,
+            },
+            ..range
+        };
+
+        assert_eq!(Span::from_utf16_range(input, split), None);
+    }
+
     #[test]
     fn lines_mid() {
         let input = Arc::from("abc\ndef\nghi");
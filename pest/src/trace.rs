@@ -0,0 +1,319 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Hooks to follow the rule calls made while parsing, enabled by the `trace` feature.
+//!
+//! A [`Tracer`] installed with [`ParserConfig::tracer`] is called on every rule entry, success
+//! and failure. Without the `trace` feature, the hooks are compiled out of `ParserState`
+//! entirely.
+//!
+//! [`Tracer`]: trait.Tracer.html
+//! [`ParserConfig::tracer`]: ../struct.ParserConfig.html#method.tracer
+
+use alloc::vec::Vec;
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use RuleType;
+
+/// Receives the calls to non-silent rules made by a [`ParserState`].
+///
+/// `depth` is the number of rules the call is nested in. Every method does nothing by default.
+///
+/// [`ParserState`]: ../struct.ParserState.html
+pub trait Tracer<R> {
+    /// Called when `rule` is entered at `pos`.
+    fn enter(&mut self, rule: R, pos: usize, depth: usize) {
+        let _ = (rule, pos, depth);
+    }
+
+    /// Called when `rule`, entered at `start`, succeeds at `end`.
+    fn success(&mut self, rule: R, start: usize, end: usize, depth: usize) {
+        let _ = (rule, start, end, depth);
+    }
+
+    /// Called when `rule`, entered at `pos`, fails.
+    fn failure(&mut self, rule: R, pos: usize, depth: usize) {
+        let _ = (rule, pos, depth);
+    }
+}
+
+/// A rule call received by an [`EventLog`].
+///
+/// [`EventLog`]: struct.EventLog.html
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TraceEvent<R> {
+    /// `rule` was entered at `pos`
+    Enter { rule: R, pos: usize, depth: usize },
+    /// `rule`, entered at `start`, succeeded at `end`
+    Success {
+        rule: R,
+        start: usize,
+        end: usize,
+        depth: usize,
+    },
+    /// `rule`, entered at `pos`, failed
+    Failure { rule: R, pos: usize, depth: usize },
+}
+
+/// A `Tracer` which collects every rule call as a [`TraceEvent`].
+///
+/// [`TraceEvent`]: enum.TraceEvent.html
+///
+/// # Examples
+///
+/// ```
+/// # use std::sync::{Arc, Mutex};
+/// # use pest::ParserConfig;
+/// # use pest::trace::{EventLog, TraceEvent};
+/// # #[allow(non_camel_case_types)]
+/// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+/// enum Rule {
+///     a
+/// }
+///
+/// let log = Arc::new(Mutex::new(EventLog::new()));
+/// let config = ParserConfig::new().tracer(log.clone());
+/// let input: Arc<str> = Arc::from("b");
+///
+/// pest::state_with_config(input, config, |state| {
+///     state.rule(Rule::a, |s| s.match_string("a"))
+/// }).unwrap_err();
+///
+/// assert_eq!(
+///     log.lock().unwrap().events(),
+///     &[
+///         TraceEvent::Enter { rule: Rule::a, pos: 0, depth: 0 },
+///         TraceEvent::Failure { rule: Rule::a, pos: 0, depth: 0 },
+///     ]
+/// );
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct EventLog<R> {
+    events: Vec<TraceEvent<R>>,
+}
+
+impl<R> EventLog<R> {
+    /// Creates an empty `EventLog`.
+    pub fn new() -> EventLog<R> {
+        EventLog { events: Vec::new() }
+    }
+
+    /// Returns the collected events, in the order they happened.
+    pub fn events(&self) -> &[TraceEvent<R>] {
+        &self.events
+    }
+
+    /// Converts the `EventLog` into the collected events.
+    pub fn into_events(self) -> Vec<TraceEvent<R>> {
+        self.events
+    }
+}
+
+impl<R: RuleType> Tracer<R> for EventLog<R> {
+    fn enter(&mut self, rule: R, pos: usize, depth: usize) {
+        self.events.push(TraceEvent::Enter { rule, pos, depth });
+    }
+
+    fn success(&mut self, rule: R, start: usize, end: usize, depth: usize) {
+        self.events.push(TraceEvent::Success {
+            rule,
+            start,
+            end,
+            depth,
+        });
+    }
+
+    fn failure(&mut self, rule: R, pos: usize, depth: usize) {
+        self.events.push(TraceEvent::Failure { rule, pos, depth });
+    }
+}
+
+/// A `Tracer` which writes every rule call on its own line, indented by its depth.
+///
+/// Errors returned by the writer are ignored.
+///
+/// # Examples
+///
+/// ```
+/// # use std::sync::{Arc, Mutex};
+/// # use pest::ParserConfig;
+/// # use pest::trace::PrintTracer;
+/// # #[allow(non_camel_case_types)]
+/// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+/// enum Rule {
+///     a,
+///     b
+/// }
+///
+/// let tracer = Arc::new(Mutex::new(PrintTracer::new(vec![])));
+/// let config = ParserConfig::new().tracer(tracer.clone());
+/// let input: Arc<str> = Arc::from("b");
+///
+/// pest::state_with_config(input, config, |state| {
+///     state.rule(Rule::a, |s| s.rule(Rule::b, |s| s.match_string("b")))
+/// }).unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(tracer.lock().unwrap().get_ref().clone()).unwrap(),
+///     "a at 0\n  b at 0\n  b matched 0..1\na matched 0..1\n"
+/// );
+/// ```
+#[derive(Debug)]
+pub struct PrintTracer<W> {
+    out: W,
+}
+
+impl<W: Write> PrintTracer<W> {
+    /// Creates a `PrintTracer` writing to `out`.
+    pub fn new(out: W) -> PrintTracer<W> {
+        PrintTracer { out }
+    }
+
+    /// Returns a reference to the writer.
+    pub fn get_ref(&self) -> &W {
+        &self.out
+    }
+
+    /// Converts the `PrintTracer` into its writer.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl PrintTracer<io::Stderr> {
+    /// Creates a `PrintTracer` writing to the standard error.
+    pub fn stderr() -> PrintTracer<io::Stderr> {
+        PrintTracer::new(io::stderr())
+    }
+}
+
+impl<R: RuleType, W: Write> Tracer<R> for PrintTracer<W> {
+    fn enter(&mut self, rule: R, pos: usize, depth: usize) {
+        let _ = writeln!(self.out, "{:3$}{:?} at {}", "", rule, pos, depth * 2);
+    }
+
+    fn success(&mut self, rule: R, start: usize, end: usize, depth: usize) {
+        let _ = writeln!(
+            self.out,
+            "{:4$}{:?} matched {}..{}",
+            "",
+            rule,
+            start,
+            end,
+            depth * 2
+        );
+    }
+
+    fn failure(&mut self, rule: R, pos: usize, depth: usize) {
+        let _ = writeln!(self.out, "{:3$}{:?} failed at {}", "", rule, pos, depth * 2);
+    }
+}
+
+/// A `Tracer` shared between a `ParserConfig` and its owner.
+pub(crate) struct SharedTracer<R>(pub Arc<Mutex<dyn Tracer<R> + Send>>);
+
+impl<R> Clone for SharedTracer<R> {
+    fn clone(&self) -> SharedTracer<R> {
+        SharedTracer(self.0.clone())
+    }
+}
+
+impl<R> fmt::Debug for SharedTracer<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("SharedTracer")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use alloc::vec;
+
+    use parser_config::ParserConfig;
+
+    #[allow(non_camel_case_types)]
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    enum Rule {
+        a,
+        b,
+        c,
+    }
+
+    fn parse(config: ParserConfig<Rule>) {
+        let input: Arc<str> = Arc::from("ab");
+
+        ::state_with_config(input, config, |state| {
+            state.rule(Rule::a, |s| {
+                s.match_string("a").and_then(|s| {
+                    s.rule(Rule::b, |s| s.match_string("c"))
+                        .or_else(|s| s.rule(Rule::c, |s| s.match_string("b")))
+                })
+            })
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn event_log() {
+        let log = Arc::new(Mutex::new(EventLog::new()));
+        parse(ParserConfig::new().tracer(log.clone()));
+
+        assert_eq!(
+            log.lock().unwrap().events(),
+            &[
+                TraceEvent::Enter {
+                    rule: Rule::a,
+                    pos: 0,
+                    depth: 0
+                },
+                TraceEvent::Enter {
+                    rule: Rule::b,
+                    pos: 1,
+                    depth: 1
+                },
+                TraceEvent::Failure {
+                    rule: Rule::b,
+                    pos: 1,
+                    depth: 1
+                },
+                TraceEvent::Enter {
+                    rule: Rule::c,
+                    pos: 1,
+                    depth: 1
+                },
+                TraceEvent::Success {
+                    rule: Rule::c,
+                    start: 1,
+                    end: 2,
+                    depth: 1
+                },
+                TraceEvent::Success {
+                    rule: Rule::a,
+                    start: 0,
+                    end: 2,
+                    depth: 0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn print_tracer() {
+        let tracer = Arc::new(Mutex::new(PrintTracer::new(vec![])));
+        parse(ParserConfig::new().tracer(tracer.clone()));
+
+        assert_eq!(
+            String::from_utf8(tracer.lock().unwrap().get_ref().clone()).unwrap(),
+            "a at 0\n  b at 1\n  b failed at 1\n  c at 1\n  c matched 1..2\na matched 0..2\n"
+        );
+    }
+}
@@ -0,0 +1,488 @@
+// pest. The Elegant Parser
+// Copyright (c) 2018 Dragoș Tiselice
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! An owned syntax tree which can be walked in every direction.
+
+use alloc::vec::Vec;
+use std::ops::Range;
+use std::sync::Arc;
+
+use iterators::Pairs;
+use line_index::SharedLineIndex;
+use span::Span;
+use token::Token;
+use RuleType;
+
+/// The identifier of a node of a [`SyntaxTree`]. Nodes are numbered from `0` in the order they
+/// start in, so that a node always comes before its descendants.
+///
+/// [`SyntaxTree`]: struct.SyntaxTree.html
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    /// Returns the index of the node, which is lower than the [`len`] of its tree. It can be used
+    /// to store data about nodes in a `Vec`.
+    ///
+    /// [`len`]: struct.SyntaxTree.html#method.len
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Node<R> {
+    rule: R,
+    start: usize,
+    end: usize,
+    depth: usize,
+    parent: Option<NodeId>,
+    prev_sibling: Option<NodeId>,
+    next_sibling: Option<NodeId>,
+    first_child: Option<NodeId>,
+    last_child: Option<NodeId>,
+    // Index of the first node after the descendants of this one.
+    subtree_end: usize,
+}
+
+/// A tree of the pairs matched by a parser, stored in an arena. Unlike a [`Pair`], which can only
+/// be walked downward, every node of a `SyntaxTree` knows its parent and its siblings.
+///
+/// Nodes are referred to by [`NodeId`]s, which stay valid for as long as the tree lives. Passing a
+/// `NodeId` from another tree returns unspecified results or panics.
+///
+/// [`Pair`]: ../iterators/struct.Pair.html
+/// [`NodeId`]: struct.NodeId.html
+#[derive(Clone, Debug)]
+pub struct SyntaxTree<R> {
+    input: Arc<str>,
+    lines: SharedLineIndex,
+    nodes: Vec<Node<R>>,
+}
+
+impl<R: RuleType> SyntaxTree<R> {
+    /// Builds a `SyntaxTree` from `pairs`, whose top-level pairs become its roots.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest;
+    /// # use pest::tree::SyntaxTree;
+    /// # use std::sync::Arc;
+    /// # #[allow(non_camel_case_types)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// enum Rule {
+    ///     a,
+    ///     b
+    /// }
+    ///
+    /// let input: Arc<str> = Arc::from("ab");
+    /// let pairs = pest::state(input, |state| {
+    ///     state.rule(Rule::a, |s| {
+    ///         s.match_string("a").and_then(|s| s.rule(Rule::b, |s| s.match_string("b")))
+    ///     })
+    /// }).unwrap();
+    ///
+    /// let tree = SyntaxTree::new(pairs);
+    /// let a = tree.roots().next().unwrap();
+    /// let b = tree.first_child(a).unwrap();
+    ///
+    /// assert_eq!(tree.rule(b), Rule::b);
+    /// assert_eq!(tree.parent(b), Some(a));
+    /// assert_eq!(tree.depth(b), 1);
+    /// ```
+    pub fn new(pairs: Pairs<R>) -> SyntaxTree<R> {
+        let input = pairs.input().clone();
+        let lines = pairs.lines().clone();
+        let mut nodes: Vec<Node<R>> = Vec::new();
+        let mut open: Vec<usize> = Vec::new();
+        let mut last_root = None;
+
+        for token in pairs.tokens() {
+            match token {
+                Token::Start { rule, pos } => {
+                    let id = NodeId(nodes.len());
+                    let parent = open.last().map(|&index| NodeId(index));
+                    let prev_sibling = match parent {
+                        Some(parent) => nodes[parent.0].last_child,
+                        None => last_root,
+                    };
+
+                    if let Some(prev_sibling) = prev_sibling {
+                        nodes[prev_sibling.0].next_sibling = Some(id);
+                    }
+
+                    match parent {
+                        Some(parent) => {
+                            let parent = &mut nodes[parent.0];
+                            parent.first_child = parent.first_child.or(Some(id));
+                            parent.last_child = Some(id);
+                        }
+                        None => last_root = Some(id),
+                    }
+
+                    nodes.push(Node {
+                        rule,
+                        start: pos.pos(),
+                        end: pos.pos(),
+                        depth: open.len(),
+                        parent,
+                        prev_sibling,
+                        next_sibling: None,
+                        first_child: None,
+                        last_child: None,
+                        subtree_end: id.0 + 1,
+                    });
+                    open.push(id.0);
+                }
+                Token::End { pos, .. } => {
+                    let index = open.pop().expect("unbalanced tokens");
+                    let subtree_end = nodes.len();
+                    let node = &mut nodes[index];
+
+                    node.end = pos.pos();
+                    node.subtree_end = subtree_end;
+                }
+            }
+        }
+
+        SyntaxTree {
+            input,
+            lines,
+            nodes,
+        }
+    }
+
+    /// Returns the input the tree was parsed from.
+    pub fn input(&self) -> &Arc<str> {
+        &self.input
+    }
+
+    /// Returns the number of nodes of the tree.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns whether the tree has no node.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns an iterator over the roots of the tree, in order.
+    pub fn roots(&self) -> Siblings<'_, R> {
+        Siblings {
+            tree: self,
+            next: if self.nodes.is_empty() {
+                None
+            } else {
+                Some(NodeId(0))
+            },
+        }
+    }
+
+    /// Returns an iterator over every node of the tree, each node coming before its descendants.
+    pub fn nodes(&self) -> Descendants {
+        Descendants {
+            range: 0..self.nodes.len(),
+        }
+    }
+
+    /// Returns the rule of the node `id`.
+    pub fn rule(&self, id: NodeId) -> R {
+        self.nodes[id.0].rule
+    }
+
+    /// Returns the `Span` of the node `id`.
+    pub fn span(&self, id: NodeId) -> Span {
+        let node = &self.nodes[id.0];
+
+        // Generated positions always come from Positions and are UTF-8 borders.
+        unsafe {
+            Span::new_shared_unchecked(self.input.clone(), self.lines.clone(), node.start, node.end)
+        }
+    }
+
+    /// Returns the input matched by the node `id`.
+    pub fn as_str(&self, id: NodeId) -> &str {
+        let node = &self.nodes[id.0];
+
+        &self.input[node.start..node.end]
+    }
+
+    /// Returns the number of ancestors of the node `id`, which is `0` for roots.
+    pub fn depth(&self, id: NodeId) -> usize {
+        self.nodes[id.0].depth
+    }
+
+    /// Returns the parent of the node `id`, or `None` for roots.
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id.0].parent
+    }
+
+    /// Returns the sibling right before the node `id`, if any. Roots are siblings of each other.
+    pub fn prev_sibling(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id.0].prev_sibling
+    }
+
+    /// Returns the sibling right after the node `id`, if any. Roots are siblings of each other.
+    pub fn next_sibling(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id.0].next_sibling
+    }
+
+    /// Returns the first child of the node `id`, if any.
+    pub fn first_child(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id.0].first_child
+    }
+
+    /// Returns the last child of the node `id`, if any.
+    pub fn last_child(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id.0].last_child
+    }
+
+    /// Returns an iterator over the children of the node `id`, in order.
+    pub fn children(&self, id: NodeId) -> Siblings<'_, R> {
+        Siblings {
+            tree: self,
+            next: self.first_child(id),
+        }
+    }
+
+    /// Returns an iterator over the ancestors of the node `id`, from its parent to its root.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pest;
+    /// # use pest::tree::SyntaxTree;
+    /// # use std::sync::Arc;
+    /// # #[allow(non_camel_case_types)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// enum Rule {
+    ///     scope,
+    ///     ident
+    /// }
+    ///
+    /// let input: Arc<str> = Arc::from("{{a}}");
+    /// let pairs = pest::state(input, |state| {
+    ///     // generating Token pairs with Rule::scope and Rule::ident ...
+    /// #     state.rule(Rule::scope, |s| {
+    /// #         s.skip(1)
+    /// #             .and_then(|s| s.rule(Rule::scope, |s| {
+    /// #                 s.skip(1)
+    /// #                     .and_then(|s| s.rule(Rule::ident, |s| s.skip(1)))
+    /// #                     .and_then(|s| s.skip(1))
+    /// #             }))
+    /// #             .and_then(|s| s.skip(1))
+    /// #     })
+    /// }).unwrap();
+    ///
+    /// let tree = SyntaxTree::new(pairs);
+    /// let ident = tree.nodes().find(|&id| tree.rule(id) == Rule::ident).unwrap();
+    /// let scope = tree.ancestors(ident).find(|&id| tree.rule(id) == Rule::scope).unwrap();
+    ///
+    /// assert_eq!(tree.as_str(scope), "{a}");
+    /// ```
+    pub fn ancestors(&self, id: NodeId) -> Ancestors<'_, R> {
+        Ancestors {
+            tree: self,
+            next: self.parent(id),
+        }
+    }
+
+    /// Returns an iterator over the descendants of the node `id`, each node coming before its
+    /// own descendants.
+    pub fn descendants(&self, id: NodeId) -> Descendants {
+        Descendants {
+            range: id.0 + 1..self.nodes[id.0].subtree_end,
+        }
+    }
+}
+
+/// An iterator over sibling nodes of a [`SyntaxTree`].
+///
+/// [`SyntaxTree`]: struct.SyntaxTree.html
+#[derive(Clone, Debug)]
+pub struct Siblings<'t, R> {
+    tree: &'t SyntaxTree<R>,
+    next: Option<NodeId>,
+}
+
+impl<'t, R: RuleType> Iterator for Siblings<'t, R> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        let id = self.next?;
+        self.next = self.tree.next_sibling(id);
+
+        Some(id)
+    }
+}
+
+/// An iterator over the ancestors of a node of a [`SyntaxTree`].
+///
+/// [`SyntaxTree`]: struct.SyntaxTree.html
+#[derive(Clone, Debug)]
+pub struct Ancestors<'t, R> {
+    tree: &'t SyntaxTree<R>,
+    next: Option<NodeId>,
+}
+
+impl<'t, R: RuleType> Iterator for Ancestors<'t, R> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        let id = self.next?;
+        self.next = self.tree.parent(id);
+
+        Some(id)
+    }
+}
+
+/// An iterator over nodes of a [`SyntaxTree`], each node coming before its descendants.
+///
+/// [`SyntaxTree`]: struct.SyntaxTree.html
+#[derive(Clone, Debug)]
+pub struct Descendants {
+    range: Range<usize>,
+}
+
+impl Iterator for Descendants {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        self.range.next().map(NodeId)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Descendants {
+    fn next_back(&mut self) -> Option<NodeId> {
+        self.range.next_back().map(NodeId)
+    }
+}
+
+impl ExactSizeIterator for Descendants {}
+
+#[cfg(test)]
+mod tests {
+    use super::super::macros::tests::*;
+    use super::super::Parser;
+    use super::*;
+    use alloc::vec;
+
+    fn tree() -> SyntaxTree<Rule> {
+        SyntaxTree::new(AbcParser::parse(Rule::a, Arc::from("abcde")).unwrap())
+    }
+
+    #[test]
+    fn navigation() {
+        let tree = tree();
+        let (a, b, c) = (NodeId(0), NodeId(1), NodeId(2));
+
+        assert_eq!(tree.len(), 3);
+        assert_eq!(tree.roots().collect::<Vec<_>>(), vec![a, c]);
+        assert_eq!(
+            tree.nodes().map(|id| tree.rule(id)).collect::<Vec<_>>(),
+            vec![Rule::a, Rule::b, Rule::c]
+        );
+
+        assert_eq!(tree.parent(b), Some(a));
+        assert_eq!(tree.parent(a), None);
+        assert_eq!(tree.first_child(a), Some(b));
+        assert_eq!(tree.last_child(a), Some(b));
+        assert_eq!(tree.first_child(b), None);
+        assert_eq!(tree.next_sibling(a), Some(c));
+        assert_eq!(tree.prev_sibling(c), Some(a));
+        assert_eq!(tree.prev_sibling(b), None);
+        assert_eq!(tree.next_sibling(b), None);
+        assert_eq!(tree.depth(a), 0);
+        assert_eq!(tree.depth(b), 1);
+        assert_eq!(tree.ancestors(b).collect::<Vec<_>>(), vec![a]);
+        assert_eq!(tree.descendants(a).collect::<Vec<_>>(), vec![b]);
+        assert_eq!(tree.descendants(c).count(), 0);
+    }
+
+    #[test]
+    fn spans() {
+        let tree = tree();
+
+        assert_eq!(tree.as_str(NodeId(0)), "abc");
+        assert_eq!(tree.span(NodeId(1)).start(), 1);
+        assert_eq!(tree.span(NodeId(1)).end(), 2);
+        assert_eq!(tree.span(NodeId(2)).as_str(), "e");
+    }
+
+    #[test]
+    fn nested() {
+        let input: Arc<str> = Arc::from("((a)(b))c");
+        let pairs = ::state(input, |state| {
+            fn list(state: Box<::ParserState<Rule>>) -> ::ParseResult<Box<::ParserState<Rule>>> {
+                state.rule(Rule::a, |s| {
+                    s.match_string("(")
+                        .and_then(|s| {
+                            s.repeat(|s| {
+                                list(s).or_else(|s| {
+                                    s.rule(Rule::b, |s| s.match_char_by(|c| c.is_ascii_lowercase()))
+                                })
+                            })
+                        })
+                        .and_then(|s| s.match_string(")"))
+                })
+            }
+
+            list(state).and_then(|s| s.rule(Rule::c, |s| s.match_string("c")))
+        })
+        .unwrap();
+        let tree = SyntaxTree::new(pairs);
+
+        let rules: Vec<_> = tree
+            .nodes()
+            .map(|id| (tree.rule(id), tree.as_str(id), tree.depth(id)))
+            .collect();
+        assert_eq!(
+            rules,
+            vec![
+                (Rule::a, "((a)(b))", 0),
+                (Rule::a, "(a)", 1),
+                (Rule::b, "a", 2),
+                (Rule::a, "(b)", 1),
+                (Rule::b, "b", 2),
+                (Rule::c, "c", 0),
+            ]
+        );
+
+        let root = NodeId(0);
+        let children: Vec<_> = tree.children(root).collect();
+        assert_eq!(children, vec![NodeId(1), NodeId(3)]);
+        assert_eq!(tree.last_child(root), Some(NodeId(3)));
+        assert_eq!(tree.prev_sibling(NodeId(3)), Some(NodeId(1)));
+        assert_eq!(
+            tree.ancestors(NodeId(4)).collect::<Vec<_>>(),
+            vec![NodeId(3), root]
+        );
+        assert_eq!(tree.descendants(root).len(), 4);
+        assert_eq!(
+            tree.descendants(NodeId(1)).collect::<Vec<_>>(),
+            vec![NodeId(2)]
+        );
+        assert_eq!(tree.next_sibling(root), Some(NodeId(5)));
+    }
+
+    #[test]
+    fn empty() {
+        let tree = SyntaxTree::new(::state::<Rule, _>(Arc::from("a"), Ok).unwrap());
+
+        assert!(tree.is_empty());
+        assert_eq!(tree.roots().count(), 0);
+        assert_eq!(&**tree.input(), "a");
+    }
+}